@@ -23,6 +23,8 @@ use aptos_types::{
 use move_deps::move_core_types::vm_status::{StatusCode, VMStatus};
 use rayon::prelude::*;
 
+pub use aptos_parallel_executor::SchedulerStats;
+
 impl PTransaction for PreprocessedTransaction {
     type Key = StateKey;
     type Value = WriteOp;
@@ -66,6 +68,25 @@ impl ParallelAptosVM {
         state_view: &S,
         concurrency_level: usize,
     ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        Self::execute_block_with_stats(transactions, state_view, concurrency_level)
+            .map(|(output, err, _stats)| (output, err))
+    }
+
+    /// Like `execute_block`, but also returns the counts of the work done by the parallel
+    /// scheduler. If the block falls back to sequential execution, these describe the attempt
+    /// to execute it in parallel.
+    pub fn execute_block_with_stats<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+    ) -> Result<
+        (
+            Vec<TransactionOutput>,
+            Option<Error<VMStatus>>,
+            SchedulerStats,
+        ),
+        VMStatus,
+    > {
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
         // sequentially while executing the transactions.
@@ -74,17 +95,19 @@ impl ParallelAptosVM {
             .map(|txn| preprocess_transaction::<AptosVM>(txn.clone()))
             .collect();
 
-        match ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
-            concurrency_level,
-        )
-        .execute_transactions_parallel(state_view, signature_verified_block)
-        {
+        let (result, stats) = ParallelTransactionExecutor::<
+            PreprocessedTransaction,
+            AptosVMWrapper<S>,
+        >::new(concurrency_level)
+        .execute_transactions_parallel_with_stats(state_view, signature_verified_block);
+        match result {
             Ok(results) => Ok((
                 results
                     .into_iter()
                     .map(AptosTransactionOutput::into)
                     .collect(),
                 None,
+                stats,
             )),
             Err(err @ Error::ModulePathReadWrite) => {
                 let output = AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?;
@@ -94,6 +117,7 @@ impl ParallelAptosVM {
                         .map(|(_vm_status, txn_output)| txn_output)
                         .collect(),
                     Some(err),
+                    stats,
                 ))
             }
             Err(Error::InvariantViolation) => Err(VMStatus::Error(
@@ -0,0 +1,145 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Custom derives for Rust mirrors of Move resources used in e2e tests.
+
+#![forbid(unsafe_code)]
+
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, DeriveInput, Error, Lit, Meta, NestedMeta,
+    Result,
+};
+
+/// Derives `e2e_move_tests::MoveResource` for a Rust mirror of a Move resource.
+///
+/// The Move resource is given by `#[move_resource(address = "0x1", module = "stake")]`. The
+/// struct name defaults to the name of the Rust struct, and can be overridden with
+/// `name = "StakePool"`.
+///
+/// Type parameters of the Rust struct must be mirrors of Move structs themselves, and are passed
+/// as the type arguments of the Move struct, e.g. a `CoinStore<C>` mirror reads
+/// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>` as `CoinStore<AptosCoin>`. Const generics
+/// are not supported.
+#[proc_macro_derive(MoveResource, attributes(move_resource))]
+pub fn derive_move_resource(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_move_resource_impl(input) {
+        Ok(token_stream) => proc_macro::TokenStream::from(token_stream),
+        Err(err) => proc_macro::TokenStream::from(err.to_compile_error()),
+    }
+}
+
+fn derive_move_resource_impl(input: DeriveInput) -> Result<TokenStream> {
+    let input_span = input.span();
+    let name = input.ident;
+    let attrs = parse_attrs(&input.attrs)?;
+    let address = attrs
+        .address
+        .ok_or_else(|| Error::new(input_span, "missing `address` in #[move_resource(..)]"))?;
+    let module = attrs
+        .module
+        .ok_or_else(|| Error::new(input_span, "missing `module` in #[move_resource(..)]"))?;
+    let struct_name = attrs.name.unwrap_or_else(|| name.to_string());
+    if let Some(param) = input.generics.const_params().next() {
+        return Err(Error::new(
+            param.span(),
+            "MoveResource cannot be derived for structs with const generics",
+        ));
+    }
+
+    let mut generics = input.generics.clone();
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    for param in &type_params {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#param: e2e_move_tests::resource::MoveResource));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let type_params_fn = if type_params.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn type_params() -> Vec<e2e_move_tests::move_deps::move_core_types::language_storage::TypeTag> {
+                vec![#(e2e_move_tests::move_deps::move_core_types::language_storage::TypeTag::Struct(
+                    <#type_params as e2e_move_tests::resource::MoveResource>::struct_tag()
+                )),*]
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics e2e_move_tests::resource::MoveResource for #name #ty_generics
+            #where_clause
+        {
+            const ADDRESS: &'static str = #address;
+            const MODULE_NAME: &'static str = #module;
+            const STRUCT_NAME: &'static str = #struct_name;
+
+            #type_params_fn
+        }
+    })
+}
+
+#[derive(Default)]
+struct MoveResourceAttrs {
+    address: Option<String>,
+    module: Option<String>,
+    name: Option<String>,
+}
+
+/// Parses the `#[move_resource(..)]` attributes.
+fn parse_attrs(attrs: &[Attribute]) -> Result<MoveResourceAttrs> {
+    let mut result = MoveResourceAttrs::default();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("move_resource"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            _ => {
+                return Err(Error::new(
+                    attr.span(),
+                    "must be of the form #[move_resource(address = \"..\", module = \"..\")]",
+                ))
+            }
+        };
+        for nested in list.nested {
+            let (key, value) = match &nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) => match (&pair.path, &pair.lit) {
+                    (path, Lit::Str(value)) if path.get_ident().is_some() => {
+                        (path.get_ident().unwrap().to_string(), value.value())
+                    }
+                    _ => return Err(Error::new(nested.span(), "expected `key = \"value\"`")),
+                },
+                _ => return Err(Error::new(nested.span(), "expected `key = \"value\"`")),
+            };
+            let slot = match key.as_str() {
+                "address" => &mut result.address,
+                "module" => &mut result.module,
+                "name" => &mut result.name,
+                _ => {
+                    return Err(Error::new(
+                        nested.span(),
+                        format!("unknown #[move_resource] key `{}`", key),
+                    ))
+                }
+            };
+            if slot.replace(value).is_some() {
+                return Err(Error::new(
+                    nested.span(),
+                    format!("`{}` specified multiple times", key),
+                ));
+            }
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,50 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use cached_framework_packages::aptos_stdlib;
+use criterion::{criterion_group, criterion_main, Criterion};
+use e2e_move_tests::{add_stake, assert_success, initialize_staking, MoveHarness};
+
+//
+// Entry function benchmarks
+//
+
+fn stake_add_stake(c: &mut Criterion) {
+    c.bench_function("stake::add_stake", |b| {
+        let mut harness = MoveHarness::new_for_benchmarking();
+        let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+        let owner_address = *owner.address();
+        // The pool is not in the validator set, so the voting power increase limit doesn't apply.
+        assert_success!(initialize_staking(
+            &mut harness,
+            &owner,
+            50_000_000,
+            owner_address,
+            owner_address
+        ));
+        b.iter(|| assert_success!(add_stake(&mut harness, &owner, 1_000)))
+    });
+}
+
+fn coin_transfer(c: &mut Criterion) {
+    c.bench_function("coin::transfer", |b| {
+        let mut harness = MoveHarness::new_for_benchmarking();
+        let sender = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+        let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+        b.iter(|| {
+            assert_success!(harness.run_transaction_payload(
+                &sender,
+                aptos_stdlib::aptos_coin_transfer(*receiver.address(), 1)
+            ))
+        })
+    });
+}
+
+criterion_group!(
+    name = harness_benches;
+    config = Criterion::default().sample_size(10);
+    targets = stake_add_stake, coin_transfer
+);
+
+criterion_main!(harness_benches);
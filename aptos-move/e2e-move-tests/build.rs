@@ -0,0 +1,41 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use framework::release::{ReleaseOptions, CORE_FRAMEWORK_RELEASE_SUFFIX, TOKEN_RELEASE_SUFFIX};
+use std::path::PathBuf;
+
+/// Generates entry function builders for the framework sources in the work tree, so tests can
+/// call new entry functions before they are part of a framework release.
+fn main() {
+    println!("cargo:rerun-if-changed=../framework/aptos-framework/sources");
+    println!("cargo:rerun-if-changed=../framework/aptos-stdlib/sources");
+    println!("cargo:rerun-if-changed=../framework/move-stdlib/sources");
+    println!("cargo:rerun-if-changed=../framework/aptos-token/sources");
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("head");
+    for (package, suffix) in [
+        ("aptos-framework", CORE_FRAMEWORK_RELEASE_SUFFIX),
+        ("aptos-token", TOKEN_RELEASE_SUFFIX),
+    ] {
+        let output = out_dir.join(suffix);
+        ReleaseOptions {
+            no_check_layout_compatibility: true,
+            no_build_docs: true,
+            with_diagram: false,
+            no_script_builder: false,
+            no_script_abis: false,
+            no_errmap: true,
+            package: PathBuf::from(package),
+            output: output.clone(),
+        }
+        .create_release();
+
+        // The builders are included into a module, which doesn't allow inner attributes.
+        let builder = std::fs::read_to_string(output.join("aptos_sdk_builder.rs")).unwrap();
+        let builder: String = builder
+            .lines()
+            .filter(|line| !line.starts_with("#!["))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        std::fs::write(out_dir.join(format!("{}_sdk_builder.rs", suffix)), builder).unwrap();
+    }
+}
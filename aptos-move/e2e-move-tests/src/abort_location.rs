@@ -0,0 +1,134 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_transaction_builder::error_explain::get_explanation;
+use aptos_types::{
+    transaction::{ExecutionStatus, TransactionStatus},
+    vm_status::{AbortLocation, StatusCode},
+};
+use move_deps::move_core_types::language_storage::ModuleId;
+use std::{fmt, path::PathBuf};
+
+/// The first use of the error constant an abort code resolves to, as a Move source location.
+///
+/// Aborts only carry the module and the abort code, not the code offset, so this is not a source
+/// map lookup: the code is resolved to its error constant via the framework error map, and the
+/// location is the first line of the module's source, outside the constant's declaration and
+/// comments, which uses the constant. For constants asserted in several places, the abort may
+/// have been raised by a later use.
+#[derive(Debug, Clone)]
+pub struct AbortConstantUse {
+    pub file: PathBuf,
+    pub line: usize,
+    pub error_name: String,
+    pub error_description: String,
+}
+
+impl fmt::Display for AbortConstantUse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} first used at {}:{} ({})",
+            self.error_name,
+            self.file.display(),
+            self.line,
+            self.error_description
+        )
+    }
+}
+
+/// Finds the first use of the error constant of an abort raised by a framework module, see
+/// `AbortConstantUse`. Returns `None` if the module is not part of the framework, the code is not
+/// in the error map or the constant is never used.
+pub fn first_use_of_abort_constant(module_id: &ModuleId, code: u64) -> Option<AbortConstantUse> {
+    let explanation = get_explanation(module_id, code)?;
+    let (file, source) = find_module_source(module_id)?;
+    let line = source.lines().position(|line| {
+        let code = line.split("//").next().unwrap_or_default();
+        !code.trim_start().starts_with("const ")
+            && !code.trim_start().starts_with('*')
+            && contains_identifier(code, &explanation.code_name)
+    })?;
+    Some(AbortConstantUse {
+        file,
+        line: line + 1,
+        error_name: explanation.code_name,
+        error_description: explanation.code_description,
+    })
+}
+
+/// Returns true if `code` contains `identifier` as a whole word, rather than as part of a longer
+/// identifier.
+fn contains_identifier(code: &str, identifier: &str) -> bool {
+    let is_identifier_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    code.match_indices(identifier).any(|(start, _)| {
+        let end = start + identifier.len();
+        !code[..start].ends_with(is_identifier_char) && !code[end..].starts_with(is_identifier_char)
+    })
+}
+
+/// Returns the VM status code of a transaction status, e.g. `OUT_OF_GAS`, `ABORTED` or the
+/// reason a transaction was discarded. Execution failures, such as arithmetic errors, do not keep
+/// their status code in the transaction status, so `None` is returned for them.
+pub fn status_code(status: &TransactionStatus) -> Option<StatusCode> {
+    match status {
+        TransactionStatus::Discard(code) => Some(*code),
+        TransactionStatus::Keep(ExecutionStatus::Success) => Some(StatusCode::EXECUTED),
+        TransactionStatus::Keep(ExecutionStatus::OutOfGas) => Some(StatusCode::OUT_OF_GAS),
+        TransactionStatus::Keep(ExecutionStatus::MoveAbort { .. }) => Some(StatusCode::ABORTED),
+        TransactionStatus::Keep(ExecutionStatus::ExecutionFailure { .. }) => None,
+        TransactionStatus::Keep(ExecutionStatus::MiscellaneousError(code)) => *code,
+        TransactionStatus::Retry => None,
+    }
+}
+
+/// Describes a transaction status for use in assertion messages. Aborts from framework modules
+/// are annotated with the first use of their error constant.
+pub fn describe_status(status: &TransactionStatus) -> String {
+    if let TransactionStatus::Keep(ExecutionStatus::MoveAbort {
+        location: AbortLocation::Module(module_id),
+        code,
+    }) = status
+    {
+        if let Some(constant_use) = first_use_of_abort_constant(module_id, *code) {
+            return format!("{:?}, {}", status, constant_use);
+        }
+    }
+    format!("{:?}", status)
+}
+
+/// Returns true if the status is an abort raised by the given framework module, e.g.
+/// `"0x1::stake"`, whose code resolves to the error constant `error_name` in the framework error
+/// map. The error category of the code is ignored.
+pub fn is_abort_with(status: &TransactionStatus, module: &str, error_name: &str) -> bool {
+    match status {
+        TransactionStatus::Keep(ExecutionStatus::MoveAbort {
+            location: AbortLocation::Module(module_id),
+            code,
+        }) => {
+            module_id.short_str_lossless() == module
+                && get_explanation(module_id, *code)
+                    .map_or(false, |explanation| explanation.code_name == error_name)
+        }
+        _ => false,
+    }
+}
+
+/// Finds the source file which declares the given module among the framework sources.
+fn find_module_source(module_id: &ModuleId) -> Option<(PathBuf, String)> {
+    framework::aptos::files().into_iter().find_map(|file| {
+        let source = std::fs::read_to_string(&file).ok()?;
+        let declares_module = source.lines().any(|line| {
+            line.trim_start()
+                .strip_prefix("module ")
+                .and_then(|decl| decl.split(|c: char| c == '{' || c.is_whitespace()).next())
+                .and_then(|name| name.rsplit("::").next())
+                == Some(module_id.name().as_str())
+        });
+        if declares_module {
+            Some((PathBuf::from(file), source))
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::harness::MoveHarness;
+use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use aptos_keygen::KeyGen;
+use aptos_types::account_address::AccountAddress;
+use language_e2e_tests::account::{Account, AccountData};
+
+/// The balance of accounts created by the harness, unless configured otherwise: 10M Aptos coins
+/// (with 8 decimals).
+pub const DEFAULT_BALANCE: u64 = 1_000_000_000_000_000;
+/// The sequence number of accounts created by the harness, unless configured otherwise.
+pub const DEFAULT_SEQUENCE_NUMBER: u64 = 10;
+
+/// Builds an account with precise initial conditions and adds it to the harness. Created via
+/// `MoveHarness::account`.
+pub struct AccountFactory<'a> {
+    harness: &'a mut MoveHarness,
+    address: Option<AccountAddress>,
+    balance: u64,
+    sequence_number: u64,
+    multisig: Option<(u8, u8)>,
+    keypair: Option<(Ed25519PrivateKey, Ed25519PublicKey)>,
+}
+
+impl<'a> AccountFactory<'a> {
+    pub(crate) fn new(harness: &'a mut MoveHarness) -> Self {
+        Self {
+            harness,
+            address: None,
+            balance: DEFAULT_BALANCE,
+            sequence_number: DEFAULT_SEQUENCE_NUMBER,
+            multisig: None,
+            keypair: None,
+        }
+    }
+
+    /// Sets the address of the account. This needs to be static for the account to be used with
+    /// golden files, or to publish Move code without rewriting code addresses. If not set, the
+    /// address is derived from a fresh key.
+    pub fn address(mut self, address: AccountAddress) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn balance(mut self, balance: u64) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn sequence_number(mut self, sequence_number: u64) -> Self {
+        self.sequence_number = sequence_number;
+        self
+    }
+
+    /// Makes the account a multi-ed25519 account with `num_keys` keys, `threshold` of which sign
+    /// its transactions.
+    pub fn multisig(mut self, threshold: u8, num_keys: u8) -> Self {
+        self.multisig = Some((threshold, num_keys));
+        self
+    }
+
+    /// Takes the key of the account from `keys`, so that a seeded `KeyGen` yields the same
+    /// accounts on every run. Unless set, the address is derived from the key.
+    pub fn key_from(mut self, keys: &mut KeyGen) -> Self {
+        self.keypair = Some(keys.generate_ed25519_keypair());
+        self
+    }
+
+    /// Creates the account and adds it to the harness.
+    pub fn build(self) -> Account {
+        let account = match (self.address, self.multisig, self.keypair) {
+            // The below will use the genesis keypair but that should be fine.
+            (Some(address), None, None) => Account::new_genesis_account(address),
+            (None, None, None) => Account::new(),
+            (Some(address), None, Some((private_key, public_key))) => {
+                Account::new_validator(address, private_key, public_key)
+            }
+            (None, None, Some((private_key, public_key))) => {
+                Account::with_keypair(private_key, public_key)
+            }
+            (_, Some(_), Some(_)) => panic!("the keys of multisig accounts can't be set"),
+            (address, Some((threshold, num_keys)), None) => {
+                // Keys are derived from the address, so static addresses yield static keys.
+                let address = address.unwrap_or_else(AccountAddress::random);
+                let mut seed = KeyGen::from_seed(address.into_bytes());
+                Account::new_multisig_at(address, &mut seed, threshold, num_keys)
+            }
+        };
+        let data = AccountData::with_account(account, self.balance, self.sequence_number);
+        self.harness.add_account_data(&data);
+        data.into_account()
+    }
+}
@@ -0,0 +1,14 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// Mimics `0x1::aggregator::Aggregator`, e.g. as a field of a mirrored resource. It only
+/// identifies the aggregator: its value is stored in the table item `key` of the table `handle`,
+/// see `MoveHarness::read_aggregator`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Aggregator {
+    pub handle: u128,
+    pub key: u128,
+    pub limit: u128,
+}
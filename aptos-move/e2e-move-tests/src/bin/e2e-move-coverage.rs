@@ -0,0 +1,28 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Writes the coverage of the framework by the e2e tests into the framework packages, see
+//! `e2e_move_tests::coverage`.
+
+use e2e_move_tests::coverage;
+use std::path::PathBuf;
+
+fn main() {
+    let trace = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .or_else(coverage::trace_file)
+        .expect("usage: e2e-move-coverage <trace file>, or set MOVE_VM_TRACE");
+    if !trace.exists() {
+        println!(
+            "trace file {} does not exist, run the tests with {}={} in a debug build first",
+            trace.display(),
+            coverage::TRACE_ENV_VAR,
+            trace.display()
+        );
+        return;
+    }
+    for path in coverage::write_framework_coverage_maps(&trace) {
+        println!("wrote {}", path.display());
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::harness::MoveHarness;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Step {
+    AdvanceSecs(u64),
+    AdvanceUsecs(u64),
+    NewEpoch,
+}
+
+/// A timeline of clock advances and epoch changes, applied to the harness in order by `run`.
+/// Created via `MoveHarness::clock`, e.g.
+/// `harness.clock().advance_secs(7200).to_next_epoch().run()`.
+#[must_use = "the timeline has no effect until `run` is called"]
+pub struct Clock<'a> {
+    harness: &'a mut MoveHarness,
+    steps: Vec<Step>,
+}
+
+impl<'a> Clock<'a> {
+    pub(crate) fn new(harness: &'a mut MoveHarness) -> Self {
+        Self {
+            harness,
+            steps: vec![],
+        }
+    }
+
+    /// Advances the block time by `seconds`, without executing any block.
+    pub fn advance_secs(mut self, seconds: u64) -> Self {
+        self.steps.push(Step::AdvanceSecs(seconds));
+        self
+    }
+
+    /// Advances the block time by `usecs` microseconds, without executing any block.
+    pub fn advance_usecs(mut self, usecs: u64) -> Self {
+        self.steps.push(Step::AdvanceUsecs(usecs));
+        self
+    }
+
+    /// Ends the current epoch, see `MoveHarness::new_epoch`.
+    pub fn to_next_epoch(mut self) -> Self {
+        self.steps.push(Step::NewEpoch);
+        self
+    }
+
+    /// Ends the current epoch and the `count - 1` epochs after it.
+    pub fn epochs(mut self, count: usize) -> Self {
+        self.steps
+            .extend(std::iter::repeat(Step::NewEpoch).take(count));
+        self
+    }
+
+    /// Applies the timeline to the harness.
+    pub fn run(self) {
+        for step in self.steps {
+            match step {
+                Step::AdvanceSecs(seconds) => self.harness.fast_forward_secs(seconds),
+                Step::AdvanceUsecs(usecs) => self.harness.fast_forward_usecs(usecs),
+                Step::NewEpoch => self.harness.new_epoch(),
+            }
+        }
+    }
+}
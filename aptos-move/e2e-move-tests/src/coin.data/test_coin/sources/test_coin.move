@@ -0,0 +1,4 @@
+/// A coin type other than `AptosCoin`, managed via `0x1::managed_coin`.
+module test_coin::test_coin {
+    struct TestCoin {}
+}
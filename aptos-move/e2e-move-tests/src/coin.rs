@@ -0,0 +1,106 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, harness::MoveHarness, summary::TxnSummary, MoveResource};
+use aptos_types::account_address::AccountAddress;
+use cached_framework_packages::aptos_stdlib;
+use framework::natives::code::UpgradePolicy;
+use language_e2e_tests::account::Account;
+use move_deps::move_core_types::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The address `TestCoin` is published under, see `publish_test_coin`.
+pub const TEST_COIN_ADDRESS: &str = "0xc0ffee";
+
+/// Mimics `0x1::aptos_coin::AptosCoin`, for use as a coin type, e.g. in
+/// `MoveHarness::balance::<AptosCoin>`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_coin")]
+pub struct AptosCoin {
+    dummy_field: bool,
+}
+
+/// Mimics `0x1::coin::MintCapability<0x1::aptos_coin::AptosCoin>`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "coin")]
+pub struct MintCapability {
+    dummy_field: bool,
+}
+
+/// Mimics `0x1::aptos_coin::Capabilities`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_coin")]
+pub struct Capabilities {
+    pub mint_cap: MintCapability,
+}
+
+/// Mimics `0xc0ffee::test_coin::TestCoin`, a coin type other than `AptosCoin`, for use as a coin
+/// type once published by `publish_test_coin`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0xc0ffee", module = "test_coin")]
+pub struct TestCoin {
+    dummy_field: bool,
+}
+
+fn coin_type<C: MoveResource>() -> TypeTag {
+    TypeTag::Struct(C::struct_tag())
+}
+
+/// Publishes `TestCoin` and initializes it via `0x1::managed_coin`. Returns the account at
+/// `TEST_COIN_ADDRESS`, which holds the capabilities to mint the coin, see `mint_coin`.
+pub fn publish_test_coin(harness: &mut MoveHarness) -> Account {
+    let account =
+        harness.new_account_at(AccountAddress::from_hex_literal(TEST_COIN_ADDRESS).unwrap());
+    assert_success!(harness.publish_package(
+        &account,
+        &PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("coin.data")
+            .join("test_coin"),
+        UpgradePolicy::compat(),
+    ));
+    assert_success!(harness.run_transaction_payload(
+        &account,
+        aptos_stdlib::managed_coin_initialize(
+            coin_type::<TestCoin>(),
+            b"Test Coin".to_vec(),
+            b"TST".to_vec(),
+            8,
+            true,
+        ),
+    ));
+    account
+}
+
+/// Registers a coin store for `C` at `account`, which it needs to receive the coin.
+pub fn register_coin<C: MoveResource>(harness: &mut MoveHarness, account: &Account) -> TxnSummary {
+    harness.run_transaction_payload(account, aptos_stdlib::coins_register(coin_type::<C>()))
+}
+
+/// Mints `amount` of `C` to `to` via `0x1::managed_coin`. `minter` needs to hold the capabilities
+/// of `C`, e.g. be the account returned by `publish_test_coin`.
+pub fn mint_coin<C: MoveResource>(
+    harness: &mut MoveHarness,
+    minter: &Account,
+    to: AccountAddress,
+    amount: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        minter,
+        aptos_stdlib::managed_coin_mint(coin_type::<C>(), to, amount),
+    )
+}
+
+/// Transfers `amount` of `C` from `account` to `to`, which needs a coin store for `C`.
+pub fn transfer_coin<C: MoveResource>(
+    harness: &mut MoveHarness,
+    account: &Account,
+    to: AccountAddress,
+    amount: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        account,
+        aptos_stdlib::coin_transfer(coin_type::<C>(), to, amount),
+    )
+}
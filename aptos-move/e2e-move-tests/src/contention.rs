@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blocks of transactions which all touch the same hotspot resource, to stress the parallel
+//! executor. The harness already asserts that every block produces the same outputs in parallel
+//! as sequentially; `run_contended_block` also requires every transaction to succeed and reports
+//! how much work the scheduler did to get there.
+
+use crate::{assert_success, harness::MoveHarness};
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use aptos_vm::parallel_executor::SchedulerStats;
+use cached_framework_packages::aptos_stdlib;
+use language_e2e_tests::account::Account;
+
+/// A block size at which conflicts between the threads of the parallel executor are all but
+/// certain.
+pub const CONTENDED_BLOCK_SIZE: usize = 200;
+
+/// Creates `num_txns` transfers of `amount` to the coin store of `receiver`, each sent by a
+/// fresh account.
+pub fn coin_store_hotspot(
+    harness: &mut MoveHarness,
+    receiver: AccountAddress,
+    num_txns: usize,
+    amount: u64,
+) -> Vec<SignedTransaction> {
+    (0..num_txns)
+        .map(|_| {
+            let sender = harness.account().build();
+            harness.create_transaction_payload(
+                &sender,
+                aptos_stdlib::aptos_coin_transfer(receiver, amount),
+            )
+        })
+        .collect()
+}
+
+/// Creates `num_txns` additions of `amount` to the stake pool of `owner`, which must already be
+/// initialized. As only the owner can add stake, the transactions have consecutive sequence
+/// numbers and thus also contend on the owner's account and coin store.
+pub fn stake_pool_hotspot(
+    harness: &mut MoveHarness,
+    owner: &Account,
+    num_txns: usize,
+    amount: u64,
+) -> Vec<SignedTransaction> {
+    (0..num_txns)
+        .map(|_| harness.create_transaction_payload(owner, aptos_stdlib::stake_add_stake(amount)))
+        .collect()
+}
+
+/// Runs a block created by one of the generators above, asserts that every transaction
+/// succeeded, and returns the counts of aborts and re-executions of the parallel executor.
+pub fn run_contended_block(
+    harness: &mut MoveHarness,
+    txn_block: Vec<SignedTransaction>,
+) -> SchedulerStats {
+    for status in harness.run_block(txn_block) {
+        assert_success!(status);
+    }
+    harness
+        .last_parallel_stats()
+        .expect("contended blocks require a harness which executes blocks in parallel")
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage of the framework's Move code by the transactions the e2e tests run, including the
+//! prologues, epilogues and reconfigurations Move unit tests don't reach.
+//!
+//! In debug builds, the Move VM appends every instruction it executes to the trace file named by
+//! `MOVE_VM_TRACE`, so running the tests with it set traces every transaction of every harness:
+//!
+//! ```text
+//! MOVE_VM_TRACE=/tmp/e2e.trace cargo test -p e2e-move-tests
+//! cargo run -p e2e-move-tests --bin e2e-move-coverage -- /tmp/e2e.trace
+//! ```
+//!
+//! The second command writes the coverage map into every framework package, where it is picked
+//! up by `move coverage`. The trace file is only ever appended to, so it needs to be removed
+//! before collecting coverage anew.
+
+use move_deps::move_coverage::coverage_map::{output_map_to_file, CoverageMap};
+use project_root::get_project_root;
+use std::path::{Path, PathBuf};
+
+/// Names the file the Move VM traces executed instructions to.
+pub const TRACE_ENV_VAR: &str = "MOVE_VM_TRACE";
+
+/// Where `move coverage` looks for the coverage map of a package.
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
+
+/// The framework packages, relative to the project root.
+const FRAMEWORK_PACKAGES: &[&str] = &[
+    "aptos-move/framework/move-stdlib",
+    "aptos-move/framework/aptos-stdlib",
+    "aptos-move/framework/aptos-framework",
+    "aptos-move/framework/aptos-token",
+];
+
+/// Returns the trace file set via `MOVE_VM_TRACE`, if any.
+pub fn trace_file() -> Option<PathBuf> {
+    std::env::var_os(TRACE_ENV_VAR).map(PathBuf::from)
+}
+
+/// Reads the coverage of all modules executed in the trace.
+pub fn coverage_map(trace: &Path) -> CoverageMap {
+    CoverageMap::from_trace_file(trace)
+}
+
+/// Writes the coverage map of the trace into every framework package, and returns the files
+/// written.
+pub fn write_framework_coverage_maps(trace: &Path) -> Vec<PathBuf> {
+    let map = coverage_map(trace);
+    let root = get_project_root().unwrap();
+    FRAMEWORK_PACKAGES
+        .iter()
+        .map(|package| {
+            let path = root.join(package).join(COVERAGE_MAP_FILE);
+            output_map_to_file(&path, &map)
+                .unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+            path
+        })
+        .collect()
+}
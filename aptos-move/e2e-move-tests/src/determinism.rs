@@ -0,0 +1,116 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::harness::MoveHarness;
+use aptos_crypto::HashValue;
+use aptos_types::transaction::TransactionOutput;
+use std::time::{Duration, Instant};
+
+/// The observable result of a transaction which must be identical across runs of a test.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutputRecord {
+    pub gas_used: u64,
+    pub output_hash: HashValue,
+}
+
+impl OutputRecord {
+    pub fn new(output: &TransactionOutput) -> Self {
+        Self {
+            gas_used: output.gas_used(),
+            output_hash: HashValue::sha3_256_of(
+                &bcs::to_bytes(output).expect("TransactionOutput has BCS"),
+            ),
+        }
+    }
+}
+
+/// Runs `test` twice in-process, each time on a fresh harness created by `new_harness`, and
+/// asserts that every transaction (including block prologues) used the same amount of gas and
+/// produced the same output in both runs. This catches nondeterminism in the VM or natives, such
+/// as hash map iteration order or leaked wall clock time.
+///
+/// The test body should not enable golden files, as they would be written twice.
+pub fn assert_deterministic<F, T>(new_harness: F, test: T)
+where
+    F: Fn() -> MoveHarness,
+    T: Fn(&mut MoveHarness),
+{
+    assert_equivalent(&new_harness, &new_harness, test)
+}
+
+/// Runs `test` once on a harness created by `new_lhs` and once on a harness created by
+/// `new_rhs`, and asserts that every transaction used the same amount of gas and produced the
+/// same output in both runs. This is used for differential testing of two harness
+/// configurations which must never diverge.
+///
+/// The VM has no paranoid type checking mode to toggle, and `FakeExecutor` already compares
+/// parallel against sequential execution for every block, so neither needs a differential test.
+///
+/// The test body should not enable golden files, as they would be written twice.
+pub fn assert_equivalent<L, R, T>(new_lhs: L, new_rhs: R, test: T)
+where
+    L: Fn() -> MoveHarness,
+    R: Fn() -> MoveHarness,
+    T: Fn(&mut MoveHarness),
+{
+    let lhs_records = record_run(new_lhs(), &test);
+    let rhs_records = record_run(new_rhs(), &test);
+    assert_records_equal(&lhs_records, &rhs_records);
+}
+
+/// The duration of a run of a test at a concurrency level, see `sweep_concurrency`.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyRun {
+    pub concurrency_level: usize,
+    pub elapsed: Duration,
+}
+
+/// Runs `test` once for each of the given concurrency levels of the parallel executor, e.g.
+/// `&[1, 2, 8, 32]`, and asserts that every transaction produced the same output at every
+/// level. Returns how long each run took, to observe the scaling behavior.
+///
+/// Note that the harness itself also executes every block sequentially, to compare against.
+pub fn sweep_concurrency<T>(concurrency_levels: &[usize], test: T) -> Vec<ConcurrencyRun>
+where
+    T: Fn(&mut MoveHarness),
+{
+    let mut first_records = None;
+    let mut runs = vec![];
+    for &concurrency_level in concurrency_levels {
+        let start = Instant::now();
+        let records = record_run(
+            MoveHarness::new_with_concurrency_level(concurrency_level),
+            &test,
+        );
+        runs.push(ConcurrencyRun {
+            concurrency_level,
+            elapsed: start.elapsed(),
+        });
+        match &first_records {
+            None => first_records = Some(records),
+            Some(first_records) => assert_records_equal(first_records, &records),
+        }
+    }
+    runs
+}
+
+fn record_run<T: Fn(&mut MoveHarness)>(mut harness: MoveHarness, test: &T) -> Vec<OutputRecord> {
+    harness.record_outputs();
+    test(&mut harness);
+    harness.take_output_records()
+}
+
+fn assert_records_equal(lhs_records: &[OutputRecord], rhs_records: &[OutputRecord]) {
+    for (idx, (lhs, rhs)) in lhs_records.iter().zip(rhs_records.iter()).enumerate() {
+        assert_eq!(
+            lhs, rhs,
+            "transaction #{} differs between the two runs of the same test",
+            idx
+        );
+    }
+    assert_eq!(
+        lhs_records.len(),
+        rhs_records.len(),
+        "number of transactions differs between the two runs of the same test"
+    );
+}
@@ -0,0 +1,130 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// A field whose value differs between two resources.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDiff {
+    /// The path to the field, e.g. `active.value` or `validators[2].addr`.
+    pub path: String,
+    /// The value on the left, or `None` if the field only exists on the right.
+    pub left: Option<Value>,
+    /// The value on the right, or `None` if the field only exists on the left.
+    pub right: Option<Value>,
+}
+
+/// Computes the fields which differ between two decoded resources, such as `StakePool`. Values
+/// are compared exactly by their BCS encoding, so this also works for types without
+/// `PartialEq`. Returns `None` if the resources are equal.
+pub fn resource_diff<T: Serialize>(left: &T, right: &T) -> Option<Vec<FieldDiff>> {
+    if bcs::to_bytes(left).expect("resource has BCS")
+        == bcs::to_bytes(right).expect("resource has BCS")
+    {
+        return None;
+    }
+    let mut diffs = vec![];
+    diff_values("", &to_json(left), &to_json(right), &mut diffs);
+    Some(diffs)
+}
+
+/// Renders the field-level differences between two resources, colored unless `NO_COLOR` is set.
+/// Returns `None` if the resources are equal.
+pub fn render_resource_diff<T: Serialize>(left: &T, right: &T) -> Option<String> {
+    let diffs = resource_diff(left, right)?;
+    let (red, green, reset) = if std::env::var_os("NO_COLOR").is_some() {
+        ("", "", "")
+    } else {
+        (RED, GREEN, RESET)
+    };
+    let mut out = String::new();
+    if diffs.is_empty() {
+        // Only possible for numbers which JSON cannot represent exactly, e.g. large `u128`s.
+        out.push_str("resources differ in values which cannot be displayed\n");
+    }
+    for diff in diffs {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "<missing>".to_string(),
+        };
+        writeln!(out, "{}:", diff.path).unwrap();
+        writeln!(out, "  {}- {}{}", red, show(&diff.left), reset).unwrap();
+        writeln!(out, "  {}+ {}{}", green, show(&diff.right), reset).unwrap();
+    }
+    Some(out)
+}
+
+/// Computes the fields which differ between two JSON values, e.g. resources rendered by the
+/// annotator.
+pub fn json_diff(left: &Value, right: &Value) -> Vec<FieldDiff> {
+    let mut diffs = vec![];
+    diff_values("", left, right, &mut diffs);
+    diffs
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    // Going through a string keeps numbers larger than `u64` (as lossy floats) instead of failing.
+    serde_json::from_str(&serde_json::to_string(value).expect("resource has JSON"))
+        .expect("JSON must parse")
+}
+
+fn diff_values(path: &str, left: &Value, right: &Value, diffs: &mut Vec<FieldDiff>) {
+    match (left, right) {
+        (Value::Object(left_fields), Value::Object(right_fields)) => {
+            for (name, left_value) in left_fields {
+                let field_path = join_path(path, name);
+                match right_fields.get(name) {
+                    Some(right_value) => diff_values(&field_path, left_value, right_value, diffs),
+                    None => diffs.push(FieldDiff {
+                        path: field_path,
+                        left: Some(left_value.clone()),
+                        right: None,
+                    }),
+                }
+            }
+            for (name, right_value) in right_fields {
+                if !left_fields.contains_key(name) {
+                    diffs.push(FieldDiff {
+                        path: join_path(path, name),
+                        left: None,
+                        right: Some(right_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(left_items), Value::Array(right_items))
+            if left_items.len() == right_items.len() =>
+        {
+            for (idx, (left_item, right_item)) in left_items.iter().zip(right_items).enumerate() {
+                diff_values(&format!("{}[{}]", path, idx), left_item, right_item, diffs);
+            }
+        }
+        _ => {
+            if left != right {
+                diffs.push(FieldDiff {
+                    path: if path.is_empty() {
+                        "<root>".to_string()
+                    } else {
+                        path.to_string()
+                    },
+                    left: Some(left.clone()),
+                    right: Some(right.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{AccountResource, CoinStoreResource, DepositEvent, WithdrawEvent},
+    event::EventKey,
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
+};
+use language_e2e_tests::executor::FakeExecutor;
+
+/// The state of a transaction sender which the transaction epilogue is responsible for.
+#[derive(Clone, Debug)]
+pub struct SenderState {
+    sequence_number: u64,
+    /// The balance and the keys of the deposit and withdraw event handles, if the sender has a
+    /// coin store.
+    coin_store: Option<(u64, EventKey, EventKey)>,
+}
+
+impl SenderState {
+    /// Reads the current state of the sender. Returns `None` if the account does not exist.
+    pub fn read(executor: &FakeExecutor, sender: &AccountAddress) -> Option<Self> {
+        let account = executor.read_resource::<AccountResource>(sender)?;
+        let coin_store = executor
+            .read_resource::<CoinStoreResource>(sender)
+            .map(|store| {
+                (
+                    store.coin(),
+                    *store.deposit_events().key(),
+                    *store.withdraw_events().key(),
+                )
+            });
+        Some(Self {
+            sequence_number: account.sequence_number(),
+            coin_store,
+        })
+    }
+
+    /// Verifies the effects of the epilogue of `txn`, given the state of its sender before and
+    /// after the transaction output has been applied: the sequence number must have been
+    /// incremented, and the gas fee must have been deducted from the balance. Balance changes
+    /// other than the fee must be accounted for by deposit and withdraw events.
+    ///
+    /// This framework version burns gas fees without emitting events, so the fee is verified
+    /// through the balance alone.
+    pub fn check_epilogue(
+        &self,
+        after: &Self,
+        txn: &SignedTransaction,
+        output: &TransactionOutput,
+    ) {
+        if !matches!(output.status(), TransactionStatus::Keep(_)) {
+            return;
+        }
+        assert_eq!(
+            after.sequence_number,
+            self.sequence_number + 1,
+            "epilogue did not increment the sequence number of {}",
+            txn.sender()
+        );
+        if let (Some((before_balance, deposit_key, withdraw_key)), Some((after_balance, ..))) =
+            (&self.coin_store, &after.coin_store)
+        {
+            let mut expected =
+                *before_balance as i128 - output.gas_used() as i128 * txn.gas_unit_price() as i128;
+            for event in output.events() {
+                if event.key() == deposit_key {
+                    expected += DepositEvent::try_from_bytes(event.event_data())
+                        .expect("deposit event must deserialize")
+                        .amount() as i128;
+                } else if event.key() == withdraw_key {
+                    expected -= WithdrawEvent::try_from_bytes(event.event_data())
+                        .expect("withdraw event must deserialize")
+                        .amount() as i128;
+                }
+            }
+            assert_eq!(
+                *after_balance as i128,
+                expected,
+                "balance of {} does not match the gas fee of {} units at {} and its coin events",
+                txn.sender(),
+                output.gas_used(),
+                txn.gas_unit_price()
+            );
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{resource::MoveResource, summary::TxnSummary};
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+    transaction::TransactionOutput,
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+use std::collections::BTreeMap;
+
+/// Verifies that events emitted under the same event handle carry contiguous sequence numbers.
+///
+/// The first event observed for a handle establishes the baseline. Every subsequent event on
+/// that handle, whether emitted by the same or a later transaction, must carry the next
+/// sequence number. This catches event counters which are skipped, reused, or reordered.
+#[derive(Debug, Default)]
+pub struct EventSequenceChecker {
+    next_sequence_number: BTreeMap<EventKey, u64>,
+}
+
+impl EventSequenceChecker {
+    /// Checks the events of a single transaction output, in the order they were emitted.
+    pub fn check(&mut self, events: &[ContractEvent]) {
+        for event in events {
+            let next = self
+                .next_sequence_number
+                .entry(*event.key())
+                .or_insert_with(|| event.sequence_number());
+            assert_eq!(
+                event.sequence_number(),
+                *next,
+                "event sequence numbers for handle {:?} are not contiguous",
+                event.key()
+            );
+            *next += 1;
+        }
+    }
+
+    /// Forgets all handles created by the given address. This is needed if the account's
+    /// resources (and with them the event handles) have been reset.
+    pub fn forget_address(&mut self, addr: &AccountAddress) {
+        self.next_sequence_number
+            .retain(|key, _| &key.get_creator_address() != addr);
+    }
+}
+
+/// Returns the events of the given Rust mirror type, in the order they were emitted.
+pub fn events_of<T: MoveResource>(events: &[ContractEvent]) -> Vec<T> {
+    let type_tag = TypeTag::Struct(T::struct_tag());
+    events
+        .iter()
+        .filter(|event| event.type_tag() == &type_tag)
+        .map(|event| bcs::from_bytes(event.event_data()).expect("event data must match its type"))
+        .collect()
+}
+
+/// The result of a transaction carrying the events it emitted, see `MoveHarness::events_for`.
+pub trait TransactionEvents {
+    fn transaction_events(&self) -> &[ContractEvent];
+}
+
+impl TransactionEvents for TransactionOutput {
+    fn transaction_events(&self) -> &[ContractEvent] {
+        self.events()
+    }
+}
+
+impl TransactionEvents for TxnSummary {
+    fn transaction_events(&self) -> &[ContractEvent] {
+        self.events()
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{get_stake_pool, harness::MoveHarness};
+use aptos_types::account_address::AccountAddress;
+use proptest::prelude::*;
+
+/// A validator taking part in a fairness simulation.
+#[derive(Clone, Copy, Debug)]
+pub struct SimulatedValidator {
+    pub address: AccountAddress,
+    pub validator_index: u32,
+}
+
+/// The cumulative rewards of each validator over a simulation, compared to its stake.
+#[derive(Clone, Debug)]
+pub struct FairnessReport {
+    /// The share of each validator in the total stake at the start of the simulation.
+    pub stake_shares: Vec<f64>,
+    /// The share of each validator in the total rewards distributed during the simulation.
+    pub reward_shares: Vec<f64>,
+}
+
+impl FairnessReport {
+    /// Returns the largest absolute difference between a validator's reward share and its stake
+    /// share.
+    pub fn max_deviation(&self) -> f64 {
+        self.stake_shares
+            .iter()
+            .zip(&self.reward_shares)
+            .map(|(stake, reward)| (stake - reward).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Asserts that each validator's reward share is within `tolerance` of its stake share.
+    pub fn assert_within(&self, tolerance: f64) {
+        assert!(
+            self.max_deviation() <= tolerance,
+            "reward distribution deviates from stake distribution by more than {}: stake shares \
+             {:?}, reward shares {:?}",
+            tolerance,
+            self.stake_shares,
+            self.reward_shares
+        );
+    }
+}
+
+/// Chooses the proposers of `num_blocks` blocks, selecting each validator with a probability
+/// proportional to its weight. At least one weight must be positive.
+pub fn weighted_proposers(weights: &[u64], num_blocks: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let total: u64 = weights.iter().sum();
+    assert!(
+        total > 0,
+        "cannot choose proposers if all weights are 0: {:?}",
+        weights
+    );
+    (0..num_blocks)
+        .map(|_| {
+            let mut target = rng.gen_range(0..total);
+            weights
+                .iter()
+                .position(|weight| {
+                    if target < *weight {
+                        true
+                    } else {
+                        target -= weight;
+                        false
+                    }
+                })
+                .expect("target is below the total weight")
+        })
+        .collect()
+}
+
+/// Simulates `num_epochs` epochs of `blocks_per_epoch` blocks each, where proposers are chosen
+/// weighted by their stake at the start of the simulation and every proposal succeeds. Returns
+/// how the rewards were distributed among the validators. A validator whose active stake went
+/// down during the simulation, e.g. because it was unlocked by a block hook, counts as having
+/// received no rewards.
+pub fn simulate_weighted_proposals(
+    harness: &mut MoveHarness,
+    validators: &[SimulatedValidator],
+    num_epochs: usize,
+    blocks_per_epoch: usize,
+    rng: &mut impl Rng,
+) -> FairnessReport {
+    let active_stake = |harness: &MoveHarness| -> Vec<u64> {
+        validators
+            .iter()
+            .map(|validator| get_stake_pool(harness, &validator.address).active)
+            .collect()
+    };
+    let initial_stakes = active_stake(harness);
+    for _ in 0..num_epochs {
+        for proposer in weighted_proposers(&initial_stakes, blocks_per_epoch, rng) {
+            harness.new_block_with_metadata(Some(validators[proposer].validator_index), vec![]);
+        }
+        harness.new_epoch();
+    }
+    let rewards: Vec<u64> = active_stake(harness)
+        .iter()
+        .zip(&initial_stakes)
+        .map(|(stake, initial)| stake.saturating_sub(*initial))
+        .collect();
+    FairnessReport {
+        stake_shares: shares(&initial_stakes),
+        reward_shares: shares(&rewards),
+    }
+}
+
+fn shares(amounts: &[u64]) -> Vec<f64> {
+    let total: u64 = amounts.iter().sum();
+    amounts
+        .iter()
+        .map(|amount| {
+            if total == 0 {
+                0.0
+            } else {
+                *amount as f64 / total as f64
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Canonical accounts used across the test suite. Each role lives at its own fixed address and
+//! uses the genesis keypair, so transactions (and with them golden files) are stable, and roles
+//! never collide within a test.
+
+use crate::harness::MoveHarness;
+use aptos_types::account_address::AccountAddress;
+use language_e2e_tests::account::Account;
+
+pub fn owner_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x123").unwrap()
+}
+
+pub fn operator_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x234").unwrap()
+}
+
+pub fn voter_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x345").unwrap()
+}
+
+pub fn delegator_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x456").unwrap()
+}
+
+/// Creates the account owning a stake pool at `owner_address`.
+pub fn owner(harness: &mut MoveHarness) -> Account {
+    harness.new_account_at(owner_address())
+}
+
+/// Creates the account operating a stake pool at `operator_address`.
+pub fn operator(harness: &mut MoveHarness) -> Account {
+    harness.new_account_at(operator_address())
+}
+
+/// Creates the account voting on behalf of a stake pool at `voter_address`.
+pub fn voter(harness: &mut MoveHarness) -> Account {
+    harness.new_account_at(voter_address())
+}
+
+/// Creates an account delegating to a stake pool at `delegator_address`.
+pub fn delegator(harness: &mut MoveHarness) -> Account {
+    harness.new_account_at(delegator_address())
+}
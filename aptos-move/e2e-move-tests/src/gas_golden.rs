@@ -0,0 +1,125 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Golden files of the gas used by every transaction of a test, see `enable_golden!`.
+
+use aptos_types::transaction::TransactionPayload;
+use language_e2e_tests::golden_outputs::write_golden_file;
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// By how much, in percent, the gas used by a transaction may drift from the golden file,
+/// unless configured otherwise via `MoveHarness::set_gas_tolerance_percent`.
+pub const DEFAULT_GAS_TOLERANCE_PERCENT: u64 = 5;
+
+/// If set, golden files are updated instead of checked.
+const UPDATE_ENV_VAR: &str = "UPDATE_GOLDENFILES";
+
+const GAS_EXT: &str = "gas";
+
+/// Records the gas used by every transaction of a test, and on `finish` checks it against the
+/// golden file `<test>.gas`. Unlike the golden file of outputs, which needs to match exactly,
+/// the gas used may drift within a tolerance, so that only significant regressions fail.
+pub(crate) struct GasGolden {
+    path: PathBuf,
+    tolerance_percent: u64,
+    entries: Vec<(String, u64)>,
+}
+
+impl GasGolden {
+    pub fn new(dir: &Path, name: &str) -> Self {
+        Self {
+            path: dir.join(name).with_extension(GAS_EXT),
+            tolerance_percent: DEFAULT_GAS_TOLERANCE_PERCENT,
+            entries: vec![],
+        }
+    }
+
+    pub fn set_tolerance_percent(&mut self, tolerance_percent: u64) {
+        self.tolerance_percent = tolerance_percent
+    }
+
+    pub fn record(&mut self, payload: &TransactionPayload, gas_used: u64) {
+        self.entries.push((payload_label(payload), gas_used))
+    }
+
+    /// Checks the recorded gas against the golden file, which fails if it is missing. The golden
+    /// file is written instead if `UPDATE_GOLDENFILES` is set.
+    pub fn finish(&self) {
+        if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+            let mut actual = String::new();
+            for (index, (label, gas_used)) in self.entries.iter().enumerate() {
+                writeln!(actual, "{} {} {}", index, label, gas_used).unwrap();
+            }
+            write_golden_file(&self.path, &actual);
+            return;
+        }
+        let expected = fs::read_to_string(&self.path).unwrap_or_else(|_| {
+            panic!(
+                "gas golden file {} is missing, run with {}=1 to create it",
+                self.path.display(),
+                UPDATE_ENV_VAR
+            )
+        });
+        let expected: Vec<(String, u64)> = expected.lines().map(parse_line).collect();
+        let mut errors = vec![];
+        if expected.len() != self.entries.len() {
+            errors.push(format!(
+                "expected {} transactions, recorded {}",
+                expected.len(),
+                self.entries.len()
+            ));
+        }
+        for (index, ((expected_label, expected_gas), (label, gas_used))) in
+            expected.iter().zip(&self.entries).enumerate()
+        {
+            if expected_label != label {
+                errors.push(format!(
+                    "transaction {}: expected {}, recorded {}",
+                    index, expected_label, label
+                ));
+            } else if expected_gas.abs_diff(*gas_used) as u128 * 100
+                > *expected_gas as u128 * self.tolerance_percent as u128
+            {
+                errors.push(format!(
+                    "transaction {} ({}): gas used {} drifted from {} by more than {}%",
+                    index, label, gas_used, expected_gas, self.tolerance_percent
+                ));
+            }
+        }
+        assert!(
+            errors.is_empty(),
+            "gas usage differs from golden file {}, run with {}=1 to update it:\n{}",
+            self.path.display(),
+            UPDATE_ENV_VAR,
+            errors.join("\n")
+        );
+    }
+}
+
+/// Names the function a transaction calls, or the kind of its payload.
+fn payload_label(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::ScriptFunction(function) => {
+            format!("{}::{}", function.module(), function.function())
+        }
+        TransactionPayload::Script(_) => "script".to_string(),
+        TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+        TransactionPayload::WriteSet(_) => "write_set".to_string(),
+    }
+}
+
+/// Parses a line of the form `<index> <label> <gas used>`.
+fn parse_line(line: &str) -> (String, u64) {
+    let mut parts = line.split_whitespace().skip(1);
+    match (parts.next(), parts.next()) {
+        (Some(label), Some(gas_used)) => (
+            label.to_string(),
+            gas_used.parse().expect("gas used must be a number"),
+        ),
+        _ => panic!("malformed line in gas golden file: {}", line),
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::transaction::TransactionStatus;
+
+/// How a client escalates the gas unit price of a transaction which was discarded, e.g. because
+/// the price was below the minimum accepted by the prologue. Used via
+/// `MoveHarness::run_with_gas_escalation`.
+#[derive(Clone, Debug)]
+pub struct GasPricePolicy {
+    /// The gas unit price of the first submission.
+    pub initial_price: u64,
+    /// The factor the gas unit price is multiplied with on every resubmission.
+    pub multiplier: u64,
+    /// The maximum number of submissions, including the first one.
+    pub max_attempts: usize,
+}
+
+/// The submissions of a transaction under a `GasPricePolicy`, in order.
+#[derive(Clone, Debug)]
+pub struct RetryOutcome {
+    /// The gas unit price and the resulting status of every submission.
+    pub attempts: Vec<(u64, TransactionStatus)>,
+}
+
+impl RetryOutcome {
+    /// Returns the gas unit price at which the transaction was accepted, i.e. kept, whether it
+    /// succeeded or aborted.
+    pub fn accepted_price(&self) -> Option<u64> {
+        self.attempts
+            .iter()
+            .find(|(_, status)| matches!(status, TransactionStatus::Keep(_)))
+            .map(|(price, _)| *price)
+    }
+
+    /// Asserts that the transaction was accepted at a gas unit price of at most `max_price`.
+    pub fn assert_accepted_within(&self, max_price: u64) {
+        match self.accepted_price() {
+            Some(price) => assert!(
+                price <= max_price,
+                "transaction accepted at gas unit price {}, above the threshold of {}",
+                price,
+                max_price
+            ),
+            None => panic!(
+                "transaction was never accepted, attempts: {:?}",
+                self.attempts
+            ),
+        }
+    }
+
+    /// Asserts that the transaction was discarded on every submission.
+    pub fn assert_never_accepted(&self) {
+        assert!(
+            self.accepted_price().is_none(),
+            "transaction was accepted, attempts: {:?}",
+            self.attempts
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    add_stake, harness::MoveHarness, initialize_staking, join_validator_set, leave_validator_set,
+    rotate_consensus_key, summary::TxnSummary, unlock_stake, withdraw_stake,
+};
+use language_e2e_tests::account::Account;
+use proptest::{collection::vec, prelude::*};
+
+/// The proposals of a single validator within one epoch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProposalRecord {
+    pub successful: u64,
+    pub failed: u64,
+}
+
+impl ProposalRecord {
+    pub fn total(&self) -> u64 {
+        self.successful + self.failed
+    }
+
+    /// Returns true if this record's success ratio is at least the one of `other`. Validators
+    /// without any proposals have a ratio of zero.
+    pub fn performs_at_least_as_well_as(&self, other: &ProposalRecord) -> bool {
+        if other.total() == 0 {
+            return true;
+        }
+        if self.total() == 0 {
+            return other.successful == 0;
+        }
+        self.successful as u128 * other.total() as u128
+            >= other.successful as u128 * self.total() as u128
+    }
+
+    /// Produces the blocks recording this performance for the validator at `validator_index`.
+    pub fn apply(&self, harness: &mut MoveHarness, validator_index: u32) {
+        for _ in 0..self.successful {
+            harness.new_block_with_metadata(Some(validator_index), vec![]);
+        }
+        for _ in 0..self.failed {
+            harness.new_block_with_metadata(None, vec![validator_index]);
+        }
+    }
+}
+
+/// Generates the stake amounts of `num_validators` validators, each in `min..=max`.
+pub fn stake_distribution(
+    num_validators: usize,
+    min: u64,
+    max: u64,
+) -> impl Strategy<Value = Vec<u64>> {
+    vec(min..=max, num_validators)
+}
+
+/// Generates a performance record for each of `num_validators` validators, with up to
+/// `max_proposals` successful and failed proposals each.
+pub fn performance_records(
+    num_validators: usize,
+    max_proposals: u64,
+) -> impl Strategy<Value = Vec<ProposalRecord>> {
+    vec(
+        (0..=max_proposals, 0..=max_proposals)
+            .prop_map(|(successful, failed)| ProposalRecord { successful, failed }),
+        num_validators,
+    )
+}
+
+/// An operation on the stake pool of one of several validators, identified by their index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StakingOperation {
+    Initialize { validator: usize, amount: u64 },
+    AddStake { validator: usize, amount: u64 },
+    Unlock { validator: usize, amount: u64 },
+    Withdraw { validator: usize, amount: u64 },
+    Join { validator: usize },
+    Leave { validator: usize },
+    NewEpoch,
+}
+
+impl StakingOperation {
+    /// Returns the index of the validator sending the transactions of this operation, if any.
+    pub fn validator(&self) -> Option<usize> {
+        match *self {
+            StakingOperation::Initialize { validator, .. }
+            | StakingOperation::AddStake { validator, .. }
+            | StakingOperation::Unlock { validator, .. }
+            | StakingOperation::Withdraw { validator, .. }
+            | StakingOperation::Join { validator }
+            | StakingOperation::Leave { validator } => Some(validator),
+            StakingOperation::NewEpoch => None,
+        }
+    }
+
+    /// Runs the operation, where every validator owns, operates and votes for its own stake
+    /// pool. Operations may fail, e.g. adding stake before the pool is initialized. Returns the
+    /// summaries of the transactions run, so their fees can be accounted for, or for `NewEpoch`
+    /// the summary of the block prologue, whose events include the rewards distributed.
+    pub fn apply(&self, harness: &mut MoveHarness, validators: &[Account]) -> Vec<TxnSummary> {
+        let summary = match *self {
+            StakingOperation::Initialize { validator, amount } => {
+                let account = &validators[validator];
+                let address = *account.address();
+                initialize_staking(harness, account, amount, address, address)
+            }
+            StakingOperation::AddStake { validator, amount } => {
+                add_stake(harness, &validators[validator], amount)
+            }
+            StakingOperation::Unlock { validator, amount } => {
+                unlock_stake(harness, &validators[validator], amount)
+            }
+            StakingOperation::Withdraw { validator, amount } => {
+                withdraw_stake(harness, &validators[validator], amount)
+            }
+            StakingOperation::Join { validator } => {
+                let account = &validators[validator];
+                let address = *account.address();
+                let rotate = rotate_consensus_key(harness, account, address);
+                return vec![rotate, join_validator_set(harness, account, address)];
+            }
+            StakingOperation::Leave { validator } => {
+                let account = &validators[validator];
+                leave_validator_set(harness, account, *account.address())
+            }
+            StakingOperation::NewEpoch => {
+                let output = harness.new_epoch_raw();
+                return vec![TxnSummary::new(&output, 0, None)];
+            }
+        };
+        vec![summary]
+    }
+}
+
+/// Generates sequences of up to `max_len` staking operations on `num_validators` validators,
+/// moving up to `max_amount` each. Epoch changes are as likely as any other operation, so that
+/// lockups expire and pending stake takes effect in between.
+pub fn staking_operations(
+    num_validators: usize,
+    max_amount: u64,
+    max_len: usize,
+) -> impl Strategy<Value = Vec<StakingOperation>> {
+    let validator = 0..num_validators;
+    let amount = 1..=max_amount;
+    let operation = prop_oneof![
+        (validator.clone(), amount.clone())
+            .prop_map(|(validator, amount)| StakingOperation::Initialize { validator, amount }),
+        (validator.clone(), amount.clone())
+            .prop_map(|(validator, amount)| StakingOperation::AddStake { validator, amount }),
+        (validator.clone(), amount.clone())
+            .prop_map(|(validator, amount)| StakingOperation::Unlock { validator, amount }),
+        (validator.clone(), amount)
+            .prop_map(|(validator, amount)| StakingOperation::Withdraw { validator, amount }),
+        validator
+            .clone()
+            .prop_map(|validator| StakingOperation::Join { validator }),
+        validator.prop_map(|validator| StakingOperation::Leave { validator }),
+        Just(StakingOperation::NewEpoch),
+    ];
+    vec(operation, 1..=max_len)
+}
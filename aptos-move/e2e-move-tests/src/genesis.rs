@@ -0,0 +1,359 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Genesis with custom validators and accounts, see `MoveHarness::new_with_genesis`.
+
+use aptos::move_tool::BuiltPackage;
+use aptos_crypto::bls12381;
+use aptos_keygen::KeyGen;
+use aptos_types::{
+    account_address::AccountAddress, account_config::CORE_CODE_ADDRESS, chain_id::ChainId,
+};
+use language_e2e_tests::account::Account;
+use move_deps::move_binary_format::CompiledModule;
+use vm_genesis::{
+    mainnet_genesis_configuration, test_genesis_configuration, GenesisConfiguration, Validator,
+};
+
+/// The genesis parameters a harness is created with, see `GenesisConfig`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GenesisFlavor {
+    /// The parameters of `MoveHarness::new`, with a core resources account which can mint.
+    Test,
+    /// The parameters of `MoveHarness::new_mainnet`, without any way to mint.
+    Mainnet,
+}
+
+impl GenesisFlavor {
+    /// Returns the stake of the single standard validator of the flavor.
+    pub fn standard_stake(self) -> u64 {
+        match self {
+            GenesisFlavor::Test => 100_000_000,
+            GenesisFlavor::Mainnet => 1_000_000_000_000_000,
+        }
+    }
+
+    fn configuration(self) -> GenesisConfiguration {
+        match self {
+            GenesisFlavor::Test => test_genesis_configuration(),
+            GenesisFlavor::Mainnet => mainnet_genesis_configuration(),
+        }
+    }
+}
+
+/// The genesis of a harness created via `MoveHarness::new_with_genesis`, e.g.
+/// `GenesisConfig::mainnet().validator(ValidatorSpec::new(stake)).account(addr, balance)`.
+/// Without validators, the single standard validator of the flavor is used. If any parameter is
+/// overridden or a framework package added, it is generated like the validators given, and
+/// available via `MoveHarness::genesis_validators`.
+#[derive(Clone, Debug)]
+pub struct GenesisConfig {
+    flavor: GenesisFlavor,
+    validators: Vec<ValidatorSpec>,
+    accounts: Vec<(AccountAddress, u64)>,
+    epoch_interval_secs: Option<u64>,
+    recurring_lockup_duration_secs: Option<u64>,
+    voting_duration_secs: Option<u64>,
+    required_stake: Option<(u64, u64)>,
+    allow_validator_set_change: Option<bool>,
+    rewards_apy_percentage: Option<u64>,
+    chain_id: Option<ChainId>,
+    framework_modules: Vec<Vec<u8>>,
+}
+
+impl GenesisConfig {
+    pub fn test() -> Self {
+        Self::new(GenesisFlavor::Test)
+    }
+
+    pub fn mainnet() -> Self {
+        Self::new(GenesisFlavor::Mainnet)
+    }
+
+    fn new(flavor: GenesisFlavor) -> Self {
+        Self {
+            flavor,
+            validators: vec![],
+            accounts: vec![],
+            epoch_interval_secs: None,
+            recurring_lockup_duration_secs: None,
+            voting_duration_secs: None,
+            required_stake: None,
+            allow_validator_set_change: None,
+            rewards_apy_percentage: None,
+            chain_id: None,
+            framework_modules: vec![],
+        }
+    }
+
+    /// Adds a validator, which is active right after genesis.
+    pub fn validator(mut self, spec: ValidatorSpec) -> Self {
+        self.validators.push(spec);
+        self
+    }
+
+    pub fn validators(mut self, specs: &[ValidatorSpec]) -> Self {
+        self.validators.extend_from_slice(specs);
+        self
+    }
+
+    /// Adds an account at a static address, holding `balance` Aptos coins right after genesis.
+    /// The coins count towards the coin supply, but not towards `MoveHarness::total_funded`.
+    pub fn account(mut self, address: AccountAddress, balance: u64) -> Self {
+        self.accounts.push((address, balance));
+        self
+    }
+
+    /// Overrides the epoch interval of the flavor. `MoveHarness::new_epoch` then fast forwards
+    /// by this interval, instead of the 7200 seconds which start an epoch in either flavor.
+    pub fn epoch_interval_secs(mut self, seconds: u64) -> Self {
+        self.epoch_interval_secs = Some(seconds);
+        self
+    }
+
+    /// Overrides the recurring lockup duration of the flavor, which needs to be at least the
+    /// epoch interval and longer than the voting duration.
+    pub fn recurring_lockup_duration_secs(mut self, seconds: u64) -> Self {
+        self.recurring_lockup_duration_secs = Some(seconds);
+        self
+    }
+
+    /// Overrides the voting duration of governance proposals, e.g. to make room for a short
+    /// lockup.
+    pub fn voting_duration_secs(mut self, seconds: u64) -> Self {
+        self.voting_duration_secs = Some(seconds);
+        self
+    }
+
+    /// Overrides the minimum and maximum stake of a validator of the flavor, e.g. to test the
+    /// rejection of stake pools outside the range. The stakes of the genesis validators need to
+    /// be within the range.
+    pub fn required_stake(mut self, minimum_stake: u64, maximum_stake: u64) -> Self {
+        self.required_stake = Some((minimum_stake, maximum_stake));
+        self
+    }
+
+    /// Overrides whether validators can join or leave the validator set after genesis, which
+    /// only testnet disallows.
+    pub fn allow_validator_set_change(mut self, allow: bool) -> Self {
+        self.allow_validator_set_change = Some(allow);
+        self
+    }
+
+    /// Overrides the yearly rewards rate of the flavor, between 1 and 99 percent. Genesis turns it
+    /// into the rewards rate per epoch, so it depends on the epoch interval. Use
+    /// `expected_epoch_rewards` to compute the resulting rewards.
+    pub fn rewards_apy_percentage(mut self, percentage: u64) -> Self {
+        self.rewards_apy_percentage = Some(percentage);
+        self
+    }
+
+    /// Overrides the chain ID, which is the testing chain ID in either flavor, e.g.
+    /// `ChainId::new(NamedChain::MAINNET.id())`. The harness signs its transactions for this
+    /// chain, see `MoveHarness::chain_id`. Whether coins can be minted depends on the flavor, not
+    /// on the chain ID.
+    pub fn chain_id(mut self, chain_id: ChainId) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Publishes the modules of a package at genesis, alongside the Aptos framework, e.g. for
+    /// experimental framework extensions, without a fork of `cached_framework_packages`. As with
+    /// the framework, the modules must be under 0x1.
+    pub fn framework_package(mut self, package: &BuiltPackage) -> Self {
+        for module in package.extract_code() {
+            let id = CompiledModule::deserialize(&module)
+                .expect("a genesis package must only contain modules")
+                .self_id();
+            assert_eq!(
+                *id.address(),
+                CORE_CODE_ADDRESS,
+                "module {} of package {} must be under 0x1 to be published at genesis",
+                id,
+                package.name()
+            );
+            self.framework_modules.push(module);
+        }
+        self
+    }
+
+    pub fn flavor(&self) -> GenesisFlavor {
+        self.flavor
+    }
+
+    pub(crate) fn validator_specs(&self) -> &[ValidatorSpec] {
+        &self.validators
+    }
+
+    pub(crate) fn account_balances(&self) -> &[(AccountAddress, u64)] {
+        &self.accounts
+    }
+
+    pub(crate) fn framework_modules(&self) -> &[Vec<u8>] {
+        &self.framework_modules
+    }
+
+    pub(crate) fn genesis_chain_id(&self) -> ChainId {
+        self.chain_id.unwrap_or_else(ChainId::test)
+    }
+
+    /// Returns the configuration of the genesis transaction, if the standard genesis of the
+    /// flavor cannot be used, because it or the chain ID differs from the flavor's or additional
+    /// framework modules need to be published.
+    pub(crate) fn genesis_configuration(&self) -> Option<GenesisConfiguration> {
+        if self.epoch_interval_secs.is_none()
+            && self.recurring_lockup_duration_secs.is_none()
+            && self.voting_duration_secs.is_none()
+            && self.required_stake.is_none()
+            && self.allow_validator_set_change.is_none()
+            && self.rewards_apy_percentage.is_none()
+            && self.chain_id.is_none()
+            && self.framework_modules.is_empty()
+        {
+            return None;
+        }
+        let mut configuration = self.flavor.configuration();
+        if let Some(seconds) = self.epoch_interval_secs {
+            configuration.epoch_duration_secs = seconds;
+        }
+        if let Some(seconds) = self.recurring_lockup_duration_secs {
+            configuration.recurring_lockup_duration_secs = seconds;
+        }
+        if let Some(seconds) = self.voting_duration_secs {
+            configuration.voting_duration_secs = seconds;
+        }
+        if let Some((minimum_stake, maximum_stake)) = self.required_stake {
+            configuration.min_stake = minimum_stake;
+            configuration.max_stake = maximum_stake;
+        }
+        if let Some(allow) = self.allow_validator_set_change {
+            configuration.allow_new_validators = allow;
+        }
+        if let Some(percentage) = self.rewards_apy_percentage {
+            configuration.rewards_apy_percentage = percentage;
+        }
+        Some(configuration)
+    }
+
+    pub(crate) fn epoch_interval_override_secs(&self) -> Option<u64> {
+        self.epoch_interval_secs
+    }
+}
+
+/// Keys of genesis validators are generated from this seed, so that genesis is the same for every
+/// run of a test.
+const GENESIS_VALIDATOR_SEED: [u8; 32] = [7; 32];
+
+/// The configuration of a validator created at genesis. Unless set otherwise, the owner's address
+/// is derived from a fresh key, and the owner is its own operator and voter.
+#[derive(Clone, Debug)]
+pub struct ValidatorSpec {
+    stake_amount: u64,
+    owner_address: Option<AccountAddress>,
+    operator_address: Option<AccountAddress>,
+    voter_address: Option<AccountAddress>,
+}
+
+impl ValidatorSpec {
+    /// A validator staking `stake_amount`, which is also minted to its owner at genesis.
+    pub fn new(stake_amount: u64) -> Self {
+        Self {
+            stake_amount,
+            owner_address: None,
+            operator_address: None,
+            voter_address: None,
+        }
+    }
+
+    /// Sets a static address for the owner, i.e. the stake pool.
+    pub fn owner(mut self, address: AccountAddress) -> Self {
+        self.owner_address = Some(address);
+        self
+    }
+
+    pub fn operator(mut self, address: AccountAddress) -> Self {
+        self.operator_address = Some(address);
+        self
+    }
+
+    pub fn voter(mut self, address: AccountAddress) -> Self {
+        self.voter_address = Some(address);
+        self
+    }
+}
+
+/// A validator created at genesis, with the accounts signing for its owner, operator and voter.
+/// Accounts which are the same address are the same account.
+#[derive(Debug)]
+pub struct GenesisValidator {
+    pub owner: Account,
+    pub operator: Account,
+    pub voter: Account,
+    pub consensus_key: bls12381::PrivateKey,
+    pub stake_amount: u64,
+}
+
+impl GenesisValidator {
+    /// Returns the address of the validator's stake pool.
+    pub fn address(&self) -> &AccountAddress {
+        self.owner.address()
+    }
+
+    /// Returns the owner, operator and voter, without duplicates.
+    pub(crate) fn accounts(&self) -> Vec<&Account> {
+        let mut accounts: Vec<&Account> = vec![];
+        for account in [&self.owner, &self.operator, &self.voter] {
+            if accounts.iter().all(|a| a.address() != account.address()) {
+                accounts.push(account);
+            }
+        }
+        accounts
+    }
+
+    /// Returns the validator in the form taken by the genesis transaction.
+    pub(crate) fn to_genesis_data(&self) -> Validator {
+        let consensus_pubkey = bls12381::PublicKey::from(&self.consensus_key);
+        Validator {
+            owner_address: *self.owner.address(),
+            operator_address: *self.operator.address(),
+            voter_address: *self.voter.address(),
+            stake_amount: self.stake_amount,
+            consensus_pubkey: consensus_pubkey.to_bytes().to_vec(),
+            proof_of_possession: bls12381::ProofOfPossession::create(&self.consensus_key)
+                .to_bytes()
+                .to_vec(),
+            network_addresses: vec![],
+            full_node_network_addresses: vec![],
+        }
+    }
+}
+
+/// Generates the accounts and keys of the validators described by `specs`. Accounts at static
+/// addresses use the genesis keypair, like `MoveHarness::new_account_at`.
+pub(crate) fn generate_validators(specs: &[ValidatorSpec]) -> Vec<GenesisValidator> {
+    let mut keys = KeyGen::from_seed(GENESIS_VALIDATOR_SEED);
+    specs
+        .iter()
+        .map(|spec| {
+            let owner = match spec.owner_address {
+                Some(address) => Account::new_genesis_account(address),
+                None => Account::new_from_seed(&mut keys),
+            };
+            let account_at = |address: Option<AccountAddress>| match address {
+                Some(address) if address != *owner.address() => {
+                    Account::new_genesis_account(address)
+                }
+                _ => owner.clone(),
+            };
+            let operator = account_at(spec.operator_address);
+            let voter = account_at(spec.voter_address);
+            GenesisValidator {
+                consensus_key: keys.generate_bls12381_private_key(),
+                owner,
+                operator,
+                voter,
+                stake_amount: spec.stake_amount,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,23 @@
+script {
+    use aptos_framework::aptos_governance;
+    use aptos_framework::governance_proposal::GovernanceProposal;
+    use aptos_framework::staking_config;
+    use aptos_framework::voting;
+
+    /// Resolves the given proposal by updating the staking config via one of the
+    /// `staking_config::update_*` functions, selected by `kind` in their order of declaration,
+    /// with the given values. Unused values are ignored.
+    fun main(proposal_id: u64, kind: u8, first: u64, second: u64) {
+        let proposal = voting::resolve<GovernanceProposal>(@aptos_framework, proposal_id);
+        let framework_signer = aptos_governance::get_signer(proposal, @aptos_framework);
+        if (kind == 0) {
+            staking_config::update_required_stake(&framework_signer, first, second);
+        } else if (kind == 1) {
+            staking_config::update_recurring_lockup_duration_secs(&framework_signer, first);
+        } else if (kind == 2) {
+            staking_config::update_rewards_rate(&framework_signer, first, second);
+        } else {
+            staking_config::update_voting_power_increase_limit(&framework_signer, first);
+        }
+    }
+}
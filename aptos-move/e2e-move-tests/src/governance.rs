@@ -0,0 +1,368 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-chain governance from Rust, following `0x1::aptos_governance`.
+
+use crate::{
+    assert_success,
+    harness::MoveHarness,
+    stake::{get_staking_config, StakingConfig},
+    summary::TxnSummary,
+    MoveResource,
+};
+use aptos::{common::types::MovePackageDir, move_tool::BuiltPackage};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    state_store::table::TableHandle,
+    transaction::{ExecutionStatus, TransactionArgument, TransactionStatus},
+};
+use cached_framework_packages::aptos_stdlib;
+use framework::natives::code::UpgradePolicy;
+use language_e2e_tests::account::Account;
+use project_root::get_project_root;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A change of the `StakingConfig`, one per `staking_config::update_*` function, applied via
+/// governance by `update_staking_config`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StakingConfigUpdate {
+    RequiredStake {
+        minimum_stake: u64,
+        maximum_stake: u64,
+    },
+    RecurringLockupDurationSecs(u64),
+    RewardsRate {
+        rewards_rate: u64,
+        rewards_rate_denominator: u64,
+    },
+    VotingPowerIncreaseLimit(u64),
+}
+
+impl StakingConfigUpdate {
+    /// Applies the update to `config`, like the corresponding Move function.
+    pub fn apply(self, config: &mut StakingConfig) {
+        match self {
+            StakingConfigUpdate::RequiredStake {
+                minimum_stake,
+                maximum_stake,
+            } => {
+                config.minimum_stake = minimum_stake;
+                config.maximum_stake = maximum_stake;
+            }
+            StakingConfigUpdate::RecurringLockupDurationSecs(seconds) => {
+                config.recurring_lockup_duration_secs = seconds;
+            }
+            StakingConfigUpdate::RewardsRate {
+                rewards_rate,
+                rewards_rate_denominator,
+            } => {
+                config.rewards_rate = rewards_rate;
+                config.rewards_rate_denominator = rewards_rate_denominator;
+            }
+            StakingConfigUpdate::VotingPowerIncreaseLimit(limit) => {
+                config.voting_power_increase_limit = limit;
+            }
+        }
+    }
+
+    /// Returns the arguments of the resolution script `update_staking_config` after the
+    /// proposal id: the index of the Move function and its values.
+    fn script_args(self) -> Vec<TransactionArgument> {
+        let (kind, first, second) = match self {
+            StakingConfigUpdate::RequiredStake {
+                minimum_stake,
+                maximum_stake,
+            } => (0, minimum_stake, maximum_stake),
+            StakingConfigUpdate::RecurringLockupDurationSecs(seconds) => (1, seconds, 0),
+            StakingConfigUpdate::RewardsRate {
+                rewards_rate,
+                rewards_rate_denominator,
+            } => (2, rewards_rate, rewards_rate_denominator),
+            StakingConfigUpdate::VotingPowerIncreaseLimit(limit) => (3, limit, 0),
+        };
+        vec![
+            TransactionArgument::U8(kind),
+            TransactionArgument::U64(first),
+            TransactionArgument::U64(second),
+        ]
+    }
+}
+
+/// Mimics `0x1::aptos_governance::GovernanceConfig`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_governance")]
+pub struct GovernanceConfig {
+    pub min_voting_threshold: u128,
+    pub required_proposer_stake: u64,
+    pub voting_duration_secs: u64,
+}
+
+/// Mimics `0x1::aptos_governance::CreateProposalEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_governance")]
+pub struct CreateProposalEvent {
+    pub proposer: AccountAddress,
+    pub stake_pool: AccountAddress,
+    pub proposal_id: u64,
+    pub execution_hash: Vec<u8>,
+    pub metadata_location: Vec<u8>,
+    pub metadata_hash: Vec<u8>,
+}
+
+/// Mimics `0x1::aptos_governance::VoteEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_governance")]
+pub struct VoteEvent {
+    pub proposal_id: u64,
+    pub voter: AccountAddress,
+    pub stake_pool: AccountAddress,
+    pub num_votes: u64,
+    pub should_pass: bool,
+}
+
+/// Mimics `0x1::aptos_governance::VotingRecords`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "aptos_governance")]
+pub struct VotingRecords {
+    pub votes: TableHandle,
+}
+
+/// Mimics `0x1::aptos_governance::RecordKey`, the key of `VotingRecords::votes`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RecordKey {
+    pub stake_pool: AccountAddress,
+    pub proposal_id: u64,
+}
+
+pub fn get_governance_config(harness: &MoveHarness) -> GovernanceConfig {
+    harness
+        .read_move_resource::<GovernanceConfig>(&CORE_CODE_ADDRESS)
+        .unwrap()
+}
+
+/// Returns whether the stake pool has been used to vote on the proposal.
+pub fn has_voted(harness: &MoveHarness, stake_pool: AccountAddress, proposal_id: u64) -> bool {
+    let records = harness
+        .read_move_resource::<VotingRecords>(&CORE_CODE_ADDRESS)
+        .unwrap();
+    harness
+        .read_table_item::<_, bool>(
+            records.votes,
+            &RecordKey {
+                stake_pool,
+                proposal_id,
+            },
+        )
+        .unwrap_or(false)
+}
+
+/// Returns the execution hash of a resolution script: only the script with this hash can resolve
+/// a proposal created with it.
+pub fn execution_hash(code: &[u8]) -> Vec<u8> {
+    HashValue::sha3_256_of(code).to_vec()
+}
+
+/// Creates a proposal, without metadata, backed by the stake pool the proposer is the voter of.
+pub fn create_proposal(
+    harness: &mut MoveHarness,
+    proposer: &Account,
+    stake_pool: AccountAddress,
+    execution_hash: Vec<u8>,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        proposer,
+        aptos_stdlib::aptos_governance_create_proposal(stake_pool, execution_hash, vec![], vec![]),
+    )
+}
+
+pub fn vote(
+    harness: &mut MoveHarness,
+    voter: &Account,
+    stake_pool: AccountAddress,
+    proposal_id: u64,
+    should_pass: bool,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        voter,
+        aptos_stdlib::aptos_governance_vote(stake_pool, proposal_id, should_pass),
+    )
+}
+
+/// Passes a proposal end to end: the proposer creates a proposal for the resolution script
+/// `code`, every voter votes for it with their stake pool, the voting period passes, and the
+/// proposer runs the script. The script receives the proposal id, followed by `args`, and is
+/// expected to resolve the proposal via `voting::resolve`. Voters are given as pairs of the
+/// delegated voter and the stake pool. Returns the output of the resolution script.
+pub fn pass_proposal(
+    harness: &mut MoveHarness,
+    proposer: &Account,
+    proposer_pool: AccountAddress,
+    voters: &[(&Account, AccountAddress)],
+    code: Vec<u8>,
+    args: Vec<TransactionArgument>,
+) -> TxnSummary {
+    let output = create_proposal(harness, proposer, proposer_pool, execution_hash(&code));
+    assert_success!(output.clone());
+    let proposal_id = harness.events_for::<CreateProposalEvent>(&output)[0].proposal_id;
+    for (voter, stake_pool) in voters {
+        assert_success!(vote(harness, voter, *stake_pool, proposal_id, true));
+    }
+    let voting_duration_secs = get_governance_config(harness).voting_duration_secs;
+    // The block applies the new time on chain, which closes the voting.
+    harness.fast_forward_secs(voting_duration_secs);
+    harness.new_block_with_metadata(None, vec![]);
+    let mut script_args = vec![TransactionArgument::U64(proposal_id)];
+    script_args.extend(args);
+    harness.run_script(proposer, code, vec![], script_args)
+}
+
+/// Updates the `StakingConfig` the way governance does, via `pass_proposal` with a resolution
+/// script which calls the `staking_config::update_*` function of `update` with the signer of
+/// 0x1. If the resolution succeeds, starts a new epoch and asserts that the config has changed
+/// exactly as given. Returns the output of the resolution script, e.g. to check that invalid
+/// values abort.
+pub fn update_staking_config(
+    harness: &mut MoveHarness,
+    proposer: &Account,
+    proposer_pool: AccountAddress,
+    voters: &[(&Account, AccountAddress)],
+    update: StakingConfigUpdate,
+) -> TxnSummary {
+    let code = MoveHarness::compile_script(
+        &PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("governance.data")
+            .join("update_staking_config"),
+    );
+    let mut expected = get_staking_config(harness);
+    update.apply(&mut expected);
+    let output = pass_proposal(
+        harness,
+        proposer,
+        proposer_pool,
+        voters,
+        code,
+        update.script_args(),
+    );
+    if output.status() == &TransactionStatus::Keep(ExecutionStatus::Success) {
+        harness.new_epoch();
+        assert_eq!(get_staking_config(harness), expected);
+    }
+    output
+}
+
+/// Upgrades the framework at `0x1` via governance: copies the `aptos-framework` package, lets
+/// `modify` change the copy, e.g. edit a module in its `sources` directory, builds it and passes
+/// a proposal whose resolution script publishes it, see `pass_proposal`. Returns the output of
+/// the resolution script.
+///
+/// There are no multi-step proposals yet, so the one resolution script embeds the code and the
+/// metadata of the whole package.
+pub fn upgrade_framework(
+    harness: &mut MoveHarness,
+    proposer: &Account,
+    proposer_pool: AccountAddress,
+    voters: &[(&Account, AccountAddress)],
+    modify: impl FnOnce(&Path),
+) -> TxnSummary {
+    let framework_dir = get_project_root().unwrap().join("aptos-move/framework");
+    let work_dir = tempfile::tempdir().expect("creating a temporary directory must succeed");
+
+    // The copy keeps depending on the stdlib packages next to the framework.
+    let package_dir = work_dir.path().join("aptos-framework");
+    copy_dir(
+        &framework_dir.join("aptos-framework/sources"),
+        &package_dir.join("sources"),
+    );
+    let manifest = fs::read_to_string(framework_dir.join("aptos-framework/Move.toml")).unwrap();
+    fs::write(
+        package_dir.join("Move.toml"),
+        manifest.replace(
+            "local = \"../",
+            &format!("local = \"{}/", framework_dir.display()),
+        ),
+    )
+    .unwrap();
+    modify(&package_dir);
+    let package = BuiltPackage::build(MovePackageDir::new(package_dir), false, false)
+        .expect("building the modified framework must succeed");
+    let metadata = package
+        .extract_metadata(UpgradePolicy::compat())
+        .expect("extracting package metadata must succeed");
+
+    let script_dir = work_dir.path().join("upgrade_framework");
+    fs::create_dir_all(script_dir.join("sources")).unwrap();
+    fs::write(
+        script_dir.join("Move.toml"),
+        format!(
+            "[package]\nname = \"upgrade_framework\"\nversion = \"0.0.0\"\n\n\
+             [dependencies]\nAptosFramework = {{ local = \"{}\" }}\n",
+            framework_dir.join("aptos-framework").display()
+        ),
+    )
+    .unwrap();
+    fs::write(
+        script_dir.join("sources").join("upgrade_framework.move"),
+        upgrade_framework_script(
+            &bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
+            &package.extract_code(),
+        ),
+    )
+    .unwrap();
+    let code = MoveHarness::compile_script(&script_dir);
+    pass_proposal(harness, proposer, proposer_pool, voters, code, vec![])
+}
+
+/// Returns the source of a resolution script which publishes the package with the given metadata
+/// and code at `0x1`.
+fn upgrade_framework_script(metadata: &[u8], code: &[Vec<u8>]) -> String {
+    let mut script = String::from(
+        "script {
+    use std::vector;
+    use aptos_framework::aptos_governance;
+    use aptos_framework::code;
+    use aptos_framework::governance_proposal::GovernanceProposal;
+    use aptos_framework::voting;
+
+    fun main(proposal_id: u64) {
+        let proposal = voting::resolve<GovernanceProposal>(@aptos_framework, proposal_id);
+        let framework_signer = aptos_governance::get_signer(proposal, @aptos_framework);
+        let code = vector::empty<vector<u8>>();
+",
+    );
+    for module in code {
+        writeln!(
+            script,
+            "        vector::push_back(&mut code, x\"{}\");",
+            hex::encode(module)
+        )
+        .unwrap();
+    }
+    writeln!(
+        script,
+        "        code::publish_package_txn(&framework_signer, x\"{}\", code);\n    }}\n}}",
+        hex::encode(metadata)
+    )
+    .unwrap();
+    script
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let target = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &target);
+        } else {
+            fs::copy(&path, &target).unwrap();
+        }
+    }
+}
@@ -1,27 +1,86 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    abort_location::describe_status,
+    account_factory::AccountFactory,
+    aggregator::Aggregator,
+    clock::Clock,
+    coin::{AptosCoin, Capabilities},
+    determinism::OutputRecord,
+    epilogue::SenderState,
+    events::{events_of, EventSequenceChecker, TransactionEvents},
+    gas_golden::GasGolden,
+    gas_price::{GasPricePolicy, RetryOutcome},
+    genesis::{generate_validators, GenesisConfig, GenesisFlavor, GenesisValidator, ValidatorSpec},
+    lint::UsageLint,
+    orphans::{OrphanedState, OrphanedStateChecker},
+    printer::render_write_set,
+    resource::MoveResource,
+    stake::{get_stake_pool, get_validator_set},
+    stats::ExecutionStats,
+    summary::TxnSummary,
+    writes::{WriteAllowlist, WriteSetDiff},
+};
 use aptos::{
     common::types::MovePackageDir,
     move_tool::{BuiltPackage, MemberId},
 };
+use aptos_api_types::{
+    AsConverter, MoveType, ScriptFunctionId, ScriptFunctionPayload,
+    TransactionPayload as ApiTransactionPayload,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    PrivateKey,
+};
+use aptos_keygen::KeyGen;
 use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    state_store::state_key::StateKey,
-    transaction::{ScriptFunction, SignedTransaction, TransactionPayload, TransactionStatus},
+    account_config::{
+        aptos_test_root_address, AccountResource, CoinStoreResource, CORE_CODE_ADDRESS,
+    },
+    chain_id::ChainId,
+    contract_event::ContractEvent,
+    event::EventHandle,
+    on_chain_config::{ConfigurationResource, GasSchedule},
+    stake_pool::StakePool,
+    state_store::{state_key::StateKey, table::TableHandle},
+    transaction::{
+        authenticator::AuthenticationKey, ExecutionStatus, Script, ScriptFunction,
+        SignedTransaction, Transaction, TransactionArgument, TransactionOutput, TransactionPayload,
+        TransactionStatus,
+    },
+    utility_coin::APTOS_COIN_TYPE,
+    write_set::{WriteOp, WriteSetMut},
+};
+use aptos_vm::{
+    data_cache::AsMoveResolver, parallel_executor::SchedulerStats, AptosVM, VMExecutor,
 };
 use cached_framework_packages::aptos_stdlib;
 use framework::natives::code::UpgradePolicy;
 use language_e2e_tests::{
-    account::{Account, AccountData},
+    account::{Account, AccountData, TransactionBuilder},
+    data_store::FakeDataStore,
     executor::FakeExecutor,
+    gas_costs::TXN_RESERVED,
+    golden_outputs::GoldenRedactions,
+};
+use move_deps::move_core_types::{
+    ident_str,
+    language_storage::{ResourceKey, StructTag, TypeTag},
+    move_resource::MoveStructType,
 };
-use move_deps::move_core_types::language_storage::{ResourceKey, StructTag, TypeTag};
 use project_root::get_project_root;
-use serde::de::DeserializeOwned;
-use std::collections::BTreeMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Once};
+use storage_interface::mock::MockDbReaderWriter;
+use tracing::{debug, debug_span, info_span, Span};
 
 /// A simple test harness for defining Move e2e tests.
 ///
@@ -43,64 +102,970 @@ pub struct MoveHarness {
     pub executor: FakeExecutor,
     /// The current transaction sequence number, by account address.
     txn_seq_no: BTreeMap<AccountAddress, u64>,
+    /// Verifies event sequence numbers and event ordering of every executed transaction, unless
+    /// disabled.
+    event_checker: Option<EventSequenceChecker>,
+    /// Whether every user transaction is executed a second time to check that its events are
+    /// deterministic.
+    rerun_events: bool,
+    /// The allowlist of state the next transaction is expected to write, if any.
+    expected_writes: Option<WriteAllowlist>,
+    /// Records of all transaction outputs, if recording is enabled.
+    output_records: Option<Vec<OutputRecord>>,
+    /// The state keys written by any transaction, if recording is enabled.
+    written_state: Option<BTreeSet<StateKey>>,
+    /// Tracks state owned by deleted resources, which is checked when the harness is dropped,
+    /// unless disabled for fuzzing.
+    orphan_checker: Option<OrphanedStateChecker>,
+    /// The span all harness operations are recorded under, carrying the name of the test.
+    span: Span,
+    /// The total amount of Aptos coins given to accounts via `fund`.
+    total_funded: u128,
+    /// The transaction fees burned since genesis, see `total_fees_burned`.
+    fees_burned: u128,
+    /// Whether the effects of the epilogue are verified after every transaction.
+    verify_epilogues: bool,
+    /// Tracks unused accounts and stake pools, if enabled.
+    usage_lint: Option<RefCell<UsageLint>>,
+    /// Whether the write set of every transaction is printed.
+    print_write_sets: bool,
+    /// Hooks run before every block, see `before_block`.
+    before_block_hooks: Vec<BlockHook>,
+    /// Hooks run after every block, see `after_block`.
+    after_block_hooks: Vec<BlockHook>,
+    /// How far `new_epoch` fast forwards, i.e. the epoch interval if configured at genesis.
+    new_epoch_secs: u64,
+    /// The epoch interval set by `set_auto_epoch`, in microseconds.
+    auto_epoch_interval_usecs: Option<u64>,
+    /// The core resources account, see `root_account`.
+    root_account: Account,
+    /// The validators created at genesis, if configured via `new_with_validators`.
+    genesis_validators: Vec<GenesisValidator>,
+    /// The accounts created at genesis, if configured via `new_with_genesis`.
+    genesis_accounts: Vec<Account>,
+    /// The chain ID set at genesis, which the transactions created by the harness are for.
+    chain_id: ChainId,
+    /// The expiration timestamp of transactions created by the harness, in seconds, if set via
+    /// `set_expiration_timestamp_secs`.
+    expiration_timestamp_secs: Option<u64>,
+    /// The events emitted by the last transaction executed, including block prologues.
+    last_events: Vec<ContractEvent>,
+    /// The state written by the last transaction run, excluding block prologues.
+    last_writes: WriteSetDiff,
+    /// Records the gas used by every transaction, if golden files are enabled.
+    gas_golden: Option<GasGolden>,
+    /// The execution statistics of the last transaction executed, including block prologues.
+    last_txn_stats: ExecutionStats,
+    /// The execution statistics of all transactions executed since genesis.
+    total_stats: ExecutionStats,
+}
+
+/// A hook run around every block, see `MoveHarness::before_block`.
+pub type BlockHook = Box<dyn FnMut(&mut MoveHarness)>;
+
+/// Mimics `0x1::coin::CoinInfo`
+#[derive(Serialize, Deserialize)]
+struct CoinInfo {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    supply: Option<u128>,
+}
+
+/// Mimics `0x1::block::BlockResource`
+#[derive(Serialize, Deserialize)]
+struct BlockResource {
+    height: u64,
+    epoch_interval: u64,
+    new_block_events: EventHandle,
+}
+
+/// Mimics `0x1::timestamp::CurrentTimeMicroseconds`
+#[derive(Serialize, Deserialize)]
+struct CurrentTimeMicroseconds {
+    microseconds: u64,
+}
+
+/// A reason for the prologue to discard a transaction, see `MoveHarness::create_discarded`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiscardReason {
+    /// The sequence number was already used, which is `SEQUENCE_NUMBER_TOO_OLD`.
+    StaleSequenceNumber,
+    /// The sender cannot pay the maximum fee at the maximum gas unit price, which is
+    /// `INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE`. Requires a sender with a low balance.
+    InsufficientBalanceForGas,
+    /// The transaction expires at the current on-chain time, which is `TRANSACTION_EXPIRED`.
+    Expired,
+    /// The transaction is for another chain, which is `BAD_CHAIN_ID`.
+    WrongChainId,
 }
 
+/// On-chain timestamps are in microseconds, while lockups and epoch durations are configured in
+/// seconds.
+const MICROS_PER_SEC: u64 = 1_000_000;
+
+/// How far `new_epoch` fast forwards by default: long enough to start an epoch and, on test
+/// genesis, to expire lockups.
+const DEFAULT_NEW_EPOCH_SECS: u64 = 7200;
+
+/// Enables `MoveHarness::verify_epilogues` for all harnesses if set.
+const VERIFY_EPILOGUES_ENV: &str = "E2E_VERIFY_EPILOGUES";
+
+/// Enables `MoveHarness::verify_event_determinism` for all harnesses if set.
+const RERUN_EVENTS_ENV: &str = "E2E_RERUN_EVENTS";
+
+/// Enables `MoveHarness::print_write_sets` for all harnesses if set.
+const PRINT_WRITE_SETS_ENV: &str = "E2E_PRINT_WRITE_SETS";
+
+/// Enables `MoveHarness::enable_usage_lint` for all harnesses if set.
+const USAGE_LINT_ENV: &str = "E2E_USAGE_LINT";
+
+static INIT_LOGGER: Once = Once::new();
+
 impl MoveHarness {
     /// Creates a new harness.
     pub fn new() -> Self {
-        Self {
-            executor: FakeExecutor::from_fresh_genesis(),
-            txn_seq_no: BTreeMap::default(),
-        }
+        Self::new_with_executor(FakeExecutor::from_fresh_genesis())
+    }
+
+    /// Creates a harness for fuzzing, which executes transactions as fast as possible and never
+    /// panics for reasons other than the transactions themselves: blocks only run sequentially,
+    /// transactions are not re-executed to check their events, orphaned state is not tracked, and
+    /// the environment variables enabling extra checks are ignored. Genesis is the standard one,
+    /// which is generated only once per process. Golden files are never written, as there is no
+    /// `enable_golden!` for a fuzz target.
+    pub fn new_for_fuzzing() -> Self {
+        let mut harness =
+            Self::new_with_executor(FakeExecutor::from_fresh_genesis().set_not_parallel());
+        harness.event_checker = None;
+        harness.rerun_events = false;
+        harness.orphan_checker = None;
+        harness.verify_epilogues = false;
+        harness.usage_lint = None;
+        harness.print_write_sets = false;
+        harness
+    }
+
+    /// Creates a harness for benchmarks of entry functions, e.g. `benches/harness_benches.rs`,
+    /// which adds as little overhead as possible to the transactions: it has the settings of
+    /// `new_for_fuzzing`, with the standard genesis generated once per process, and golden files
+    /// are only written if enabled via `enable_golden!`, which benchmarks should not do.
+    pub fn new_for_benchmarking() -> Self {
+        Self::new_for_fuzzing()
     }
 
     pub fn new_mainnet() -> Self {
-        Self {
-            executor: FakeExecutor::from_mainnet_genesis(),
-            txn_seq_no: BTreeMap::default(),
-        }
+        Self::new_with_executor(FakeExecutor::from_mainnet_genesis())
+    }
+
+    /// Creates a new harness with the genesis parameters of testnet, which for instance does not
+    /// allow new validators to join.
+    pub fn new_testnet() -> Self {
+        Self::new_with_executor(FakeExecutor::from_testnet_genesis())
     }
 
     pub fn new_no_parallel() -> Self {
+        Self::new_with_executor(FakeExecutor::from_fresh_genesis().set_not_parallel())
+    }
+
+    /// Creates a new harness whose parallel executor uses `concurrency_level` threads.
+    pub fn new_with_concurrency_level(concurrency_level: usize) -> Self {
+        Self::new_with_executor(
+            FakeExecutor::from_fresh_genesis().set_concurrency_level(concurrency_level),
+        )
+    }
+
+    /// Creates a new harness whose test genesis has the given validators, instead of the single
+    /// standard validator. The validators are active right away, with indices in the order
+    /// given. Their accounts are available via `genesis_validators`.
+    pub fn new_with_validators(validators: &[ValidatorSpec]) -> Self {
+        assert!(
+            !validators.is_empty(),
+            "genesis needs at least one validator"
+        );
+        Self::new_with_genesis(GenesisConfig::test().validators(validators))
+    }
+
+    /// Creates a new harness with custom validators and accounts at genesis, see `GenesisConfig`.
+    /// Accounts are created right after the genesis transaction, as genesis itself cannot create
+    /// them, and are available via `genesis_accounts`.
+    pub fn new_with_genesis(config: GenesisConfig) -> Self {
+        let genesis_configuration = config.genesis_configuration();
+        let mut specs = config.validator_specs().to_vec();
+        if specs.is_empty() && genesis_configuration.is_some() {
+            // Only the standard genesis comes with the standard validator.
+            specs.push(ValidatorSpec::new(config.flavor().standard_stake()));
+        }
+        let validators = generate_validators(&specs);
+        let genesis_data: Vec<_> = validators
+            .iter()
+            .map(GenesisValidator::to_genesis_data)
+            .collect();
+        let executor = match genesis_configuration {
+            Some(genesis_configuration) => FakeExecutor::from_genesis_with_configuration(
+                &genesis_data,
+                &genesis_configuration,
+                config.genesis_chain_id(),
+                config.framework_modules(),
+            ),
+            None => match (config.flavor(), genesis_data.is_empty()) {
+                (GenesisFlavor::Test, true) => FakeExecutor::from_fresh_genesis(),
+                (GenesisFlavor::Test, false) => {
+                    FakeExecutor::from_fresh_genesis_with_validators(&genesis_data)
+                }
+                (GenesisFlavor::Mainnet, true) => FakeExecutor::from_mainnet_genesis(),
+                (GenesisFlavor::Mainnet, false) => {
+                    FakeExecutor::from_mainnet_genesis_with_validators(&genesis_data)
+                }
+            },
+        };
+        let mut harness = Self::new_with_executor(executor);
+        if let Some(seconds) = config.epoch_interval_override_secs() {
+            harness.new_epoch_secs = seconds;
+        }
+        harness.chain_id = config.genesis_chain_id();
+        for validator in &validators {
+            for account in validator.accounts() {
+                // Genesis creates the accounts with an authentication key matching their address,
+                // which static addresses don't have a key for.
+                harness.set_auth_key(account.address(), &account.pubkey);
+                harness.txn_seq_no.insert(*account.address(), 0);
+            }
+        }
+        harness.genesis_validators = validators;
+        for (address, balance) in config.account_balances() {
+            let account = harness
+                .account()
+                .address(*address)
+                .balance(0)
+                .sequence_number(0)
+                .build();
+            harness.patch_balance(account.address(), *balance);
+            harness.genesis_accounts.push(account);
+        }
+        harness
+    }
+
+    /// Returns the validators created at genesis by `new_with_validators`, in the order given.
+    pub fn genesis_validators(&self) -> &[GenesisValidator] {
+        &self.genesis_validators
+    }
+
+    /// Returns the accounts created at genesis by `new_with_genesis`, in the order given.
+    pub fn genesis_accounts(&self) -> &[Account] {
+        &self.genesis_accounts
+    }
+
+    /// Returns the chain ID of the harness, which is set at genesis via `GenesisConfig::chain_id`.
+    /// Transactions created by the harness are for this chain, while transactions built directly
+    /// via `Account::transaction` need `.chain_id(harness.chain_id())` to pass the prologue.
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Sets the expiration timestamp, in seconds, of the transactions created by the harness from
+    /// now on, e.g. to `harness.get_on_chain_time_usecs() / 1_000_000 + ttl_secs` for a time to
+    /// live. `None` restores the default, which is far in the future. See `run_expired`.
+    pub fn set_expiration_timestamp_secs(&mut self, expiration_timestamp_secs: Option<u64>) {
+        self.expiration_timestamp_secs = expiration_timestamp_secs;
+    }
+
+    fn new_with_executor(executor: FakeExecutor) -> Self {
+        // Logs harness operations if `RUST_LOG` is set.
+        INIT_LOGGER.call_once(aptos_logger::Logger::init_for_testing);
+        // The test runner names each thread after the test it runs.
+        let test = std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string();
         Self {
-            executor: FakeExecutor::from_fresh_genesis().set_not_parallel(),
+            executor,
             txn_seq_no: BTreeMap::default(),
+            event_checker: Some(EventSequenceChecker::default()),
+            rerun_events: std::env::var(RERUN_EVENTS_ENV).is_ok(),
+            expected_writes: None,
+            output_records: None,
+            written_state: None,
+            orphan_checker: Some(OrphanedStateChecker::default()),
+            span: info_span!("move_harness", test = %test),
+            total_funded: 0,
+            fees_burned: 0,
+            verify_epilogues: std::env::var(VERIFY_EPILOGUES_ENV).is_ok(),
+            usage_lint: std::env::var(USAGE_LINT_ENV).is_ok().then(RefCell::default),
+            print_write_sets: std::env::var(PRINT_WRITE_SETS_ENV).is_ok(),
+            before_block_hooks: vec![],
+            after_block_hooks: vec![],
+            new_epoch_secs: DEFAULT_NEW_EPOCH_SECS,
+            auto_epoch_interval_usecs: None,
+            root_account: Account::new_aptos_root(),
+            genesis_validators: vec![],
+            genesis_accounts: vec![],
+            chain_id: ChainId::test(),
+            expiration_timestamp_secs: None,
+            last_events: vec![],
+            last_writes: WriteSetDiff::default(),
+            gas_golden: None,
+            last_txn_stats: ExecutionStats::default(),
+            total_stats: ExecutionStats::default(),
         }
     }
 
+    /// Registers a hook which is run before every block, i.e. before `new_epoch`,
+    /// `new_block_with_metadata` and `run_block`. Hooks may run transactions themselves, e.g. to
+    /// update an oracle, but blocks started from within a hook do not trigger hooks again.
+    pub fn before_block(&mut self, hook: impl FnMut(&mut MoveHarness) + 'static) {
+        self.before_block_hooks.push(Box::new(hook))
+    }
+
+    /// Registers a hook which is run after every block, see `before_block`.
+    pub fn after_block(&mut self, hook: impl FnMut(&mut MoveHarness) + 'static) {
+        self.after_block_hooks.push(Box::new(hook))
+    }
+
+    /// Runs the hooks selected by `hooks`, in the order they were registered.
+    fn run_block_hooks(&mut self, hooks: fn(&mut MoveHarness) -> &mut Vec<BlockHook>) {
+        // The hooks are taken out while running, so that blocks started by a hook don't recurse.
+        let mut running = std::mem::take(hooks(self));
+        for hook in &mut running {
+            hook(self);
+        }
+        let registered_meanwhile = std::mem::replace(hooks(self), running);
+        hooks(self).extend(registered_meanwhile);
+    }
+
+    /// Enables reporting accounts which are created but never used, and stake pools which are
+    /// set up but never asserted, when the harness is dropped. See `UsageLint`. This can also be
+    /// enabled for all harnesses by setting the `E2E_USAGE_LINT` environment variable.
+    pub fn enable_usage_lint(&mut self) {
+        self.usage_lint.get_or_insert_with(RefCell::default);
+    }
+
+    /// Returns the warnings of the usage lint so far, if enabled.
+    pub fn usage_lint_warnings(&self) -> Vec<String> {
+        self.usage_lint
+            .as_ref()
+            .map_or(vec![], |lint| lint.borrow().warnings())
+    }
+
+    /// Enables printing the write set of every transaction, including block prologues, in
+    /// human-readable form, see `render_write_set`. This can also be enabled for all harnesses by
+    /// setting the `E2E_PRINT_WRITE_SETS` environment variable.
+    pub fn print_write_sets(&mut self) {
+        self.print_write_sets = true
+    }
+
+    /// Prints the write set of the output if enabled. `before` is the state the write set
+    /// applies to.
+    fn maybe_print_write_set(&self, before: &FakeDataStore, output: &TransactionOutput) {
+        if self.print_write_sets {
+            println!(
+                "write set ({:?}):\n{}",
+                output.status(),
+                render_write_set(before, output.write_set())
+            );
+        }
+    }
+
+    /// Enables the verification of epilogue effects after every transaction run via `run_raw`
+    /// (and thus `run`), see `SenderState::check_epilogue`. This can also be enabled for all
+    /// harnesses by setting the `E2E_VERIFY_EPILOGUES` environment variable.
+    pub fn verify_epilogues(&mut self) {
+        self.verify_epilogues = true
+    }
+
+    /// Enables executing every transaction run via `run_raw` (and thus `run`) a second time on
+    /// the same state, to check that it emits exactly the same events. This doubles the execution
+    /// time, so it is off by default, but can be enabled for all harnesses by setting the
+    /// `E2E_RERUN_EVENTS` environment variable. Has no effect if event checks are disabled.
+    pub fn verify_event_determinism(&mut self) {
+        self.rerun_events = true
+    }
+
+    /// Disables the verification of event sequence numbers and event ordering. This is useful
+    /// for tests which deliberately manipulate event handles.
+    pub fn disable_event_checks(&mut self) {
+        self.event_checker = None
+    }
+
     /// Creates an account for the given static address. This address needs to be static so
     /// we can load regular Move code to there without need to rewrite code addresses.
     pub fn new_account_at(&mut self, addr: AccountAddress) -> Account {
-        // The below will use the genesis keypair but that should be fine.
-        let acc = Account::new_genesis_account(addr);
-        // Mint the account 10M Aptos coins (with 8 decimals).
-        let data = AccountData::with_account(acc, 1_000_000_000_000_000, 10);
-        self.txn_seq_no.insert(addr, 10);
-        self.executor.add_account_data(&data);
-        data.account().clone()
+        self.account().address(addr).build()
     }
 
-    /// Runs a signed transaction. On success, applies the write set.
-    pub fn run(&mut self, txn: SignedTransaction) -> TransactionStatus {
-        let output = self.executor.execute_transaction(txn);
+    /// Creates a funded multi-ed25519 account with `num_keys` keys, `threshold` of which need to
+    /// sign. Transactions are signed with the first `threshold` keys, see
+    /// `Account::with_multisig_signers` to sign with others.
+    pub fn new_multisig_account(&mut self, threshold: u8, num_keys: u8) -> Account {
+        self.account().multisig(threshold, num_keys).build()
+    }
+
+    /// Creates `count` accounts whose keys, and with them their addresses, are derived from
+    /// `seed`. The same seed yields the same accounts, so tests with many accounts stay
+    /// reproducible, e.g. for golden files.
+    pub fn new_accounts(&mut self, seed: u64, count: usize) -> Vec<Account> {
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+        let mut keys = KeyGen::from_seed(seed_bytes);
+        (0..count)
+            .map(|_| self.account().key_from(&mut keys).build())
+            .collect()
+    }
+
+    /// Returns a factory for an account with custom initial conditions, e.g.
+    /// `harness.account().address(addr).balance(1_000_000).multisig(2, 3).build()`.
+    pub fn account(&mut self) -> AccountFactory {
+        AccountFactory::new(self)
+    }
+
+    /// Adds an account which already exists on chain, e.g. a resource account created by a
+    /// transaction, so the harness can run its transactions. Its sequence number is read from
+    /// chain.
+    pub fn add_existing_account(&mut self, account: &Account) {
+        let sequence_number = self
+            .executor
+            .read_resource::<AccountResource>(account.address())
+            .unwrap_or_else(|| panic!("account {} must exist", account.address()))
+            .sequence_number();
+        self.txn_seq_no.insert(*account.address(), sequence_number);
+    }
+
+    /// Adds the account to the state, replacing any existing account at its address.
+    pub(crate) fn add_account_data(&mut self, data: &AccountData) {
+        let addr = *data.address();
+        self.txn_seq_no.insert(addr, data.sequence_number());
+        if let Some(lint) = &self.usage_lint {
+            lint.borrow_mut().account_created(addr);
+        }
+        self.executor.add_account_data(data);
+        if let Some(checker) = &mut self.event_checker {
+            // The account's event handles have been reset.
+            checker.forget_address(&addr);
+        }
+    }
+
+    /// Enables minting Aptos coins under mainnet genesis, by installing the core resources
+    /// account and granting it the mint capability, as under test genesis. Afterwards, `fund`
+    /// mints coins via transactions instead of patching state. Has no effect under test genesis.
+    pub fn enable_test_mint(&mut self) {
+        let root_address = aptos_test_root_address();
+        if self.exists_resource(&root_address, Capabilities::struct_tag()) {
+            return;
+        }
+        if self
+            .executor
+            .read_resource::<AccountResource>(&root_address)
+            .is_none()
+        {
+            self.root_account = self.account().address(root_address).build();
+        }
+        let write_set = WriteSetMut::new(vec![(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                root_address,
+                Capabilities::struct_tag(),
+            ))),
+            WriteOp::Value(bcs::to_bytes(&Capabilities::default()).expect("Capabilities has BCS")),
+        )])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+    }
+
+    /// Funds the account at `addr`, which needs to have a coin store, with `amount` Aptos coins.
+    ///
+    /// Under test genesis, or if `enable_test_mint` was called, the coins are minted by the core
+    /// resources account. Otherwise, the coin store and the coin supply are patched directly.
+    pub fn fund(&mut self, addr: &AccountAddress, amount: u64) {
+        let root_address = aptos_test_root_address();
+        let can_mint = self.exists_resource(&root_address, Capabilities::struct_tag());
+        if can_mint {
+            let root = self.root_account();
+            let status =
+                self.run_transaction_payload(&root, aptos_stdlib::aptos_coin_mint(*addr, amount));
+            assert_eq!(
+                status,
+                TransactionStatus::Keep(ExecutionStatus::Success),
+                "minting coins failed: {}",
+                describe_status(status.status())
+            );
+        } else {
+            self.patch_balance(addr, amount);
+        }
+        self.total_funded += amount as u128;
+    }
+
+    /// Adds `amount` Aptos coins to the coin store at `addr` and to the coin supply, by patching
+    /// state instead of minting.
+    fn patch_balance(&mut self, addr: &AccountAddress, amount: u64) {
+        let store = self
+            .executor
+            .read_resource::<CoinStoreResource>(addr)
+            .expect("account to fund must have a coin store");
+        let store = CoinStoreResource::new(
+            store.coin() + amount,
+            store.frozen(),
+            store.deposit_events().clone(),
+            store.withdraw_events().clone(),
+        );
+        let info_tag = Self::aptos_coin_info_tag();
+        let mut info = self
+            .read_resource::<CoinInfo>(&CORE_CODE_ADDRESS, info_tag.clone())
+            .expect("Aptos coin must be initialized");
+        if let Some(supply) = &mut info.supply {
+            *supply += amount as u128;
+        }
+        let resource_key = |addr: AccountAddress, tag: StructTag| {
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                addr, tag,
+            )))
+        };
+        let write_set = WriteSetMut::new(vec![
+            (
+                resource_key(*addr, CoinStoreResource::struct_tag()),
+                WriteOp::Value(bcs::to_bytes(&store).expect("CoinStore has BCS")),
+            ),
+            (
+                resource_key(CORE_CODE_ADDRESS, info_tag),
+                WriteOp::Value(bcs::to_bytes(&info).expect("CoinInfo has BCS")),
+            ),
+        ])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+    }
+
+    fn aptos_coin_info_tag() -> StructTag {
+        StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("coin").to_owned(),
+            name: ident_str!("CoinInfo").to_owned(),
+            type_params: vec![APTOS_COIN_TYPE.clone()],
+        }
+    }
+
+    /// Returns the supply of Aptos coins, as tracked by `0x1::coin::CoinInfo`. Coins are minted
+    /// by funding accounts and by rewards at every epoch change, and burned by transaction fees,
+    /// see `total_fees_burned`.
+    pub fn total_supply(&self) -> u128 {
+        self.read_resource::<CoinInfo>(&CORE_CODE_ADDRESS, Self::aptos_coin_info_tag())
+            .expect("Aptos coin must be initialized")
+            .supply
+            .expect("the supply of Aptos coins must be tracked")
+    }
+
+    /// Returns the transaction fees burned by the transactions run by this harness, added up.
+    /// The epilogue burns the whole fee of every kept transaction, i.e. its
+    /// `TxnSummary::fee_charged`, and none is collected, e.g. for the block proposer.
+    pub fn total_fees_burned(&self) -> u128 {
+        self.fees_burned
+    }
+
+    /// Returns the core resources account, which under test genesis can mint coins and sign
+    /// administrative operations. Under mainnet genesis the account does not exist, so its
+    /// transactions are discarded, unless `enable_test_mint` installed it.
+    pub fn root_account(&mut self) -> Account {
+        let root_address = *self.root_account.address();
+        if !self.txn_seq_no.contains_key(&root_address) {
+            let sequence_number = self
+                .executor
+                .read_resource::<AccountResource>(&root_address)
+                .map_or(0, |account| account.sequence_number());
+            self.txn_seq_no.insert(root_address, sequence_number);
+        }
+        self.root_account.clone()
+    }
+
+    /// Replaces the key of the core resources account, such that transactions signed with the
+    /// genesis key are rejected. Returns the account signing with the new key, which is also
+    /// returned by `root_account` from now on.
+    pub fn set_root_key(&mut self, private_key: Ed25519PrivateKey) -> Account {
+        let root_address = *self.root_account.address();
+        let public_key = private_key.public_key();
+        self.set_auth_key(&root_address, &public_key);
+        self.root_account.rotate_key(private_key, public_key);
+        self.root_account()
+    }
+
+    /// Rotates the authentication key of `account` to the one of `private_key` by running
+    /// `0x1::account::rotate_authentication_key`, signed with the current key. On success,
+    /// `account` signs subsequent transactions with the new key. The framework rotates to any
+    /// well-formed key, without a challenge proving ownership of the new key, so there is no
+    /// such flow to drive.
+    pub fn rotate_auth_key(
+        &mut self,
+        account: &mut Account,
+        private_key: Ed25519PrivateKey,
+    ) -> TxnSummary {
+        let public_key = private_key.public_key();
+        let output = self.run_transaction_payload(
+            account,
+            aptos_stdlib::account_rotate_authentication_key(
+                AuthenticationKey::ed25519(&public_key).to_vec(),
+            ),
+        );
+        if output.status() == &TransactionStatus::Keep(ExecutionStatus::Success) {
+            account.rotate_key(private_key, public_key);
+        }
+        output
+    }
+
+    /// Replaces the authentication key of the existing account at `addr` by the one of
+    /// `public_key`.
+    fn set_auth_key(&mut self, addr: &AccountAddress, public_key: &Ed25519PublicKey) {
+        let account = self
+            .executor
+            .read_resource::<AccountResource>(addr)
+            .unwrap_or_else(|| panic!("account {} must exist", addr));
+        let account = AccountResource::new(
+            account.sequence_number(),
+            AuthenticationKey::ed25519(public_key).to_vec(),
+            account.coin_register_events().clone(),
+        );
+        let write_set = WriteSetMut::new(vec![(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                *addr,
+                AccountResource::struct_tag(),
+            ))),
+            WriteOp::Value(bcs::to_bytes(&account).expect("AccountResource has BCS")),
+        )])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+    }
+
+    /// Returns the total amount of Aptos coins given to accounts via `fund`.
+    pub fn total_funded(&self) -> u128 {
+        self.total_funded
+    }
+
+    /// Starts recording the gas usage and output hash of every transaction executed by this
+    /// harness, including block prologues.
+    pub fn record_outputs(&mut self) {
+        self.output_records = Some(vec![])
+    }
+
+    /// Returns the records collected since recording was enabled.
+    pub fn take_output_records(&mut self) -> Vec<OutputRecord> {
+        self.output_records
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Starts recording the state keys written by every transaction executed by this harness,
+    /// including block prologues.
+    pub fn record_written_state(&mut self) {
+        self.written_state = Some(BTreeSet::new())
+    }
+
+    /// Returns the state keys written since recording was enabled.
+    pub fn take_written_state(&mut self) -> BTreeSet<StateKey> {
+        self.written_state
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Returns the state left behind by resources deleted so far, and stops tracking it. Unless
+    /// this is called, orphaned state fails the test when the harness is dropped.
+    pub fn check_orphaned_state(&mut self) -> Vec<OrphanedState> {
+        match &mut self.orphan_checker {
+            Some(checker) => std::mem::take(checker).check(self.executor.get_state_view()),
+            None => vec![],
+        }
+    }
+
+    /// Expects the next transaction run by this harness to only write state in the given
+    /// allowlist. The transaction fails the test if it writes anything else.
+    pub fn expect_writes(&mut self, allowlist: WriteAllowlist) {
+        self.expected_writes = Some(allowlist)
+    }
+
+    /// Runs a signed transaction and returns its full output. On success, applies the write set.
+    pub fn run_raw(&mut self, txn: SignedTransaction) -> TransactionOutput {
+        let sender = txn.sender();
+        if let Some(lint) = &self.usage_lint {
+            lint.borrow_mut().account_used(sender);
+        }
+        let span = debug_span!(
+            parent: &self.span,
+            "run_transaction",
+            sender = %sender,
+            sequence_number = txn.sequence_number()
+        )
+        .entered();
+        let sender_before = if self.verify_epilogues {
+            SenderState::read(&self.executor, &sender)
+        } else {
+            None
+        };
+        let output = self.executor.execute_transaction(txn.clone());
+        if let Some(allowlist) = self.expected_writes.take() {
+            allowlist.check(&sender, self.executor.get_state_view(), output.write_set());
+        }
+        if self.rerun_events && self.event_checker.is_some() {
+            // Event ordering must be deterministic, so running the transaction again on the
+            // same state must produce exactly the same events.
+            let rerun_output =
+                self.execute_without_logging(Transaction::UserTransaction(txn.clone()));
+            assert_eq!(
+                output.events(),
+                rerun_output.events(),
+                "events differ between executions of the same transaction"
+            );
+        }
+        self.check_output(&output);
+        self.record_fee(txn.gas_unit_price(), &output);
+        if let Some(golden) = &mut self.gas_golden {
+            golden.record(txn.payload(), output.gas_used());
+        }
+        self.maybe_print_write_set(self.executor.get_state_view(), &output);
+        self.last_writes = WriteSetDiff::new(self.executor.get_state_view(), output.write_set());
         if matches!(output.status(), TransactionStatus::Keep(_)) {
             self.executor.apply_write_set(output.write_set());
         }
-        output.status().to_owned()
+        if let Some(before) = sender_before {
+            let after = SenderState::read(&self.executor, &sender)
+                .expect("sender must still exist after its transaction");
+            before.check_epilogue(&after, &txn, &output);
+        }
+        drop(span);
+        self.maybe_auto_epoch();
+        output
+    }
+
+    /// Runs a signed transaction. On success, applies the write set.
+    pub fn run(&mut self, txn: SignedTransaction) -> TxnSummary {
+        let sender = txn.sender();
+        let gas_unit_price = txn.gas_unit_price();
+        let output = self.run_raw(txn);
+        self.summarize(&sender, gas_unit_price, &output)
+    }
+
+    /// Replays the signed transactions in the file at `path`, a BCS serialized
+    /// `Vec<SignedTransaction>`, e.g. captured from a network to turn an incident into a
+    /// deterministic regression test. The transactions run one after another against the current
+    /// state, so their senders must exist with the keys and sequence numbers they were signed with.
+    pub fn replay_from_file(&mut self, path: &Path) -> Vec<TxnSummary> {
+        let bytes =
+            std::fs::read(path).unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+        let txns: Vec<SignedTransaction> = bcs::from_bytes(&bytes).unwrap_or_else(|e| {
+            panic!(
+                "{} is not a BCS list of transactions: {}",
+                path.display(),
+                e
+            )
+        });
+        txns.into_iter()
+            .map(|txn| {
+                let sender = txn.sender();
+                let summary = self.run(txn);
+                // Keep transactions created by the harness in sync with the replayed ones.
+                if let (Some(seq_no), Some(sequence_number)) =
+                    (self.txn_seq_no.get_mut(&sender), summary.sequence_number())
+                {
+                    *seq_no = sequence_number;
+                }
+                summary
+            })
+            .collect()
+    }
+
+    /// Writes signed transactions to the file at `path` in the format read by `replay_from_file`.
+    pub fn write_transactions_to_file(path: &Path, txns: &[SignedTransaction]) {
+        let bytes = bcs::to_bytes(txns).expect("transactions must serialize");
+        std::fs::write(path, bytes)
+            .unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+    }
+
+    /// Summarizes the output of a transaction of `sender` which has been applied.
+    fn summarize(
+        &self,
+        sender: &AccountAddress,
+        gas_unit_price: u64,
+        output: &TransactionOutput,
+    ) -> TxnSummary {
+        let sequence_number = self
+            .executor
+            .read_resource::<AccountResource>(sender)
+            .map(|account| account.sequence_number());
+        TxnSummary::new(output, gas_unit_price, sequence_number)
     }
 
     /// Runs a block of signed transactions. On success, applies the write set.
-    pub fn run_block(&mut self, txn_block: Vec<SignedTransaction>) -> Vec<TransactionStatus> {
+    pub fn run_block(&mut self, txn_block: Vec<SignedTransaction>) -> Vec<TxnSummary> {
+        self.run_block_with(None, txn_block)
+    }
+
+    /// Runs a block of signed transactions after a block prologue with the given metadata, in a
+    /// single block like on chain, e.g. to test the ordering of transactions within a block. Like
+    /// `new_block_with_metadata`, fast forwards by a second for the new block. If the prologue
+    /// starts a new epoch, the transactions are not executed but need to be retried. Returns the
+    /// summaries of the transactions, not including the prologue.
+    pub fn run_block_with_metadata(
+        &mut self,
+        proposer_index: Option<u32>,
+        failed_proposer_indices: Vec<u32>,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Vec<TxnSummary> {
+        self.run_block_with(Some((proposer_index, failed_proposer_indices)), txn_block)
+    }
+
+    /// Runs a block of signed transactions, preceded by a block prologue if its proposer index
+    /// and failed proposer indices are given.
+    fn run_block_with(
+        &mut self,
+        prologue: Option<(Option<u32>, Vec<u32>)>,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Vec<TxnSummary> {
+        self.run_block_hooks(|h| &mut h.before_block_hooks);
+        if let Some(lint) = &self.usage_lint {
+            for txn in &txn_block {
+                lint.borrow_mut().account_used(txn.sender());
+            }
+        }
+        let span = debug_span!(parent: &self.span, "run_block", size = txn_block.len()).entered();
+        let senders: Vec<_> = txn_block
+            .iter()
+            .map(|txn| (txn.sender(), txn.gas_unit_price(), txn.payload().clone()))
+            .collect();
         let mut result = vec![];
-        for output in self.executor.execute_block(txn_block).unwrap() {
+        let outputs = match prologue {
+            Some((proposer_index, failed_proposer_indices)) => {
+                self.fast_forward_secs(1);
+                let metadata = self
+                    .executor
+                    .block_metadata(proposer_index, failed_proposer_indices);
+                let mut outputs = self
+                    .executor
+                    .execute_block_with_metadata(metadata, txn_block)
+                    .unwrap()
+                    .into_iter();
+                let prologue_output = outputs.next().expect("block must have a prologue");
+                assert!(
+                    matches!(prologue_output.status(), TransactionStatus::Keep(_)),
+                    "block prologue failed: {:?}",
+                    prologue_output.status()
+                );
+                self.check_output(&prologue_output);
+                self.maybe_print_write_set(self.executor.get_state_view(), &prologue_output);
+                self.executor.apply_write_set(prologue_output.write_set());
+                outputs
+            }
+            None => self.executor.execute_block(txn_block).unwrap().into_iter(),
+        };
+        for (output, (sender, gas_unit_price, payload)) in outputs.zip(senders) {
+            self.check_output(&output);
+            self.record_fee(gas_unit_price, &output);
+            if let Some(golden) = &mut self.gas_golden {
+                golden.record(&payload, output.gas_used());
+            }
+            self.maybe_print_write_set(self.executor.get_state_view(), &output);
+            self.last_writes =
+                WriteSetDiff::new(self.executor.get_state_view(), output.write_set());
             if matches!(output.status(), TransactionStatus::Keep(_)) {
                 self.executor.apply_write_set(output.write_set());
             }
-            result.push(output.status().to_owned())
+            result.push(self.summarize(&sender, gas_unit_price, &output))
         }
+        drop(span);
+        self.run_block_hooks(|h| &mut h.after_block_hooks);
+        self.maybe_auto_epoch();
         result
     }
 
+    /// Returns the scheduler counts of the last block executed in parallel, i.e. how many
+    /// transactions were aborted and re-executed. `None` if no block was executed in parallel.
+    pub fn last_parallel_stats(&self) -> Option<SchedulerStats> {
+        self.executor.last_parallel_stats()
+    }
+
+    /// Executes a transaction against the current state, bypassing golden files and tracing.
+    fn execute_without_logging(&self, txn: Transaction) -> TransactionOutput {
+        AptosVM::execute_block(vec![txn], self.executor.get_state_view())
+            .expect("The VM should not fail to startup")
+            .pop()
+            .expect("A block with one transaction should have one output")
+    }
+
+    /// Adds the fee of a user transaction to the fees burned, if it is kept.
+    fn record_fee(&mut self, gas_unit_price: u64, output: &TransactionOutput) {
+        if matches!(output.status(), TransactionStatus::Keep(_)) {
+            self.fees_burned += output.gas_used() as u128 * gas_unit_price as u128;
+        }
+    }
+
+    /// Runs the enabled checks on the output of a transaction before it is applied.
+    fn check_output(&mut self, output: &TransactionOutput) {
+        debug!(
+            status = ?output.status(),
+            gas_used = output.gas_used(),
+            writes = output.write_set().iter().count(),
+            events = output.events().len(),
+            "executed transaction"
+        );
+        if let Some(checker) = &mut self.event_checker {
+            checker.check(output.events());
+        }
+        self.last_events = output.events().to_vec();
+        let stats = ExecutionStats::new(self.executor.get_state_view(), output);
+        self.last_txn_stats = stats;
+        self.total_stats += stats;
+        if let Some(records) = &mut self.output_records {
+            records.push(OutputRecord::new(output));
+        }
+        if let Some(written_state) = &mut self.written_state {
+            written_state.extend(output.write_set().iter().map(|(key, _)| key.clone()));
+        }
+        if let Some(checker) = &mut self.orphan_checker {
+            checker.observe(self.executor.get_state_view(), output);
+        }
+        if let Some(lint) = &self.usage_lint {
+            lint.borrow_mut().observe(output);
+        }
+    }
+
+    /// Returns the events emitted by the last transaction executed, including block prologues,
+    /// e.g. by `new_epoch`. See also `expect_event!`.
+    pub fn last_events(&self) -> &[ContractEvent] {
+        &self.last_events
+    }
+
+    /// Returns the state written by the last transaction run, e.g. the last of a block, with the
+    /// values before and after. Unlike `last_events`, this excludes block prologues. See also
+    /// `assert_writes!`.
+    pub fn last_writes(&self) -> &WriteSetDiff {
+        &self.last_writes
+    }
+
+    /// Returns the execution statistics of the last transaction executed, including block
+    /// prologues, e.g. to assert the storage a transaction allocates.
+    pub fn last_txn_stats(&self) -> ExecutionStats {
+        self.last_txn_stats
+    }
+
+    /// Returns the execution statistics of all transactions executed since the harness was
+    /// created, added up.
+    pub fn total_stats(&self) -> ExecutionStats {
+        self.total_stats
+    }
+
+    /// Returns the events of the given Rust mirror type emitted by a transaction, e.g.
+    /// `harness.events_for::<JoinValidatorSetEvent>(&output)`, in the order they were emitted.
+    pub fn events_for<T: MoveResource>(&self, output: &impl TransactionEvents) -> Vec<T> {
+        events_of(output.transaction_events())
+    }
+
+    /// Returns a builder for a transaction sent by `account`, for the chain of the harness and
+    /// with its expiration timestamp, if set.
+    fn transaction(&self, account: &Account) -> TransactionBuilder {
+        let builder = account.transaction().chain_id(self.chain_id);
+        match self.expiration_timestamp_secs {
+            Some(expiration_timestamp_secs) => builder.ttl(expiration_timestamp_secs),
+            None => builder,
+        }
+    }
+
     /// Creates a transaction, based on provided payload.
     pub fn create_transaction_payload(
         &mut self,
@@ -111,25 +1076,244 @@ impl MoveHarness {
         let seq_no_ref = self.txn_seq_no.get_mut(account.address()).unwrap();
         let seq_no = *seq_no_ref;
         *seq_no_ref += 1;
-        account
-            .transaction()
+        self.transaction(account)
             .sequence_number(seq_no)
             .gas_unit_price(1)
             .payload(payload)
             .sign()
     }
 
+    /// Runs a transaction, based on provided payload, resubmitting it with an escalating gas unit
+    /// price according to `policy` for as long as it is discarded. This models the retry
+    /// behavior of clients against the pricing checks of the prologue.
+    pub fn run_with_gas_escalation(
+        &mut self,
+        account: &Account,
+        payload: TransactionPayload,
+        policy: &GasPricePolicy,
+    ) -> RetryOutcome {
+        let mut attempts = vec![];
+        let mut price = policy.initial_price;
+        for _ in 0..policy.max_attempts {
+            // Discarded transactions don't consume a sequence number, so it is only advanced
+            // once the transaction is kept.
+            let seq_no = self.txn_seq_no[account.address()];
+            let txn = self
+                .transaction(account)
+                .sequence_number(seq_no)
+                .gas_unit_price(price)
+                .payload(payload.clone())
+                .sign();
+            let status = TransactionStatus::from(self.run(txn));
+            let kept = matches!(status, TransactionStatus::Keep(_));
+            attempts.push((price, status));
+            if kept {
+                *self.txn_seq_no.get_mut(account.address()).unwrap() += 1;
+                break;
+            }
+            price = price.saturating_mul(policy.multiplier);
+        }
+        RetryOutcome { attempts }
+    }
+
+    /// Returns the on-chain gas schedule.
+    pub fn gas_schedule(&self) -> GasSchedule {
+        self.read_resource::<GasSchedule>(&CORE_CODE_ADDRESS, Self::gas_schedule_tag())
+            .expect("gas schedule must exist")
+    }
+
+    /// Replaces the on-chain gas schedule, e.g. to run transactions under future gas parameters.
+    /// The VM needs every entry of the current schedule, so the schedule is best derived from
+    /// `gas_schedule`.
+    pub fn set_gas_schedule(&mut self, schedule: &GasSchedule) {
+        let write_set = WriteSetMut::new(vec![(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                CORE_CODE_ADDRESS,
+                Self::gas_schedule_tag(),
+            ))),
+            WriteOp::Value(bcs::to_bytes(schedule).expect("GasSchedule has BCS")),
+        )])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+    }
+
+    /// Overrides an entry of the on-chain gas schedule, e.g. `txn.min_price_per_gas_unit`.
+    pub fn set_gas_schedule_entry(&mut self, name: &str, value: u64) {
+        let mut schedule = self.gas_schedule();
+        match schedule.entries.iter_mut().find(|(entry, _)| entry == name) {
+            Some((_, entry_value)) => *entry_value = value,
+            None => panic!("gas schedule has no entry {}", name),
+        }
+        self.set_gas_schedule(&schedule);
+    }
+
+    fn gas_schedule_tag() -> StructTag {
+        StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("gas_schedule").to_owned(),
+            name: ident_str!("GasSchedule").to_owned(),
+            type_params: vec![],
+        }
+    }
+
     /// Runs a transaction, based on provided payload. If the transaction succeeds, any generated
     /// writeset will be applied to storage.
     pub fn run_transaction_payload(
         &mut self,
         account: &Account,
         payload: TransactionPayload,
-    ) -> TransactionStatus {
+    ) -> TxnSummary {
         let txn = self.create_transaction_payload(account, payload);
         self.run(txn)
     }
 
+    /// Creates a transaction, based on provided payload, which the prologue discards for the
+    /// given reason. Unlike other transactions created by the harness, it does not use up a
+    /// sequence number, so transactions created afterwards are unaffected.
+    pub fn create_discarded(
+        &mut self,
+        account: &Account,
+        payload: TransactionPayload,
+        reason: DiscardReason,
+    ) -> SignedTransaction {
+        let seq_no = self.txn_seq_no[account.address()];
+        let builder = self
+            .transaction(account)
+            .sequence_number(seq_no)
+            .gas_unit_price(1)
+            .payload(payload);
+        let builder = match reason {
+            DiscardReason::StaleSequenceNumber => builder.sequence_number(
+                seq_no
+                    .checked_sub(1)
+                    .expect("the account must have sent a transaction before"),
+            ),
+            DiscardReason::InsufficientBalanceForGas => {
+                let max_gas_unit_price = self
+                    .gas_schedule()
+                    .entries
+                    .into_iter()
+                    .find(|(name, _)| name == "txn.max_price_per_gas_unit")
+                    .expect("gas schedule must have a maximum gas unit price")
+                    .1;
+                let max_fee = max_gas_unit_price as u128 * TXN_RESERVED as u128;
+                let balance = self.balance::<AptosCoin>(account.address());
+                assert!(
+                    (balance as u128) < max_fee,
+                    "balance {} of {} covers the maximum fee {}",
+                    balance,
+                    account.address(),
+                    max_fee
+                );
+                builder
+                    .max_gas_amount(TXN_RESERVED)
+                    .gas_unit_price(max_gas_unit_price)
+            }
+            DiscardReason::Expired => builder.ttl(self.get_on_chain_time_usecs() / MICROS_PER_SEC),
+            DiscardReason::WrongChainId => {
+                builder.chain_id(ChainId::new(self.chain_id.id() % u8::MAX + 1))
+            }
+        };
+        builder.sign()
+    }
+
+    /// Runs a transaction, based on provided payload, which the prologue discards for the given
+    /// reason, see `create_discarded`.
+    pub fn run_discarded(
+        &mut self,
+        account: &Account,
+        payload: TransactionPayload,
+        reason: DiscardReason,
+    ) -> TxnSummary {
+        let txn = self.create_discarded(account, payload, reason);
+        self.run(txn)
+    }
+
+    /// Runs block prologues until the on-chain time reaches the expiration timestamp of `txn`,
+    /// e.g. as set via `set_expiration_timestamp_secs`, and then runs it, which the prologue
+    /// discards as `TRANSACTION_EXPIRED`. As the transaction is discarded, its sequence number is
+    /// handed out again to the next transaction created by the harness.
+    pub fn run_expired(&mut self, txn: SignedTransaction) -> TxnSummary {
+        let expiration_usecs = txn.expiration_timestamp_secs() * MICROS_PER_SEC;
+        if self.get_on_chain_time_usecs() < expiration_usecs {
+            self.fast_forward_to_timestamp_usecs(expiration_usecs);
+        }
+        let sender = txn.sender();
+        let sequence_number = txn.sequence_number();
+        let summary = self.run(txn);
+        if let (TransactionStatus::Discard(_), Some(seq_no)) =
+            (summary.status(), self.txn_seq_no.get_mut(&sender))
+        {
+            *seq_no = (*seq_no).min(sequence_number);
+        }
+        summary
+    }
+
+    /// Simulates a transaction, based on provided payload, the way the REST simulate API does,
+    /// and returns its output, with the gas used, the events, and the write set. Nothing is
+    /// applied and the sequence number is not used up, so running the same payload afterwards
+    /// executes against the same state. As with the API, the transaction carries an invalid
+    /// signature, which the simulation does not check, so `account` must not be a multisig account.
+    pub fn simulate(
+        &mut self,
+        account: &Account,
+        payload: TransactionPayload,
+    ) -> TransactionOutput {
+        let raw_txn = self
+            .transaction(account)
+            .sequence_number(self.txn_seq_no[account.address()])
+            .gas_unit_price(1)
+            .payload(payload)
+            .raw();
+        let txn = SignedTransaction::new(
+            raw_txn,
+            account.pubkey.clone(),
+            Ed25519Signature::dummy_signature(),
+        );
+        let state_view = self.executor.get_state_view();
+        let (_, output) = AptosVM::simulate_signed_transaction(&txn, state_view);
+        output
+            .into_transaction_output(state_view)
+            .expect("applying the deltas of a simulated transaction should not fail")
+    }
+
+    /// Creates a multi-agent transaction, based on provided payload, sent by `sender` and also
+    /// signed by the `secondary_signers`. The payload receives the signers of the sender and of
+    /// the secondary signers, in this order. Only the sequence number of the sender advances.
+    ///
+    /// Note that the sender always pays for gas: there are no fee-payer (sponsored) transactions
+    /// yet, as neither the transaction authenticator nor the epilogue in `account.move` know of a
+    /// fee payer.
+    pub fn create_multi_agent(
+        &mut self,
+        sender: &Account,
+        secondary_signers: &[Account],
+        payload: TransactionPayload,
+    ) -> SignedTransaction {
+        let seq_no_ref = self.txn_seq_no.get_mut(sender.address()).unwrap();
+        let seq_no = *seq_no_ref;
+        *seq_no_ref += 1;
+        self.transaction(sender)
+            .secondary_signers(secondary_signers.to_vec())
+            .sequence_number(seq_no)
+            .gas_unit_price(1)
+            .payload(payload)
+            .sign_multi_agent()
+    }
+
+    /// Runs a multi-agent transaction, see `create_multi_agent`. If the transaction succeeds, any
+    /// generated writeset will be applied to storage.
+    pub fn run_multi_agent(
+        &mut self,
+        sender: &Account,
+        secondary_signers: &[Account],
+        payload: TransactionPayload,
+    ) -> TxnSummary {
+        let txn = self.create_multi_agent(sender, secondary_signers, payload);
+        self.run(txn)
+    }
+
     /// Creates a transaction which runs the specified entry point `fun`. Arguments need to be
     /// provided in bcs-serialized form.
     pub fn create_entry_function(
@@ -161,13 +1345,59 @@ impl MoveHarness {
         fun: MemberId,
         ty_args: Vec<TypeTag>,
         args: Vec<Vec<u8>>,
-    ) -> TransactionStatus {
+    ) -> TxnSummary {
         let txn = self.create_entry_function(account, fun, ty_args, args);
         self.run(txn)
     }
 
+    /// Creates a transaction which runs the specified entry point `fun`, e.g.
+    /// `"0x1::stake::add_stake"`. Arguments need to be provided as a JSON array, in the same
+    /// representation as submitted to the REST API, and are converted using the ABI of the
+    /// function as currently published.
+    pub fn create_entry_json(
+        &mut self,
+        account: &Account,
+        fun: &str,
+        ty_args: Vec<TypeTag>,
+        args: serde_json::Value,
+    ) -> SignedTransaction {
+        let function = ScriptFunctionId::from_str(fun)
+            .expect("function must be of the form addr::module::fun");
+        let arguments = match args {
+            serde_json::Value::Array(arguments) => arguments,
+            args => panic!("arguments must be a JSON array, got: {}", args),
+        };
+        let payload = ApiTransactionPayload::ScriptFunctionPayload(ScriptFunctionPayload {
+            function,
+            type_arguments: ty_args.into_iter().map(MoveType::from).collect(),
+            arguments,
+        });
+        let payload = self
+            .executor
+            .get_state_view()
+            .as_move_resolver()
+            .as_converter(Arc::new(MockDbReaderWriter))
+            .try_into_aptos_core_transaction_payload(payload)
+            .expect("arguments must match the ABI of the function");
+        self.create_transaction_payload(account, payload)
+    }
+
+    /// Run the specified entry point `fun`, e.g. `"0x1::stake::add_stake"`, with arguments
+    /// provided as a JSON array, e.g. `json!(["1000000"])`. See `create_entry_json`.
+    pub fn run_entry_json(
+        &mut self,
+        account: &Account,
+        fun: &str,
+        ty_args: Vec<TypeTag>,
+        args: serde_json::Value,
+    ) -> TxnSummary {
+        let txn = self.create_entry_json(account, fun, ty_args, args);
+        self.run(txn)
+    }
+
     /// Creates a transaction which publishes the Move Package found at the given path on behalf
-    /// of the given account.
+    /// of the given account. The package is compiled from source, and its metadata is derived
+    /// with the given upgrade policy, so that tests can deploy custom modules and then call them.
     pub fn create_publish_package(
         &mut self,
         account: &Account,
@@ -195,20 +1425,316 @@ impl MoveHarness {
         account: &Account,
         path: &Path,
         upgrade_policy: UpgradePolicy,
-    ) -> TransactionStatus {
+    ) -> TxnSummary {
         let txn = self.create_publish_package(account, path, upgrade_policy);
         self.run(txn)
     }
 
-    pub fn fast_forward(&mut self, seconds: u64) {
+    /// Builds the Move package at `path`, which needs to contain exactly one script, and returns
+    /// the bytecode of the script.
+    pub fn compile_script(path: &Path) -> Vec<u8> {
+        let package = BuiltPackage::build(MovePackageDir::new(path.to_owned()), false, false)
+            .expect("building package must succeed");
+        let mut scripts = package.extract_script_code();
+        assert_eq!(
+            scripts.len(),
+            1,
+            "package {} must contain exactly one script",
+            path.display()
+        );
+        scripts.pop().unwrap()
+    }
+
+    /// Creates a transaction which runs the script `code` with the given arguments, e.g. as
+    /// compiled by `compile_script`. The account is passed to the script as its signer.
+    pub fn create_script(
+        &mut self,
+        account: &Account,
+        code: Vec<u8>,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+    ) -> SignedTransaction {
+        self.create_transaction_payload(
+            account,
+            TransactionPayload::Script(Script::new(code, ty_args, args)),
+        )
+    }
+
+    /// Runs the script `code` with the given arguments, see `create_script`.
+    pub fn run_script(
+        &mut self,
+        account: &Account,
+        code: Vec<u8>,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+    ) -> TxnSummary {
+        let txn = self.create_script(account, code, ty_args, args);
+        self.run(txn)
+    }
+
+    /// Returns a builder for a timeline of clock advances and epoch changes, e.g.
+    /// `harness.clock().advance_secs(7200).to_next_epoch().run()`.
+    pub fn clock(&mut self) -> Clock {
+        Clock::new(self)
+    }
+
+    /// Returns the current block time in microseconds, the unit of on-chain timestamps.
+    pub fn get_block_time_usecs(&self) -> u64 {
+        self.executor.get_block_time()
+    }
+
+    /// Returns the current block time in whole seconds, the unit of lockups, rounded down.
+    pub fn get_block_time_secs(&self) -> u64 {
+        self.executor.get_block_time_seconds()
+    }
+
+    /// Returns the on-chain time in microseconds, which only follows the block time once a block
+    /// prologue runs, e.g. via `new_block_at_usecs` or `new_epoch`.
+    pub fn get_on_chain_time_usecs(&self) -> u64 {
+        let tag = StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("timestamp").to_owned(),
+            name: ident_str!("CurrentTimeMicroseconds").to_owned(),
+            type_params: vec![],
+        };
+        self.read_resource::<CurrentTimeMicroseconds>(&CORE_CODE_ADDRESS, tag)
+            .expect("timestamp must be initialized")
+            .microseconds
+    }
+
+    /// Sets the block time to `usecs` microseconds, without executing any block. The block time
+    /// cannot go backwards.
+    pub fn set_block_time_usecs(&mut self, usecs: u64) {
         let current_time = self.executor.get_block_time();
-        self.executor
-            .set_block_time(current_time + seconds * 1_000_000)
+        assert!(
+            usecs >= current_time,
+            "block time cannot go backwards from {} to {} microseconds",
+            current_time,
+            usecs
+        );
+        self.executor.set_block_time(usecs)
+    }
+
+    /// Advances the block time by `usecs` microseconds, without executing any block.
+    pub fn fast_forward_usecs(&mut self, usecs: u64) {
+        let current_time = self.executor.get_block_time();
+        self.executor.set_block_time(
+            current_time
+                .checked_add(usecs)
+                .expect("block time overflows u64 microseconds"),
+        )
+    }
+
+    /// Advances the block time by `seconds`, without executing any block.
+    pub fn fast_forward_secs(&mut self, seconds: u64) {
+        self.fast_forward_usecs(
+            seconds
+                .checked_mul(MICROS_PER_SEC)
+                .expect("block time overflows u64 microseconds"),
+        )
     }
 
     pub fn new_epoch(&mut self) {
-        self.fast_forward(7200);
-        self.executor.new_block()
+        self.new_epoch_raw();
+    }
+
+    /// Starts a new epoch and returns the output of its block prologue, e.g. to inspect the
+    /// events emitted on reconfiguration.
+    pub fn new_epoch_raw(&mut self) -> TransactionOutput {
+        self.new_epoch_after(self.new_epoch_secs)
+    }
+
+    /// Starts `count` new epochs, like calling `new_epoch` `count` times, but with less overhead
+    /// per epoch, e.g. for a year of reward compounding: the block prologues only run
+    /// sequentially, they are not logged to golden files or printed, and the block hooks run once
+    /// around all of them. The enabled checks still see every block prologue.
+    pub fn new_epochs(&mut self, count: usize) {
+        self.run_block_hooks(|h| &mut h.before_block_hooks);
+        let span = debug_span!(
+            parent: &self.span,
+            "new_epochs",
+            count,
+            fast_forward_secs = self.new_epoch_secs
+        )
+        .entered();
+        for _ in 0..count {
+            self.fast_forward_secs(self.new_epoch_secs);
+            self.fast_forward_usecs(1);
+            let metadata = self.executor.block_metadata(None, vec![]);
+            let output = self.execute_without_logging(Transaction::BlockMetadata(metadata));
+            assert_eq!(
+                output.status(),
+                &TransactionStatus::Keep(ExecutionStatus::Success),
+                "block prologue must succeed"
+            );
+            self.check_output(&output);
+            self.executor.apply_write_set(output.write_set());
+        }
+        drop(span);
+        self.run_block_hooks(|h| &mut h.after_block_hooks);
+    }
+
+    /// Returns the number of the current epoch.
+    pub fn current_epoch(&self) -> u64 {
+        self.executor
+            .read_resource::<ConfigurationResource>(&CORE_CODE_ADDRESS)
+            .expect("reconfiguration must be initialized")
+            .epoch()
+    }
+
+    /// Starts new epochs, via `new_epoch`, until the epoch with the number `epoch` begins.
+    pub fn fast_forward_to_epoch(&mut self, epoch: u64) {
+        let current_epoch = self.current_epoch();
+        assert!(
+            epoch >= current_epoch,
+            "epoch cannot go backwards from {} to {}",
+            current_epoch,
+            epoch
+        );
+        for _ in current_epoch..epoch {
+            self.new_epoch();
+        }
+        assert_eq!(
+            self.current_epoch(),
+            epoch,
+            "new_epoch must start exactly one epoch"
+        );
+    }
+
+    /// Runs block prologues until the on-chain time is `timestamp_usecs`. Like on chain, every
+    /// time the on-chain epoch interval passes on the way, a block starts a new epoch, as soon as
+    /// the block time allows. The last block, at `timestamp_usecs`, also starts a new epoch if one
+    /// is due.
+    pub fn fast_forward_to_timestamp_usecs(&mut self, timestamp_usecs: u64) {
+        let on_chain_time = self.get_on_chain_time_usecs();
+        assert!(
+            timestamp_usecs > on_chain_time,
+            "on-chain time cannot go backwards from {} to {} microseconds",
+            on_chain_time,
+            timestamp_usecs
+        );
+        let interval_usecs = self.read_block_resource().epoch_interval;
+        loop {
+            let last_reconfiguration = self
+                .executor
+                .read_resource::<ConfigurationResource>(&CORE_CODE_ADDRESS)
+                .expect("reconfiguration must be initialized")
+                .last_reconfiguration_time();
+            // The block prologue starts a new epoch once more than the interval has passed.
+            let next_epoch_usecs = last_reconfiguration + interval_usecs + 1;
+            if next_epoch_usecs >= timestamp_usecs {
+                break;
+            }
+            let block_usecs = next_epoch_usecs.max(self.get_block_time_usecs());
+            self.new_block_at_usecs(block_usecs, None, vec![]);
+        }
+        self.new_block_at_usecs(timestamp_usecs, None, vec![]);
+    }
+
+    /// Runs block prologues until the on-chain time is `seconds`, see
+    /// `fast_forward_to_timestamp_usecs`.
+    pub fn fast_forward_to_timestamp_secs(&mut self, seconds: u64) {
+        self.fast_forward_to_timestamp_usecs(
+            seconds
+                .checked_mul(MICROS_PER_SEC)
+                .expect("block time overflows u64 microseconds"),
+        )
+    }
+
+    /// Fast forwards by `seconds` and runs a block prologue, which starts a new epoch if the
+    /// epoch interval has passed by then.
+    fn new_epoch_after(&mut self, seconds: u64) -> TransactionOutput {
+        self.run_block_hooks(|h| &mut h.before_block_hooks);
+        let span =
+            debug_span!(parent: &self.span, "new_epoch", fast_forward_secs = seconds).entered();
+        self.fast_forward_secs(seconds);
+        self.fast_forward_usecs(1);
+        let output = self.run_block_prologue(None, vec![]);
+        drop(span);
+        self.run_block_hooks(|h| &mut h.after_block_hooks);
+        output
+    }
+
+    /// Makes the harness start a new epoch after every transaction or block it runs once more
+    /// than `seconds` of simulated time have passed since the last reconfiguration, so long
+    /// scenarios can `fast_forward_secs` instead of calling `new_epoch` at precomputed points. Also
+    /// sets the on-chain epoch interval, so block prologues, e.g. of `new_block_with_metadata`,
+    /// reconfigure on the same schedule, and makes `new_epoch` fast forward by `seconds`.
+    pub fn set_auto_epoch(&mut self, seconds: u64) {
+        let interval_usecs = seconds * MICROS_PER_SEC;
+        let mut block = self.read_block_resource();
+        block.epoch_interval = interval_usecs;
+        let write_set = WriteSetMut::new(vec![(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                CORE_CODE_ADDRESS,
+                Self::block_resource_tag(),
+            ))),
+            WriteOp::Value(bcs::to_bytes(&block).expect("BlockResource has BCS")),
+        )])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+        self.auto_epoch_interval_usecs = Some(interval_usecs);
+        self.new_epoch_secs = seconds;
+    }
+
+    fn block_resource_tag() -> StructTag {
+        StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("block").to_owned(),
+            name: ident_str!("BlockResource").to_owned(),
+            type_params: vec![],
+        }
+    }
+
+    fn read_block_resource(&self) -> BlockResource {
+        self.read_resource::<BlockResource>(&CORE_CODE_ADDRESS, Self::block_resource_tag())
+            .expect("block resource must exist")
+    }
+
+    /// Starts a new epoch if the interval set by `set_auto_epoch` has passed.
+    fn maybe_auto_epoch(&mut self) {
+        let interval_usecs = match self.auto_epoch_interval_usecs {
+            Some(interval_usecs) => interval_usecs,
+            None => return,
+        };
+        let configuration = self
+            .executor
+            .read_resource::<ConfigurationResource>(&CORE_CODE_ADDRESS)
+            .expect("reconfiguration must be initialized");
+        let elapsed_usecs = self
+            .get_block_time_usecs()
+            .saturating_sub(configuration.last_reconfiguration_time());
+        if elapsed_usecs > interval_usecs {
+            self.new_epoch_after(0);
+        }
+    }
+
+    /// Executes a block prologue at the current block time, runs the enabled checks on its
+    /// output and applies it.
+    fn run_block_prologue(
+        &mut self,
+        proposer_index: Option<u32>,
+        failed_proposer_indices: Vec<u32>,
+    ) -> TransactionOutput {
+        let metadata = self
+            .executor
+            .block_metadata(proposer_index, failed_proposer_indices);
+        let output = self
+            .executor
+            .execute_block_with_metadata(metadata, vec![])
+            .expect("Executing block prologue should succeed")
+            .pop()
+            .expect("block must have a prologue");
+        assert!(
+            matches!(output.status(), TransactionStatus::Keep(_)),
+            "block prologue failed: {:?}",
+            output.status()
+        );
+        self.check_output(&output);
+        self.maybe_print_write_set(self.executor.get_state_view(), &output);
+        self.executor.apply_write_set(output.write_set());
+        output
     }
 
     pub fn new_block_with_metadata(
@@ -216,12 +1742,80 @@ impl MoveHarness {
         proposer_index: Option<u32>,
         failed_proposer_indices: Vec<u32>,
     ) {
-        self.fast_forward(1);
-        self.executor
-            .new_block_with_metadata(proposer_index, failed_proposer_indices);
+        let timestamp_usecs = self
+            .get_block_time_usecs()
+            .checked_add(MICROS_PER_SEC)
+            .expect("block time overflows u64 microseconds");
+        self.new_block_at_usecs(timestamp_usecs, proposer_index, failed_proposer_indices)
+    }
+
+    /// Runs a block prologue with the timestamp `timestamp_usecs`, e.g. to simulate irregular
+    /// block intervals. Like on chain, the timestamp must be later than the current on-chain time,
+    /// and the block starts a new epoch if more than the epoch interval has passed since the last
+    /// reconfiguration.
+    pub fn new_block_at_usecs(
+        &mut self,
+        timestamp_usecs: u64,
+        proposer_index: Option<u32>,
+        failed_proposer_indices: Vec<u32>,
+    ) {
+        self.run_block_hooks(|h| &mut h.before_block_hooks);
+        let span = debug_span!(
+            parent: &self.span,
+            "new_block",
+            timestamp_usecs,
+            proposer = ?proposer_index,
+            failed_proposers = ?failed_proposer_indices
+        )
+        .entered();
+        self.set_block_time_usecs(timestamp_usecs);
+        self.run_block_prologue(proposer_index, failed_proposer_indices);
+        drop(span);
+        self.run_block_hooks(|h| &mut h.after_block_hooks);
+    }
+
+    /// Runs blocks with the proposals of `schedule`, entries of `(epoch, proposer_index,
+    /// failed_proposer_indices)` in epoch order, e.g. `(3, Some(0), vec![1])` for a block of epoch
+    /// 3 proposed by the validator with index 0 after the one with index 1 failed. Epochs without
+    /// entries pass without proposals, and the epoch of the last entry is ended, so all rewards
+    /// are distributed. Returns the stake pools of the validators of the scheduled epochs, by
+    /// pool address.
+    pub fn run_epochs_with_proposals(
+        &mut self,
+        schedule: &[(u64, Option<u32>, Vec<u32>)],
+    ) -> BTreeMap<AccountAddress, StakePool> {
+        let mut pool_addresses = BTreeSet::new();
+        for (epoch, proposer_index, failed_proposer_indices) in schedule {
+            self.fast_forward_to_epoch(*epoch);
+            let set = get_validator_set(self);
+            pool_addresses.extend(
+                set.active_validators
+                    .iter()
+                    .chain(set.pending_inactive.iter())
+                    .map(|info| info.account_address),
+            );
+            self.new_block_with_metadata(*proposer_index, failed_proposer_indices.clone());
+        }
+        if let Some((epoch, _, _)) = schedule.last() {
+            self.fast_forward_to_epoch(epoch + 1);
+        }
+        pool_addresses
+            .into_iter()
+            .map(|pool_address| (pool_address, get_stake_pool(self, &pool_address)))
+            .collect()
+    }
+
+    /// Returns a read-only view of the current state, e.g. to run a resource viewer or any other
+    /// `StateView` consumer against it. Reads through the view bypass the usage lint.
+    pub fn state_view(&self) -> &FakeDataStore {
+        self.executor.get_state_view()
     }
 
     pub fn read_state_value(&self, state_key: &StateKey) -> Option<Vec<u8>> {
+        let _span = debug_span!(parent: &self.span, "read_state", key = ?state_key).entered();
+        if let Some(lint) = &self.usage_lint {
+            lint.borrow_mut().state_read(state_key);
+        }
         self.executor.read_state_value(state_key).and_then(|bytes| {
             if bytes.is_empty() {
                 None
@@ -241,6 +1835,42 @@ impl MoveHarness {
         self.read_state_value(&StateKey::AccessPath(path))
     }
 
+    /// Reads the raw, serialized value of a table item, given the serialized key.
+    pub fn read_table_item_raw(&self, handle: TableHandle, key: &[u8]) -> Option<Vec<u8>> {
+        self.read_state_value(&StateKey::TableItem {
+            handle,
+            key: key.to_vec(),
+        })
+    }
+
+    /// Reads the value `V` of a table item, e.g. of a table whose handle is read from a mirrored
+    /// resource holding a `TableHandle` in place of the `Table`.
+    pub fn read_table_item<K: Serialize, V: DeserializeOwned>(
+        &self,
+        handle: TableHandle,
+        key: &K,
+    ) -> Option<V> {
+        let key = bcs::to_bytes(key).expect("table key must serialize");
+        Some(
+            bcs::from_bytes::<V>(&self.read_table_item_raw(handle, &key)?).expect(
+                "serialization expected to succeed (Rust type incompatible with Move type?)",
+            ),
+        )
+    }
+
+    /// Reads the current value of an aggregator, e.g. of an `Aggregator` field of a mirrored
+    /// resource, from the table item holding it. Panics if the aggregator has no value in
+    /// storage.
+    pub fn read_aggregator(&self, aggregator: &Aggregator) -> u128 {
+        self.read_table_item(TableHandle(aggregator.handle), &aggregator.key)
+            .unwrap_or_else(|| {
+                panic!(
+                    "aggregator {} of table {} must have a value",
+                    aggregator.key, aggregator.handle
+                )
+            })
+    }
+
     /// Reads the resource data `T`.
     pub fn read_resource<T: DeserializeOwned>(
         &self,
@@ -254,6 +1884,73 @@ impl MoveHarness {
         )
     }
 
+    /// Calls the Move function `fun`, e.g. `"0x1::coin::balance"`, against the current state,
+    /// the way clients read state, and returns its return values decoded from BCS. Multiple
+    /// return values decode as a tuple. Arguments need to be provided in bcs-serialized form.
+    /// The effects of the function are discarded. Panics if the function aborts.
+    pub fn execute_view_function<T: DeserializeOwned>(
+        &self,
+        fun: MemberId,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> T {
+        let MemberId {
+            module_id,
+            member_id: function_id,
+        } = fun;
+        let return_values = self
+            .executor
+            .execute_view_function(&module_id, &function_id, ty_args, args)
+            .unwrap_or_else(|status| {
+                panic!(
+                    "view function {}::{} failed: {:?}",
+                    module_id, function_id, status
+                )
+            });
+        // The BCS encoding of a tuple is the concatenation of the encodings of its elements.
+        bcs::from_bytes(&return_values.concat())
+            .expect("return values must decode as the requested type")
+    }
+
+    /// Reads the resource `T`, using the struct tag it mirrors.
+    pub fn read_move_resource<T: MoveResource>(&self, addr: &AccountAddress) -> Option<T> {
+        self.read_resource(addr, T::struct_tag())
+    }
+
+    /// Overwrites the resource `T` at `addr`, bypassing any validation of the Move code which
+    /// usually modifies it.
+    pub fn write_move_resource<T: MoveResource + Serialize>(
+        &mut self,
+        addr: &AccountAddress,
+        resource: &T,
+    ) {
+        let write_set = WriteSetMut::new(vec![(
+            StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                *addr,
+                T::struct_tag(),
+            ))),
+            WriteOp::Value(bcs::to_bytes(resource).expect("MoveResource has BCS")),
+        )])
+        .freeze()
+        .unwrap();
+        self.executor.apply_write_set(&write_set);
+    }
+
+    /// Returns the balance of `addr` in the coin type `C`, e.g. `balance::<AptosCoin>(addr)`.
+    /// Panics if the account has no coin store for `C`. There is no `fa_balance` counterpart
+    /// since there is no fungible-asset framework in this tree.
+    pub fn balance<C: MoveResource>(&self, addr: &AccountAddress) -> u64 {
+        let tag = StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("coin").to_owned(),
+            name: ident_str!("CoinStore").to_owned(),
+            type_params: vec![TypeTag::Struct(C::struct_tag())],
+        };
+        self.read_resource::<CoinStoreResource>(addr, tag.clone())
+            .unwrap_or_else(|| panic!("account {} has no {}", addr, tag))
+            .coin()
+    }
+
     /// Checks whether resource exists.
     pub fn exists_resource(&self, addr: &AccountAddress, struct_tag: StructTag) -> bool {
         self.read_resource_raw(addr, struct_tag).is_some()
@@ -261,7 +1958,9 @@ impl MoveHarness {
 }
 
 /// Enables golden files for the given harness. The golden file will be stored side-by-side
-/// with the data directory of a Rust source, named after the test function.
+/// with the data directory of a Rust source, named after the test function. Next to it, the gas
+/// used by every transaction is recorded in a `.gas` golden file, which is checked within the
+/// tolerance set via `set_gas_tolerance_percent` when the harness is dropped.
 #[macro_export]
 macro_rules! enable_golden {
     ($h:expr) => {
@@ -276,20 +1975,113 @@ impl MoveHarness {
         // so we need to add that to it. We also want to replace the extension `.rs` with `.data`.
         let mut path = get_project_root().unwrap().join(file_macro_value);
         path.set_extension("data");
-        // The result of the `current_function` macro gives us the fully qualified
-        // We only want the trailing simple name.
-        let fun = function_macro_value.split("::").last().unwrap();
+        let fun = golden_name(function_macro_value);
+        self.gas_golden = Some(GasGolden::new(&path, &fun));
         self.executor
-            .set_golden_file_at(&path.display().to_string(), fun)
+            .set_golden_file_at(&path.display().to_string(), &fun)
+    }
+
+    /// Sets by how much, in percent, the gas used by a transaction may drift from the gas golden
+    /// file before the test fails. Defaults to `DEFAULT_GAS_TOLERANCE_PERCENT`. Needs to be
+    /// called after `enable_golden!`.
+    pub fn set_gas_tolerance_percent(&mut self, tolerance_percent: u64) {
+        self.gas_golden
+            .as_mut()
+            .expect("golden files must be enabled")
+            .set_tolerance_percent(tolerance_percent)
+    }
+
+    /// Returns the redactions applied to transaction outputs before they are recorded in the
+    /// golden file, e.g. `harness.golden_redactions().address(addr, "owner").gas_used()`. Can be
+    /// called before or after `enable_golden!`, and applies to outputs recorded from then on.
+    pub fn golden_redactions(&mut self) -> &mut GoldenRedactions {
+        self.executor.golden_redactions()
+    }
+}
+
+/// Names the golden files of the test function at `function_path`, as given by the
+/// `current_function` macro. The name of the test crate is dropped, as the golden files are
+/// already per test file, and nested modules are kept, e.g. `a__test` for `crate::a::test`, so
+/// tests of the same name never share a golden file. Closures, e.g. of `proptest!`, are skipped.
+fn golden_name(function_path: &str) -> String {
+    let segments: Vec<&str> = function_path
+        .split("::")
+        .skip(1)
+        .filter(|segment| *segment != "{{closure}}")
+        .collect();
+    segments.join("__")
+}
+
+impl Drop for MoveHarness {
+    fn drop(&mut self) {
+        // Scenarios which delete state are checked for orphans at their end, unless the test is
+        // already failing.
+        if let Some(checker) = &self.orphan_checker {
+            if !std::thread::panicking() && !checker.is_empty() {
+                checker.assert_no_orphans(self.executor.get_state_view());
+            }
+        }
+        if let Some(golden) = self.gas_golden.take() {
+            if !std::thread::panicking() {
+                golden.finish();
+            }
+        }
+        for warning in self.usage_lint_warnings() {
+            eprintln!("warning: {}", warning);
+        }
     }
 }
 
-/// Helper to assert transaction is successful
+/// Asserts that a transaction output contains an event of the given Rust mirror type whose
+/// listed fields equal the given values. Fields which are not listed are ignored, e.g.
+/// `assert_event!(output, DistributeRewardsEvent { pool_address: owner, rewards_amount: 285 })`.
+#[macro_export]
+macro_rules! assert_event {
+    ($output:expr, $ty:path { $($field:ident : $value:expr),* $(,)? }) => {{
+        let events = $crate::events::events_of::<$ty>($output.events());
+        assert!(
+            events.iter().any(|event| true $(&& event.$field == $value)*),
+            "no {} event matching {{ {} }}, found: {:?}",
+            stringify!($ty),
+            stringify!($($field: $value),*),
+            events
+        );
+    }};
+}
+
+/// Asserts that the last transaction executed by the harness emitted an event of the given
+/// Rust mirror type whose listed fields equal the given values, and evaluates to the first such
+/// event, e.g. `expect_event!(harness, JoinValidatorSetEvent { pool_address: owner })`. Unlike
+/// `assert_event!`, this needs no output, so it also works after e.g. `new_epoch`.
+#[macro_export]
+macro_rules! expect_event {
+    ($harness:expr, $ty:path { $($field:ident : $value:expr),* $(,)? }) => {{
+        let events = $crate::events::events_of::<$ty>($harness.last_events());
+        match events.iter().position(|event| true $(&& event.$field == $value)*) {
+            Some(index) => events[index].clone(),
+            None => panic!(
+                "no {} event matching {{ {} }} in the last transaction, found: {:?}",
+                stringify!($ty),
+                stringify!($($field: $value),*),
+                events
+            ),
+        }
+    }};
+}
+
+/// Helper to assert transaction is successful. On failure, aborts in framework modules are
+/// reported with their Move source location.
 #[macro_export]
 macro_rules! assert_success {
     ($s:expr) => {{
         use aptos_types::transaction::*;
-        assert_eq!($s, TransactionStatus::Keep(ExecutionStatus::Success))
+        let status: TransactionStatus = $s.into();
+        assert_eq!(
+            status,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            "{}",
+            $crate::abort_location::describe_status(&status)
+        )
     }};
 }
 
@@ -298,21 +2090,154 @@ macro_rules! assert_success {
 macro_rules! assert_abort {
     ($s:expr, $c:pat) => {{
         use aptos_types::transaction::*;
-        assert!(matches!(
-            $s,
-            TransactionStatus::Keep(ExecutionStatus::MoveAbort { code: $c, .. })
-        ));
+        let status: TransactionStatus = $s.into();
+        assert!(
+            matches!(
+                status,
+                TransactionStatus::Keep(ExecutionStatus::MoveAbort { code: $c, .. })
+            ),
+            "unexpected status: {}",
+            $crate::abort_location::describe_status(&status)
+        );
     }};
 }
 
-/// Helper to assert vm status code.
+/// Helper to assert transaction aborts in the given module, e.g. `"0x1::stake"`. Useful to check
+/// that an attack is stopped by the expected framework module rather than anywhere else. The
+/// abort is given either as a pattern of the code, or as the name of an error constant of a
+/// framework module, e.g. `assert_abort_in!(status, "0x1::stake", EALREADY_ACTIVE_VALIDATOR)`.
+#[macro_export]
+macro_rules! assert_abort_in {
+    ($s:expr, $m:expr, $e:ident) => {{
+        let status: aptos_types::transaction::TransactionStatus = $s.into();
+        assert!(
+            $crate::abort_location::is_abort_with(&status, $m, stringify!($e)),
+            "expected abort with {} in {}, got: {}",
+            stringify!($e),
+            $m,
+            $crate::abort_location::describe_status(&status)
+        );
+    }};
+    ($s:expr, $m:expr, $c:pat) => {{
+        use aptos_types::{transaction::*, vm_status::AbortLocation};
+        let status: TransactionStatus = $s.into();
+        assert!(
+            matches!(
+                &status,
+                TransactionStatus::Keep(ExecutionStatus::MoveAbort {
+                    location: AbortLocation::Module(module_id),
+                    code: $c,
+                }) if module_id.short_str_lossless() == $m
+            ),
+            "unexpected status: {}",
+            $crate::abort_location::describe_status(&status)
+        );
+    }};
+}
+
+/// Helper to assert transaction aborts with a named error constant of a framework module at
+/// `0x1`, e.g. `assert_aborts_with!(status, stake::EALREADY_REGISTERED)`. The constant is
+/// resolved via the framework error map, so the assertion is unaffected by renumbering.
+#[macro_export]
+macro_rules! assert_aborts_with {
+    ($s:expr, $m:ident :: $e:ident) => {{
+        let status: aptos_types::transaction::TransactionStatus = $s.into();
+        assert!(
+            $crate::abort_location::is_abort_with(
+                &status,
+                concat!("0x1::", stringify!($m)),
+                stringify!($e)
+            ),
+            "expected abort with {}::{}, got: {}",
+            stringify!($m),
+            stringify!($e),
+            $crate::abort_location::describe_status(&status)
+        );
+    }};
+}
+
+/// Helper to assert the exact resources the last transaction run by the harness wrote, besides
+/// paying for gas, e.g.
+/// `assert_writes!(harness, sender, { pool_address => "0x1::stake::StakePool" })`. Table items
+/// and modules are not checked, see `MoveHarness::expect_writes` for those.
+#[macro_export]
+macro_rules! assert_writes {
+    ($h:expr, $sender:expr, { $($addr:expr => $tag:expr),* $(,)? }) => {{
+        $h.last_writes().assert_resources(&$sender, &[$(($addr, $tag)),*]);
+    }};
+}
+
+/// Helper to assert two decoded resources are equal. On failure, prints the differing fields
+/// instead of the full values. Works for any type implementing `Serialize`, such as `StakePool`.
+#[macro_export]
+macro_rules! assert_resource_eq {
+    ($left:expr, $right:expr $(,)?) => {{
+        if let Some(diff) = $crate::diff::render_resource_diff(&$left, &$right) {
+            panic!("resources are not equal:\n{}", diff);
+        }
+    }};
+}
+
+/// Helper to assert a transaction is discarded with the given status, e.g.
+/// `assert_discard!(output, DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD)`.
+#[macro_export]
+macro_rules! assert_discard {
+    ($s:expr, $c:expr) => {{
+        let status: aptos_types::transaction::TransactionStatus = $s.into();
+        assert_eq!(
+            status,
+            aptos_types::transaction::TransactionStatus::Discard($c),
+            "{}",
+            $crate::abort_location::describe_status(&status)
+        );
+    }};
+}
+
+/// Helper to assert vm status code, e.g. `StatusCode::OUT_OF_GAS`, including the status codes of
+/// discarded transactions. Aborts are `StatusCode::ABORTED`, see `assert_abort_in!` to also check
+/// the module and code. Execution failures, such as arithmetic errors, carry no status code.
 #[macro_export]
 macro_rules! assert_vm_status {
     ($s:expr, $c:pat) => {{
-        use aptos_types::transaction::*;
-        assert!(matches!(
-            $s,
-            TransactionStatus::Keep(ExecutionStatus::MiscellaneousError(Some($c)))
-        ));
+        let status: aptos_types::transaction::TransactionStatus = $s.into();
+        assert!(
+            matches!($crate::abort_location::status_code(&status), Some($c)),
+            "unexpected status: {}",
+            $crate::abort_location::describe_status(&status)
+        );
     }};
 }
+
+/// Runs an entry function written like a Move call, encoding the arguments with BCS. Type
+/// arguments are given as string literals. Returns the `TxnSummary` of the transaction.
+///
+/// ```ignore
+/// aptos_call!(harness, &owner, 0x1::stake::unlock(25_000_000u64));
+/// aptos_call!(harness, &owner, 0x1::coin::transfer<"0x1::aptos_coin::AptosCoin">(to, 100u64));
+/// ```
+///
+/// Integer arguments should carry a type suffix, as the Move type is derived from the Rust type.
+/// Type arguments are only parsed when the call runs, so an invalid one panics at runtime rather
+/// than failing to compile.
+#[macro_export]
+macro_rules! aptos_call {
+    ($h:expr, $acc:expr, $addr:literal :: $module:ident :: $fun:ident
+        $(< $($ty:literal),* >)? ( $($arg:expr),* $(,)? )) => {
+        $h.run_entry_function(
+            $acc,
+            str::parse(concat!(
+                stringify!($addr),
+                "::",
+                stringify!($module),
+                "::",
+                stringify!($fun)
+            ))
+            .unwrap(),
+            vec![$($(
+                $crate::move_deps::move_core_types::parser::parse_type_tag($ty)
+                    .expect("type argument must parse")
+            ),*)?],
+            vec![$($crate::bcs::to_bytes(&$arg).expect("argument must have BCS")),*],
+        )
+    };
+}
@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Entry function builders for the framework at head, generated from the Move sources in the
+//! work tree by this crate's build script. In contrast to the builders in
+//! `cached_framework_packages::aptos_stdlib`, these always reflect the current sources, so
+//! brand-new entry functions can be called from tests before a framework release is cut.
+
+#[allow(dead_code, unused_imports, clippy::all)]
+mod aptos_framework_sdk_builder {
+    include!(concat!(env!("OUT_DIR"), "/head/framework_sdk_builder.rs"));
+}
+
+#[allow(dead_code, unused_imports, clippy::all)]
+pub mod aptos_token_stdlib {
+    include!(concat!(env!("OUT_DIR"), "/head/token_sdk_builder.rs"));
+}
+
+pub use aptos_framework_sdk_builder::*;
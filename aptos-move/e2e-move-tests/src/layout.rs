@@ -0,0 +1,150 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::harness::MoveHarness;
+use aptos_types::{account_address::AccountAddress, state_store::state_key::StateKey};
+use move_deps::{
+    move_binary_format::CompiledModule,
+    move_core_types::{
+        language_storage::{ModuleId, StructTag},
+        resolver::{ModuleResolver, ResourceResolver},
+    },
+    move_resource_viewer::MoveValueAnnotator,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// A set of framework modules used to resolve the type layouts of resources.
+pub struct FrameworkLayouts {
+    modules: BTreeMap<ModuleId, Vec<u8>>,
+}
+
+impl FrameworkLayouts {
+    /// The layouts of the cached (released) framework packages.
+    pub fn cached() -> Self {
+        Self::from_blobs(cached_framework_packages::module_blobs().iter().cloned())
+    }
+
+    /// The layouts of the framework built from the sources at head.
+    pub fn head() -> Self {
+        Self::from_blobs(framework::aptos::module_blobs())
+    }
+
+    fn from_blobs(blobs: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        let modules = blobs
+            .into_iter()
+            .map(|blob| {
+                let id = CompiledModule::deserialize(&blob)
+                    .expect("framework module must deserialize")
+                    .self_id();
+                (id, blob)
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// Deserializes a resource using these layouts and renders it, including field names.
+    fn view_resource(&self, tag: &StructTag, blob: &[u8]) -> Result<String, String> {
+        MoveValueAnnotator::new(self)
+            .view_resource(tag, blob)
+            .map(|value| value.to_string())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl ModuleResolver for FrameworkLayouts {
+    type Error = anyhow::Error;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.modules.get(module_id).cloned())
+    }
+}
+
+impl ResourceResolver for FrameworkLayouts {
+    type Error = anyhow::Error;
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+/// A resource whose bytes in storage cannot be interpreted the same way by two framework
+/// versions.
+#[derive(Clone, Debug)]
+pub struct LayoutIncompatibility {
+    pub address: AccountAddress,
+    pub tag: StructTag,
+    /// The resource as viewed with the cached layouts, or the deserialization error.
+    pub cached: Result<String, String>,
+    /// The resource as viewed with the head layouts, or the deserialization error.
+    pub head: Result<String, String>,
+}
+
+impl fmt::Display for LayoutIncompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "resource {} at {}:", self.tag, self.address)?;
+        writeln!(f, "  cached: {:?}", self.cached)?;
+        write!(f, "  head: {:?}", self.head)
+    }
+}
+
+/// Deserializes every framework resource the harness has written since
+/// `MoveHarness::record_written_state` was called, using both the cached and head framework
+/// layouts. A resource is incompatible if only one of the layouts can deserialize it, or if both
+/// can but disagree on its fields, as happens e.g. when fields of `StakePool` are added,
+/// removed, reordered or renamed.
+///
+/// Resources not declared by the cached framework, and resources deleted by the scenario, are
+/// skipped.
+pub fn check_layout_compatibility(harness: &mut MoveHarness) -> Vec<LayoutIncompatibility> {
+    let cached = FrameworkLayouts::cached();
+    let head = FrameworkLayouts::head();
+    let mut incompatibilities = vec![];
+    for key in harness.take_written_state() {
+        let path = match &key {
+            StateKey::AccessPath(path) => path,
+            _ => continue,
+        };
+        let tag = match path.get_struct_tag() {
+            Some(tag) => tag,
+            None => continue,
+        };
+        let module_id = ModuleId::new(tag.address, tag.module.clone());
+        if !cached.modules.contains_key(&module_id) {
+            continue;
+        }
+        let blob = match harness.read_state_value(&key) {
+            Some(blob) => blob,
+            None => continue,
+        };
+        let cached_view = cached.view_resource(&tag, &blob);
+        let head_view = head.view_resource(&tag, &blob);
+        if cached_view != head_view {
+            incompatibilities.push(LayoutIncompatibility {
+                address: path.address,
+                tag,
+                cached: cached_view,
+                head: head_view,
+            });
+        }
+    }
+    incompatibilities
+}
+
+/// Asserts that all framework resources written by the harness are layout compatible between
+/// the cached and head framework.
+pub fn assert_layout_compatible(harness: &mut MoveHarness) {
+    let incompatibilities = check_layout_compatibility(harness);
+    assert!(
+        incompatibilities.is_empty(),
+        "resources are not layout compatible between framework versions:\n{}",
+        incompatibilities
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
@@ -1,8 +1,52 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+// Allows `#[derive(MoveResource)]` to refer to this crate from within itself.
+extern crate self as e2e_move_tests;
+
+pub mod abort_location;
+pub mod account_factory;
+pub mod aggregator;
+pub mod clock;
+pub mod coin;
+pub mod contention;
+pub mod coverage;
+pub mod determinism;
+pub mod diff;
+pub mod epilogue;
+pub mod events;
+pub mod fairness;
+pub mod fixtures;
+pub mod gas_golden;
+pub mod gas_price;
+pub mod generators;
+pub mod genesis;
+pub mod governance;
 pub mod harness;
+pub mod head_framework;
+pub mod layout;
+pub mod lint;
+pub mod orphans;
+pub mod printer;
+pub mod resource;
+pub mod resource_account;
+pub mod scenarios;
 pub mod stake;
+pub mod stats;
+pub mod summary;
+pub mod token;
+pub mod writes;
 
+pub use coin::AptosCoin;
+pub use e2e_move_tests_derive::MoveResource;
 pub use harness::*;
+pub use resource::MoveResource;
 pub use stake::*;
+pub use summary::TxnSummary;
+pub use writes::WriteAllowlist;
+
+// Used by `aptos_call!`, so that crates using it don't need to depend on these directly.
+#[doc(hidden)]
+pub use bcs;
+#[doc(hidden)]
+pub use move_deps;
@@ -0,0 +1,80 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    account_address::AccountAddress, state_store::state_key::StateKey,
+    transaction::TransactionOutput, write_set::WriteOp,
+};
+use move_deps::move_core_types::{language_storage::StructTag, parser::parse_struct_tag};
+use std::collections::BTreeSet;
+
+/// Tracks how a test uses the accounts and stake pools it sets up, to report setup which is
+/// never used, e.g. after copy-pasting a scenario.
+///
+/// An account counts as used once it sends a transaction, any transaction writes state under
+/// its address, or the test reads state under its address. A stake pool counts as asserted once
+/// the test reads it.
+#[derive(Debug, Default)]
+pub struct UsageLint {
+    created_accounts: BTreeSet<AccountAddress>,
+    used_accounts: BTreeSet<AccountAddress>,
+    stake_pools: BTreeSet<AccountAddress>,
+    asserted_stake_pools: BTreeSet<AccountAddress>,
+}
+
+fn is_stake_pool(tag: &StructTag) -> bool {
+    tag == &parse_struct_tag("0x1::stake::StakePool").unwrap()
+}
+
+impl UsageLint {
+    pub fn account_created(&mut self, addr: AccountAddress) {
+        self.created_accounts.insert(addr);
+    }
+
+    pub fn account_used(&mut self, addr: AccountAddress) {
+        self.used_accounts.insert(addr);
+    }
+
+    /// Records the state written by a transaction.
+    pub fn observe(&mut self, output: &TransactionOutput) {
+        for (key, op) in output.write_set() {
+            if let StateKey::AccessPath(path) = key {
+                self.used_accounts.insert(path.address);
+                let created_pool = matches!(op, WriteOp::Value(_))
+                    && path
+                        .get_struct_tag()
+                        .map_or(false, |tag| is_stake_pool(&tag));
+                if created_pool {
+                    self.stake_pools.insert(path.address);
+                }
+            }
+        }
+    }
+
+    /// Records state read by the test.
+    pub fn state_read(&mut self, key: &StateKey) {
+        if let StateKey::AccessPath(path) = key {
+            self.used_accounts.insert(path.address);
+            if path
+                .get_struct_tag()
+                .map_or(false, |tag| is_stake_pool(&tag))
+            {
+                self.asserted_stake_pools.insert(path.address);
+            }
+        }
+    }
+
+    /// Returns a warning for every account created but never used, and every stake pool set up
+    /// but never asserted.
+    pub fn warnings(&self) -> Vec<String> {
+        let unused_accounts = self
+            .created_accounts
+            .difference(&self.used_accounts)
+            .map(|addr| format!("account {} was created but never used", addr));
+        let unasserted_pools = self
+            .stake_pools
+            .difference(&self.asserted_stake_pools)
+            .map(|addr| format!("stake pool {} was set up but never asserted", addr));
+        unused_accounts.chain(unasserted_pools).collect()
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_state_view::StateView;
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    event::EventKey,
+    state_store::{state_key::StateKey, table::TableHandle},
+    transaction::{TransactionOutput, TransactionStatus},
+    write_set::WriteOp,
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use language_e2e_tests::data_store::FakeDataStore;
+use move_deps::{
+    move_core_types::language_storage::{StructTag, TypeTag},
+    move_resource_viewer::{AnnotatedMoveValue, MoveValueAnnotator},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// State which was owned by a deleted resource but is still alive.
+#[derive(Clone, Debug)]
+pub enum OrphanedState {
+    /// Items of a table which was stored in a deleted resource.
+    TableItems {
+        handle: TableHandle,
+        owner: StructTag,
+        count: usize,
+    },
+    /// An event handle which was stored in a deleted resource but was emitted to afterwards.
+    EventHandle { key: EventKey, owner: StructTag },
+}
+
+impl fmt::Display for OrphanedState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrphanedState::TableItems {
+                handle,
+                owner,
+                count,
+            } => write!(
+                f,
+                "{} items of table {:?} owned by deleted resource {}",
+                count, handle, owner
+            ),
+            OrphanedState::EventHandle { key, owner } => write!(
+                f,
+                "events emitted to handle {:?} owned by deleted resource {}",
+                key, owner
+            ),
+        }
+    }
+}
+
+/// Handles found inside of Move values.
+#[derive(Debug, Default)]
+struct Handles {
+    /// Table handles, with the value type of the table.
+    tables: BTreeMap<TableHandle, TypeTag>,
+    event_keys: BTreeSet<EventKey>,
+}
+
+impl Handles {
+    fn collect(&mut self, value: &AnnotatedMoveValue) {
+        match value {
+            AnnotatedMoveValue::Vector(_, items) => {
+                for item in items {
+                    self.collect(item);
+                }
+            }
+            AnnotatedMoveValue::Struct(struct_value) => {
+                let tag = &struct_value.type_;
+                let field = |name: &str| {
+                    struct_value
+                        .value
+                        .iter()
+                        .find(|(field, _)| field.as_str() == name)
+                        .map(|(_, value)| value)
+                };
+                if is_framework_struct(tag, "table", "Table") {
+                    if let (Some(AnnotatedMoveValue::U128(handle)), Some(value_type)) =
+                        (field("handle"), tag.type_params.get(1))
+                    {
+                        self.tables.insert(TableHandle(*handle), value_type.clone());
+                    }
+                } else if is_framework_struct(tag, "event", "EventHandle") {
+                    if let Some(key) = field("guid").and_then(event_key_of_guid) {
+                        self.event_keys.insert(key);
+                    }
+                } else {
+                    for (_, field) in &struct_value.value {
+                        self.collect(field);
+                    }
+                }
+            }
+            AnnotatedMoveValue::U8(_)
+            | AnnotatedMoveValue::U64(_)
+            | AnnotatedMoveValue::U128(_)
+            | AnnotatedMoveValue::Bool(_)
+            | AnnotatedMoveValue::Address(_)
+            | AnnotatedMoveValue::Bytes(_) => {}
+        }
+    }
+}
+
+/// Detects state left behind when resources are deleted, e.g. by withdrawing all funds or
+/// cleaning up an account.
+///
+/// Deleting a resource which embeds a table leaves the table items in storage, where they can no
+/// longer be reached. Likewise, an event handle stored in a deleted resource should not be
+/// emitted to anymore. Since a deleted resource may have been moved elsewhere within the same
+/// transaction, handles are only reported if no live resource or reachable table item still
+/// references them.
+#[derive(Debug, Default)]
+pub struct OrphanedStateChecker {
+    /// Table handles found in deleted resources, with the deleted resource.
+    tables: BTreeMap<TableHandle, StructTag>,
+    /// Event handles found in deleted resources, with the deleted resource.
+    event_keys: BTreeMap<EventKey, StructTag>,
+    /// Event handles found in deleted resources which were emitted to afterwards.
+    emitted_after_deletion: BTreeSet<EventKey>,
+}
+
+impl OrphanedStateChecker {
+    /// Observes the output of a transaction before its write set is applied to `state`.
+    pub fn observe(&mut self, state: &FakeDataStore, output: &TransactionOutput) {
+        if !matches!(output.status(), TransactionStatus::Keep(_)) {
+            return;
+        }
+        for event in output.events() {
+            if self.event_keys.contains_key(event.key()) {
+                self.emitted_after_deletion.insert(*event.key());
+            }
+        }
+        let resolver = state.as_move_resolver();
+        let annotator = MoveValueAnnotator::new(&resolver);
+        for (key, op) in output.write_set().iter() {
+            let (tag, blob) = match (key, op) {
+                (StateKey::AccessPath(path), WriteOp::Deletion) => {
+                    match (path.get_struct_tag(), state.get_state_value(key)) {
+                        (Some(tag), Ok(Some(blob))) => (tag, blob),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            let value = match annotator.view_resource(&tag, &blob) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            let mut handles = Handles::default();
+            handles.collect(&AnnotatedMoveValue::Struct(value));
+            for handle in handles.tables.into_keys() {
+                self.tables.insert(handle, tag.clone());
+            }
+            for key in handles.event_keys {
+                self.event_keys.insert(key, tag.clone());
+            }
+        }
+    }
+
+    /// Returns true if no resource owning a table or event handle has been deleted so far.
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty() && self.event_keys.is_empty()
+    }
+
+    /// Returns the orphaned state in `state`.
+    pub fn check(&self, state: &FakeDataStore) -> Vec<OrphanedState> {
+        if self.tables.is_empty() && self.emitted_after_deletion.is_empty() {
+            return vec![];
+        }
+        let live = live_handles(state);
+        let mut orphans = vec![];
+        for (handle, owner) in &self.tables {
+            if live.tables.contains_key(handle) {
+                continue;
+            }
+            let count = state
+                .inner()
+                .keys()
+                .filter(|key| matches!(key, StateKey::TableItem { handle: h, .. } if h == handle))
+                .count();
+            if count > 0 {
+                orphans.push(OrphanedState::TableItems {
+                    handle: *handle,
+                    owner: owner.clone(),
+                    count,
+                });
+            }
+        }
+        for key in &self.emitted_after_deletion {
+            if !live.event_keys.contains(key) {
+                orphans.push(OrphanedState::EventHandle {
+                    key: *key,
+                    owner: self.event_keys[key].clone(),
+                });
+            }
+        }
+        orphans
+    }
+
+    /// Asserts that there is no orphaned state in `state`.
+    pub fn assert_no_orphans(&self, state: &FakeDataStore) {
+        let orphans = self.check(state);
+        assert!(
+            orphans.is_empty(),
+            "deleted resources left orphaned state behind:\n{}",
+            orphans
+                .iter()
+                .map(|orphan| orphan.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+/// Collects the handles referenced by all resources in `state`, and by the items of the tables
+/// reachable from them.
+fn live_handles(state: &FakeDataStore) -> Handles {
+    let resolver = state.as_move_resolver();
+    let annotator = MoveValueAnnotator::new(&resolver);
+    let mut handles = Handles::default();
+    for (key, blob) in state.inner() {
+        if let StateKey::AccessPath(path) = key {
+            if let Some(tag) = path.get_struct_tag() {
+                if let Ok(value) = annotator.view_resource(&tag, blob) {
+                    handles.collect(&AnnotatedMoveValue::Struct(value));
+                }
+            }
+        }
+    }
+    // Tables may be nested inside of other tables, so visit table items until no new tables are
+    // found.
+    let mut visited = BTreeSet::new();
+    loop {
+        let pending: Vec<_> = handles
+            .tables
+            .iter()
+            .filter(|(handle, _)| !visited.contains(*handle))
+            .map(|(handle, value_type)| (*handle, value_type.clone()))
+            .collect();
+        if pending.is_empty() {
+            return handles;
+        }
+        for (handle, value_type) in pending {
+            visited.insert(handle);
+            for (key, blob) in state.inner() {
+                if matches!(key, StateKey::TableItem { handle: h, .. } if *h == handle) {
+                    if let Ok(value) = annotator.view_value(&value_type, blob) {
+                        handles.collect(&value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_framework_struct(tag: &StructTag, module: &str, name: &str) -> bool {
+    tag.address == CORE_CODE_ADDRESS && tag.module.as_str() == module && tag.name.as_str() == name
+}
+
+/// Converts an annotated `0x1::guid::GUID` into the event key it identifies.
+fn event_key_of_guid(guid: &AnnotatedMoveValue) -> Option<EventKey> {
+    let id = match guid {
+        AnnotatedMoveValue::Struct(guid) => match guid.value.first() {
+            Some((_, AnnotatedMoveValue::Struct(id))) => id,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match id.value.as_slice() {
+        [(_, AnnotatedMoveValue::U64(creation_num)), (_, AnnotatedMoveValue::Address(addr))] => {
+            Some(EventKey::new(*creation_num, *addr))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::diff::json_diff;
+use aptos_api_types::AsConverter;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::Path,
+    state_store::state_key::StateKey,
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use move_deps::move_core_types::language_storage::StructTag;
+use serde_json::Value;
+use std::{fmt::Write, sync::Arc};
+use storage_interface::mock::MockDbReaderWriter;
+
+/// Renders a write set in human-readable form, one line per written state key. Resources are
+/// rendered as `0x1::stake::StakePool at 0x123 { field: old -> new, .. }`, decoded with the
+/// annotator using the layouts found in `before`, which must be the state the write set applies
+/// to. Resources that cannot be decoded, table items and modules are rendered by their size.
+pub fn render_write_set<S: StateView>(before: &S, write_set: &WriteSet) -> String {
+    let resolver = before.as_move_resolver();
+    let converter = resolver.as_converter(Arc::new(MockDbReaderWriter));
+    let to_json = |tag: &StructTag, blob: &[u8]| -> Option<Value> {
+        let resource = converter.try_into_resource(tag, blob).ok()?;
+        serde_json::to_value(&resource.data).ok()
+    };
+    let mut out = String::new();
+    for (key, op) in write_set {
+        let old = before.get_state_value(key).ok().flatten();
+        let new = match op {
+            WriteOp::Value(blob) => Some(blob),
+            WriteOp::Deletion => None,
+        };
+        let marker = match (&old, &new) {
+            (None, Some(_)) => "+",
+            (Some(_), None) => "-",
+            _ => "~",
+        };
+        match key {
+            StateKey::AccessPath(path) => match path.get_path() {
+                Path::Resource(tag) => {
+                    write!(out, "{} {} at {}", marker, tag, path.address).unwrap();
+                    let old = old.as_ref().and_then(|blob| to_json(&tag, blob));
+                    let new = new.and_then(|blob| to_json(&tag, blob));
+                    match (old, new) {
+                        (Some(old), Some(new)) => {
+                            let fields: Vec<_> = json_diff(&old, &new)
+                                .into_iter()
+                                .map(|diff| {
+                                    let show = |value: Option<Value>| {
+                                        value.map_or("<missing>".to_string(), |v| v.to_string())
+                                    };
+                                    format!(
+                                        "{}: {} -> {}",
+                                        diff.path,
+                                        show(diff.left),
+                                        show(diff.right)
+                                    )
+                                })
+                                .collect();
+                            write!(out, " {{ {} }}", fields.join(", ")).unwrap();
+                        }
+                        (None, Some(value)) | (Some(value), None) => {
+                            write!(out, " {}", value).unwrap();
+                        }
+                        (None, None) => {
+                            if let Some(blob) = new {
+                                write!(out, " ({} bytes)", blob.len()).unwrap();
+                            }
+                        }
+                    }
+                }
+                Path::Code(module_id) => {
+                    write!(out, "{} module {}", marker, module_id.short_str_lossless()).unwrap();
+                    if let Some(blob) = new {
+                        write!(out, " ({} bytes)", blob.len()).unwrap();
+                    }
+                }
+            },
+            StateKey::TableItem { handle, key } => {
+                write!(
+                    out,
+                    "{} table item {:?}[0x{}]",
+                    marker,
+                    handle,
+                    hex::encode(key)
+                )
+                .unwrap();
+                if let Some(blob) = new {
+                    write!(out, " ({} bytes)", blob.len()).unwrap();
+                }
+            }
+            StateKey::Raw(raw) => {
+                write!(out, "{} raw 0x{}", marker, hex::encode(raw)).unwrap();
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
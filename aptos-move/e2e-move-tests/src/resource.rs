@@ -0,0 +1,37 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use move_deps::move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::de::DeserializeOwned;
+
+/// A Rust mirror of a Move resource which can be read via `MoveHarness::read_move_resource`,
+/// or of a Move event struct which can be matched via `assert_event!`. Usually implemented via
+/// `#[derive(MoveResource)]`.
+pub trait MoveResource: DeserializeOwned {
+    /// The address the Move module is published under, e.g. `0x1`.
+    const ADDRESS: &'static str;
+    const MODULE_NAME: &'static str;
+    const STRUCT_NAME: &'static str;
+
+    /// The type arguments of the Move struct, e.g. the coin type of a `0x1::coin::CoinStore`.
+    /// The derive passes the struct tags of the Rust type parameters, in order.
+    fn type_params() -> Vec<TypeTag> {
+        vec![]
+    }
+
+    fn struct_tag() -> StructTag {
+        StructTag {
+            address: AccountAddress::from_hex_literal(Self::ADDRESS)
+                .expect("MoveResource address must be a hex literal"),
+            module: Identifier::new(Self::MODULE_NAME)
+                .expect("MoveResource module must be an identifier"),
+            name: Identifier::new(Self::STRUCT_NAME)
+                .expect("MoveResource name must be an identifier"),
+            type_params: Self::type_params(),
+        }
+    }
+}
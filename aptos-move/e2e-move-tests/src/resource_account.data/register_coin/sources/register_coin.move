@@ -0,0 +1,10 @@
+script {
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coins;
+
+    /// Registers a coin store for the resource account, the secondary signer, so it can pay for
+    /// its own transactions. The sender pays for this one.
+    fun main(_origin: &signer, resource: &signer) {
+        coins::register_internal<AptosCoin>(resource);
+    }
+}
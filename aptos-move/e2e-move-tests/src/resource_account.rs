@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resource accounts from Rust, following `0x1::resource_account`.
+
+use crate::{account_factory::DEFAULT_BALANCE, assert_success, harness::MoveHarness, MoveResource};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{Script, TransactionPayload},
+};
+use cached_framework_packages::aptos_stdlib;
+use framework::natives::code::UpgradePolicy;
+use language_e2e_tests::account::Account;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Mimics `0x1::account::SignerCapability`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignerCapability {
+    pub account: AccountAddress,
+}
+
+/// Mimics `0x1::resource_account::Container`. The `SimpleMap` is represented by its entries,
+/// which serialize the same way.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "resource_account")]
+pub struct Container {
+    pub store: Vec<(AccountAddress, SignerCapability)>,
+}
+
+/// Returns the address of the resource account created by `source` with `seed`, as computed by
+/// `0x1::account::create_resource_account`.
+pub fn resource_account_address(source: &AccountAddress, seed: &[u8]) -> AccountAddress {
+    let mut bytes = bcs::to_bytes(source).unwrap();
+    bytes.extend_from_slice(seed);
+    AccountAddress::from_bytes(HashValue::sha3_256_of(&bytes).to_vec()).unwrap()
+}
+
+/// Returns the signer capabilities `origin` holds for its resource accounts which have not been
+/// retrieved yet, if any.
+pub fn get_container(harness: &MoveHarness, origin: &AccountAddress) -> Option<Container> {
+    harness.read_move_resource::<Container>(origin)
+}
+
+/// Creates the resource account of `origin` for `seed` via
+/// `0x1::resource_account::create_resource_account`, which keeps its signer capability in
+/// origin's `Container` and gives it origin's authentication key. Returns the account, signing
+/// with origin's key.
+///
+/// The resource account is given a coin store funded with `DEFAULT_BALANCE`, so it can pay for
+/// its own transactions.
+pub fn create_resource_account(
+    harness: &mut MoveHarness,
+    origin: &Account,
+    seed: &[u8],
+) -> Account {
+    assert!(
+        origin.multisig().is_none(),
+        "resource accounts of multisig accounts are not supported"
+    );
+    assert_success!(harness.run_transaction_payload(
+        origin,
+        aptos_stdlib::resource_account_create_resource_account(seed.to_vec(), vec![]),
+    ));
+    let addr = resource_account_address(origin.address(), seed);
+    let resource = Account::new_validator(addr, origin.privkey.clone(), origin.pubkey.clone());
+    harness.add_existing_account(&resource);
+
+    // Origin pays for the registration, as the resource account has no coins yet.
+    let code = MoveHarness::compile_script(
+        &PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("resource_account.data")
+            .join("register_coin"),
+    );
+    assert_success!(harness.run_multi_agent(
+        origin,
+        &[resource.clone()],
+        TransactionPayload::Script(Script::new(code, vec![], vec![])),
+    ));
+    harness.fund(&addr, DEFAULT_BALANCE);
+    resource
+}
+
+/// Creates the resource account of `origin` for `seed`, see `create_resource_account`, and
+/// publishes the package at `path` from it. The package's `init_module` is expected to retrieve
+/// the signer capability via `0x1::resource_account::retrieve_resource_account_cap`, which
+/// leaves the account without a usable authentication key.
+///
+/// The framework has no `create_resource_account_and_publish_package`, so this takes two
+/// transactions instead of one.
+pub fn create_resource_account_and_publish_package(
+    harness: &mut MoveHarness,
+    origin: &Account,
+    seed: &[u8],
+    path: &Path,
+) -> Account {
+    let resource = create_resource_account(harness, origin, seed);
+    assert_success!(harness.publish_package(&resource, path, UpgradePolicy::compat()));
+    resource
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Well-known staking states which tests can start from, following the patterns in
+//! `tests/stake.rs`. All scenarios run on test genesis.
+
+use crate::{
+    assert_success,
+    harness::MoveHarness,
+    stake::{leave_validator_set, setup_staking, unlock_stake},
+};
+use aptos_types::account_address::AccountAddress;
+use language_e2e_tests::account::Account;
+
+/// The stake of the validator in `single_validator_active` and
+/// `validator_pending_inactive_with_unlocked_stake`.
+pub const VALIDATOR_STAKE: u64 = 50_000_000;
+/// The stakes of the validators in `two_validators_unequal_stake`. Together they stay within the
+/// voting power increase limit of a single epoch.
+pub const SMALL_VALIDATOR_STAKE: u64 = 10_000_000;
+pub const LARGE_VALIDATOR_STAKE: u64 = 40_000_000;
+/// The stake unlocked in `validator_pending_inactive_with_unlocked_stake`.
+pub const UNLOCKED_STAKE: u64 = 10_000_000;
+
+fn validator_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x123").unwrap()
+}
+
+fn second_validator_address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x234").unwrap()
+}
+
+/// A validator at `0x123` with `VALIDATOR_STAKE` in the active validator set, right after the
+/// epoch change which activated it.
+pub fn single_validator_active() -> (MoveHarness, Account) {
+    let mut harness = MoveHarness::new();
+    let validator = harness.new_account_at(validator_address());
+    assert_success!(setup_staking(&mut harness, &validator, VALIDATOR_STAKE));
+    harness.new_epoch();
+    (harness, validator)
+}
+
+/// Two active validators, at `0x123` with `SMALL_VALIDATOR_STAKE` and at `0x234` with
+/// `LARGE_VALIDATOR_STAKE`, in this order.
+pub fn two_validators_unequal_stake() -> (MoveHarness, Account, Account) {
+    let mut harness = MoveHarness::new();
+    let small = harness.new_account_at(validator_address());
+    let large = harness.new_account_at(second_validator_address());
+    assert_success!(setup_staking(&mut harness, &small, SMALL_VALIDATOR_STAKE));
+    assert_success!(setup_staking(&mut harness, &large, LARGE_VALIDATOR_STAKE));
+    harness.new_epoch();
+    (harness, small, large)
+}
+
+/// A validator at `0x123` with `VALIDATOR_STAKE`, of which `UNLOCKED_STAKE` is unlocked and thus
+/// pending_inactive, which has requested to leave the validator set and is pending_inactive
+/// itself.
+pub fn validator_pending_inactive_with_unlocked_stake() -> (MoveHarness, Account) {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    assert_success!(unlock_stake(&mut harness, &validator, UNLOCKED_STAKE));
+    assert_success!(leave_validator_set(
+        &mut harness,
+        &validator,
+        validator_address,
+    ));
+    (harness, validator)
+}
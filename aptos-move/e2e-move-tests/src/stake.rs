@@ -1,22 +1,126 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::harness::MoveHarness;
+use crate::{harness::MoveHarness, MoveResource};
 use aptos_crypto::{bls12381, PrivateKey, Uniform};
 use aptos_types::{
     account_address::AccountAddress, account_config::CORE_CODE_ADDRESS,
-    on_chain_config::ValidatorSet, stake_pool::StakePool, transaction::TransactionStatus,
-    validator_config::ValidatorConfig,
+    on_chain_config::ValidatorSet, stake_pool::StakePool, validator_config::ValidatorConfig,
+    validator_info::ValidatorInfo,
 };
 use cached_framework_packages::aptos_stdlib;
 use language_e2e_tests::account::Account;
 use move_deps::move_core_types::parser::parse_struct_tag;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryFrom, ops::Deref};
+
+/// Mimics `0x1::staking_config::StakingConfig`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "staking_config")]
+pub struct StakingConfig {
+    pub minimum_stake: u64,
+    pub maximum_stake: u64,
+    pub recurring_lockup_duration_secs: u64,
+    pub allow_validator_set_change: bool,
+    pub rewards_rate: u64,
+    pub rewards_rate_denominator: u64,
+    pub voting_power_increase_limit: u64,
+}
+
+/// Mimics `0x1::stake::DistributeRewardsEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct DistributeRewardsEvent {
+    pub pool_address: AccountAddress,
+    pub rewards_amount: u64,
+}
+
+/// Mimics `0x1::stake::AddStakeEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct AddStakeEvent {
+    pub pool_address: AccountAddress,
+    pub amount_added: u64,
+}
+
+/// Mimics `0x1::stake::JoinValidatorSetEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct JoinValidatorSetEvent {
+    pub pool_address: AccountAddress,
+}
+
+/// Mimics `0x1::stake::UnlockStakeEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct UnlockStakeEvent {
+    pub pool_address: AccountAddress,
+    pub amount_unlocked: u64,
+}
+
+/// Mimics `0x1::stake::WithdrawStakeEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct WithdrawStakeEvent {
+    pub pool_address: AccountAddress,
+    pub amount_withdrawn: u64,
+}
+
+/// Mimics `0x1::stake::LeaveValidatorSetEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct LeaveValidatorSetEvent {
+    pub pool_address: AccountAddress,
+}
+
+/// Mimics `0x1::stake::SetOperatorEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct SetOperatorEvent {
+    pub pool_address: AccountAddress,
+    pub old_operator: AccountAddress,
+    pub new_operator: AccountAddress,
+}
+
+/// Mimics `0x1::stake::UpdateNetworkAndFullnodeAddressesEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct UpdateNetworkAndFullnodeAddressesEvent {
+    pub pool_address: AccountAddress,
+    pub old_network_addresses: Vec<u8>,
+    pub new_network_addresses: Vec<u8>,
+    pub old_fullnode_addresses: Vec<u8>,
+    pub new_fullnode_addresses: Vec<u8>,
+}
+
+/// Mimics `0x1::stake::IncreaseLockupEvent`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct IncreaseLockupEvent {
+    pub pool_address: AccountAddress,
+    pub old_locked_until_secs: u64,
+    pub new_locked_until_secs: u64,
+}
+
+/// Mimics `0x1::stake::IndividualValidatorPerformance`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IndividualValidatorPerformance {
+    pub successful_proposals: u64,
+    pub failed_proposals: u64,
+}
+
+/// Mimics `0x1::stake::ValidatorPerformance`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "stake")]
+pub struct ValidatorPerformance {
+    pub validators: Vec<IndividualValidatorPerformance>,
+}
 
 pub fn setup_staking(
     harness: &mut MoveHarness,
     account: &Account,
     initial_stake_amount: u64,
-) -> TransactionStatus {
+) -> TxnSummary {
     let address = *account.address();
     initialize_staking(harness, account, initial_stake_amount, address, address);
     rotate_consensus_key(harness, account, address);
@@ -29,7 +133,7 @@ pub fn initialize_staking(
     initial_stake_amount: u64,
     operator_address: AccountAddress,
     voter_address: AccountAddress,
-) -> TransactionStatus {
+) -> TxnSummary {
     harness.run_transaction_payload(
         account,
         aptos_stdlib::stake_initialize_owner_only(
@@ -40,23 +144,15 @@ pub fn initialize_staking(
     )
 }
 
-pub fn add_stake(harness: &mut MoveHarness, account: &Account, amount: u64) -> TransactionStatus {
+pub fn add_stake(harness: &mut MoveHarness, account: &Account, amount: u64) -> TxnSummary {
     harness.run_transaction_payload(account, aptos_stdlib::stake_add_stake(amount))
 }
 
-pub fn unlock_stake(
-    harness: &mut MoveHarness,
-    account: &Account,
-    amount: u64,
-) -> TransactionStatus {
+pub fn unlock_stake(harness: &mut MoveHarness, account: &Account, amount: u64) -> TxnSummary {
     harness.run_transaction_payload(account, aptos_stdlib::stake_unlock(amount))
 }
 
-pub fn withdraw_stake(
-    harness: &mut MoveHarness,
-    account: &Account,
-    amount: u64,
-) -> TransactionStatus {
+pub fn withdraw_stake(harness: &mut MoveHarness, account: &Account, amount: u64) -> TxnSummary {
     harness.run_transaction_payload(account, aptos_stdlib::stake_withdraw(amount))
 }
 
@@ -64,7 +160,7 @@ pub fn join_validator_set(
     harness: &mut MoveHarness,
     account: &Account,
     pool_address: AccountAddress,
-) -> TransactionStatus {
+) -> TxnSummary {
     harness.run_transaction_payload(
         account,
         aptos_stdlib::stake_join_validator_set(pool_address),
@@ -75,12 +171,30 @@ pub fn rotate_consensus_key(
     harness: &mut MoveHarness,
     account: &Account,
     pool_address: AccountAddress,
-) -> TransactionStatus {
+) -> TxnSummary {
     let consensus_key = bls12381::PrivateKey::generate_for_testing();
+    rotate_consensus_key_with(harness, account, pool_address, &consensus_key, false)
+}
+
+/// Rotates the consensus key of the validator at `pool_address`, which `account` must operate, to
+/// the public key of `consensus_key`, along with a proof of possession of it. If
+/// `corrupt_proof_of_possession` is set, the proof is made with another key instead, which
+/// `0x1::stake::rotate_consensus_key` must reject.
+pub fn rotate_consensus_key_with(
+    harness: &mut MoveHarness,
+    account: &Account,
+    pool_address: AccountAddress,
+    consensus_key: &bls12381::PrivateKey,
+    corrupt_proof_of_possession: bool,
+) -> TxnSummary {
     let consensus_pubkey = consensus_key.public_key().to_bytes().to_vec();
-    let proof_of_possession = bls12381::ProofOfPossession::create(&consensus_key)
-        .to_bytes()
-        .to_vec();
+    let proof_of_possession = if corrupt_proof_of_possession {
+        bls12381::ProofOfPossession::create(&bls12381::PrivateKey::generate_for_testing())
+    } else {
+        bls12381::ProofOfPossession::create(consensus_key)
+    }
+    .to_bytes()
+    .to_vec();
     harness.run_transaction_payload(
         account,
         aptos_stdlib::stake_rotate_consensus_key(
@@ -95,13 +209,59 @@ pub fn leave_validator_set(
     harness: &mut MoveHarness,
     account: &Account,
     pool_address: AccountAddress,
-) -> TransactionStatus {
+) -> TxnSummary {
     harness.run_transaction_payload(
         account,
         aptos_stdlib::stake_leave_validator_set(pool_address),
     )
 }
 
+/// Makes `operator_address` the operator of the stake pool owned by `account`.
+pub fn set_operator(
+    harness: &mut MoveHarness,
+    account: &Account,
+    operator_address: AccountAddress,
+) -> TxnSummary {
+    harness.run_transaction_payload(account, aptos_stdlib::stake_set_operator(operator_address))
+}
+
+/// Makes `voter_address` the delegated voter of the stake pool owned by `account`.
+pub fn set_delegated_voter(
+    harness: &mut MoveHarness,
+    account: &Account,
+    voter_address: AccountAddress,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        account,
+        aptos_stdlib::stake_set_delegated_voter(voter_address),
+    )
+}
+
+/// Renews the lockup of the stake pool owned by `account` to a full recurring lockup duration
+/// from now.
+pub fn increase_lockup(harness: &mut MoveHarness, account: &Account) -> TxnSummary {
+    harness.run_transaction_payload(account, aptos_stdlib::stake_increase_lockup())
+}
+
+/// Updates the addresses of the validator at `pool_address`, which `account` must operate. Takes
+/// effect in the next epoch.
+pub fn update_network_and_fullnode_addresses(
+    harness: &mut MoveHarness,
+    account: &Account,
+    pool_address: AccountAddress,
+    network_addresses: Vec<u8>,
+    fullnode_addresses: Vec<u8>,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        account,
+        aptos_stdlib::stake_update_network_and_fullnode_addresses(
+            pool_address,
+            network_addresses,
+            fullnode_addresses,
+        ),
+    )
+}
+
 pub fn get_stake_pool(harness: &MoveHarness, pool_address: &AccountAddress) -> StakePool {
     harness
         .read_resource::<StakePool>(
@@ -111,6 +271,14 @@ pub fn get_stake_pool(harness: &MoveHarness, pool_address: &AccountAddress) -> S
         .unwrap()
 }
 
+/// Returns the seconds until the lockup of the stake pool at `pool_address` expires, or zero if
+/// it has expired. Like on chain, the block time is rounded down to whole seconds.
+pub fn get_lockup_remaining_secs(harness: &MoveHarness, pool_address: &AccountAddress) -> u64 {
+    get_stake_pool(harness, pool_address)
+        .locked_until_secs
+        .saturating_sub(harness.get_block_time_secs())
+}
+
 pub fn get_validator_config(
     harness: &MoveHarness,
     pool_address: &AccountAddress,
@@ -123,11 +291,168 @@ pub fn get_validator_config(
         .unwrap()
 }
 
-pub fn get_validator_set(harness: &MoveHarness) -> ValidatorSet {
+/// Returns the proposals of the active validators in the current epoch, by validator index, as
+/// recorded by the block prologues. The counts are reset on every epoch change, after they
+/// determined the rewards.
+pub fn get_validator_performance(harness: &MoveHarness) -> Vec<IndividualValidatorPerformance> {
     harness
+        .read_move_resource::<ValidatorPerformance>(&CORE_CODE_ADDRESS)
+        .unwrap()
+        .validators
+}
+
+pub fn get_validator_set(harness: &MoveHarness) -> ValidatorSetView {
+    let set = harness
         .read_resource::<ValidatorSet>(
             &CORE_CODE_ADDRESS,
             parse_struct_tag("0x1::stake::ValidatorSet").unwrap(),
         )
+        .unwrap();
+    let indices = set
+        .active_validators
+        .iter()
+        .chain(set.pending_inactive.iter())
+        .map(|info| {
+            let config = get_validator_config(harness, &info.account_address);
+            (info.account_address, config.validator_index as u32)
+        })
+        .collect();
+    ValidatorSetView { set, indices }
+}
+
+/// The state of a validator within the validator set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidatorState {
+    PendingActive,
+    Active,
+    PendingInactive,
+}
+
+/// The validator set as returned by `get_validator_set`, with lookups by address and index.
+/// Derefs to the on-chain `ValidatorSet` for direct access to its fields.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidatorSetView {
+    set: ValidatorSet,
+    /// The indices of the validators of the current epoch, from their validator configs.
+    indices: BTreeMap<AccountAddress, u32>,
+}
+
+impl ValidatorSetView {
+    /// Returns the state and info of the validator at `addr`, if it is in the set.
+    pub fn find(&self, addr: &AccountAddress) -> Option<(ValidatorState, &ValidatorInfo)> {
+        let groups = [
+            (ValidatorState::PendingActive, &self.set.pending_active),
+            (ValidatorState::Active, &self.set.active_validators),
+            (ValidatorState::PendingInactive, &self.set.pending_inactive),
+        ];
+        for (state, validators) in groups {
+            if let Some(info) = validators.iter().find(|info| info.account_address == *addr) {
+                return Some((state, info));
+            }
+        }
+        None
+    }
+
+    /// Returns the state of the validator at `addr`, if it is in the set.
+    pub fn state_of(&self, addr: &AccountAddress) -> Option<ValidatorState> {
+        self.find(addr).map(|(state, _)| state)
+    }
+
+    pub fn contains_active(&self, addr: &AccountAddress) -> bool {
+        self.state_of(addr) == Some(ValidatorState::Active)
+    }
+
+    pub fn contains_pending_active(&self, addr: &AccountAddress) -> bool {
+        self.state_of(addr) == Some(ValidatorState::PendingActive)
+    }
+
+    pub fn contains_pending_inactive(&self, addr: &AccountAddress) -> bool {
+        self.state_of(addr) == Some(ValidatorState::PendingInactive)
+    }
+
+    /// Returns the voting power of the current epoch, i.e. of the active and pending_inactive
+    /// validators, as recorded on chain at the last epoch change.
+    pub fn total_voting_power(&self) -> u128 {
+        self.set.total_voting_power
+    }
+
+    /// Returns the index of the validator at `addr` in the current epoch, which is used as
+    /// proposer index in block metadata. Only active and pending_inactive validators have one.
+    pub fn index_of(&self, addr: &AccountAddress) -> Option<u32> {
+        self.indices.get(addr).copied()
+    }
+
+    /// Returns the validator of the current epoch with index `index`, see `index_of`.
+    pub fn at_index(&self, index: u32) -> Option<&ValidatorInfo> {
+        let (addr, _) = self.indices.iter().find(|(_, idx)| **idx == index)?;
+        self.find(addr).map(|(_, info)| info)
+    }
+}
+
+impl Deref for ValidatorSetView {
+    type Target = ValidatorSet;
+
+    fn deref(&self) -> &ValidatorSet {
+        &self.set
+    }
+}
+
+pub fn get_staking_config(harness: &MoveHarness) -> StakingConfig {
+    harness
+        .read_move_resource::<StakingConfig>(&CORE_CODE_ADDRESS)
         .unwrap()
 }
+
+/// Sets the rewards rate to `rewards_rate / rewards_rate_denominator` per epoch. Unlike
+/// `staking_config::update_rewards_rate`, this accepts any values, including a zero denominator.
+pub fn set_rewards_rate(
+    harness: &mut MoveHarness,
+    rewards_rate: u64,
+    rewards_rate_denominator: u64,
+) {
+    let mut config = get_staking_config(harness);
+    config.rewards_rate = rewards_rate;
+    config.rewards_rate_denominator = rewards_rate_denominator;
+    harness.write_move_resource(&CORE_CODE_ADDRESS, &config);
+}
+
+/// Returns the rewards of `stake_amount` for the current epoch under the on-chain rewards rate,
+/// given the validator's proposals, i.e. what it will receive on the next epoch change, see
+/// `expected_rewards`. Panics if the computation overflows.
+pub fn expected_epoch_rewards(
+    harness: &MoveHarness,
+    stake_amount: u64,
+    num_successful_proposals: u64,
+    num_total_proposals: u64,
+) -> u64 {
+    let config = get_staking_config(harness);
+    expected_rewards(
+        stake_amount,
+        num_successful_proposals,
+        num_total_proposals,
+        config.rewards_rate,
+        config.rewards_rate_denominator,
+    )
+    .expect("rewards must not overflow")
+}
+
+/// Mirrors `stake::calculate_rewards_amount`: the rewards of `stake_amount` for an epoch, given
+/// the validator's proposals and the rewards rate, rounded down. Returns `None` if the on-chain
+/// computation aborts on an arithmetic overflow, which fails the epoch change.
+pub fn expected_rewards(
+    stake_amount: u64,
+    num_successful_proposals: u64,
+    num_total_proposals: u64,
+    rewards_rate: u64,
+    rewards_rate_denominator: u64,
+) -> Option<u64> {
+    let numerator = (stake_amount as u128)
+        .checked_mul(rewards_rate as u128)?
+        .checked_mul(num_successful_proposals as u128)?;
+    let denominator =
+        (rewards_rate_denominator as u128).checked_mul(num_total_proposals as u128)?;
+    if denominator == 0 {
+        return Some(0);
+    }
+    u64::try_from(numerator / denominator).ok()
+}
@@ -0,0 +1,67 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_state_view::StateView;
+use aptos_types::{transaction::TransactionOutput, write_set::WriteOp};
+use std::ops::AddAssign;
+
+/// Execution statistics of transactions, for asserting performance budgets, e.g.
+/// `assert!(harness.last_txn_stats().slots_created <= 2)`. Available for the last transaction
+/// via `MoveHarness::last_txn_stats`, and added up over all transactions via
+/// `MoveHarness::total_stats`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionStats {
+    /// The number of transactions, including block prologues.
+    pub transactions: u64,
+    /// The gas units used, regardless of the gas unit price.
+    pub gas_used: u64,
+    /// The number of state slots which did not exist before being written.
+    pub slots_created: u64,
+    /// The number of existing state slots which were deleted.
+    pub slots_deleted: u64,
+    /// The size of the values written, without the state keys.
+    pub bytes_written: u64,
+    /// The number of events emitted.
+    pub events: u64,
+}
+
+impl ExecutionStats {
+    /// Computes the statistics of a transaction output, against `before`, the state it applies
+    /// to.
+    pub(crate) fn new<S: StateView>(before: &S, output: &TransactionOutput) -> Self {
+        let mut stats = Self {
+            transactions: 1,
+            gas_used: output.gas_used(),
+            events: output.events().len() as u64,
+            ..Self::default()
+        };
+        for (key, op) in output.write_set().iter() {
+            let existed = before.get_state_value(key).ok().flatten().is_some();
+            match op {
+                WriteOp::Value(blob) => {
+                    stats.bytes_written += blob.len() as u64;
+                    if !existed {
+                        stats.slots_created += 1;
+                    }
+                }
+                WriteOp::Deletion => {
+                    if existed {
+                        stats.slots_deleted += 1;
+                    }
+                }
+            }
+        }
+        stats
+    }
+}
+
+impl AddAssign for ExecutionStats {
+    fn add_assign(&mut self, other: Self) {
+        self.transactions += other.transactions;
+        self.gas_used += other.gas_used;
+        self.slots_created += other.slots_created;
+        self.slots_deleted += other.slots_deleted;
+        self.bytes_written += other.bytes_written;
+        self.events += other.events;
+    }
+}
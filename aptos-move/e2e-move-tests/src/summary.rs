@@ -0,0 +1,91 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::StateKey,
+    transaction::{TransactionOutput, TransactionStatus},
+};
+
+/// The result of running a transaction via the harness, e.g. via `MoveHarness::run`. Can be
+/// compared against a `TransactionStatus` and passed to the `assert_*!` macros directly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TxnSummary {
+    status: TransactionStatus,
+    gas_used: u64,
+    gas_unit_price: u64,
+    events: Vec<ContractEvent>,
+    written_keys: Vec<StateKey>,
+    sequence_number: Option<u64>,
+}
+
+impl TxnSummary {
+    /// Summarizes the output of a transaction with the given gas unit price. `sequence_number`
+    /// is the sequence number of the sender after the transaction was applied, if it exists.
+    pub(crate) fn new(
+        output: &TransactionOutput,
+        gas_unit_price: u64,
+        sequence_number: Option<u64>,
+    ) -> Self {
+        Self {
+            status: output.status().clone(),
+            gas_used: output.gas_used(),
+            gas_unit_price,
+            events: output.events().to_vec(),
+            written_keys: output
+                .write_set()
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect(),
+            sequence_number,
+        }
+    }
+
+    pub fn status(&self) -> &TransactionStatus {
+        &self.status
+    }
+
+    /// Returns the gas units used. The VM does not report how they break down by operation.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    pub fn gas_unit_price(&self) -> u64 {
+        self.gas_unit_price
+    }
+
+    /// Returns the fee charged to the sender, in Aptos coins. Discarded transactions are free.
+    pub fn fee_charged(&self) -> u64 {
+        match self.status {
+            TransactionStatus::Keep(_) => self.gas_used * self.gas_unit_price,
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => 0,
+        }
+    }
+
+    pub fn events(&self) -> &[ContractEvent] {
+        &self.events
+    }
+
+    /// Returns the state keys written by the transaction, in write set order.
+    pub fn written_keys(&self) -> &[StateKey] {
+        &self.written_keys
+    }
+
+    /// Returns the sequence number of the sender after the transaction, i.e. the sequence number
+    /// of its next transaction, or `None` if the sender does not exist.
+    pub fn sequence_number(&self) -> Option<u64> {
+        self.sequence_number
+    }
+}
+
+impl PartialEq<TransactionStatus> for TxnSummary {
+    fn eq(&self, other: &TransactionStatus) -> bool {
+        self.status == *other
+    }
+}
+
+impl From<TxnSummary> for TransactionStatus {
+    fn from(summary: TxnSummary) -> Self {
+        summary.status
+    }
+}
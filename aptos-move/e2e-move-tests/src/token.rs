@@ -0,0 +1,333 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tokens from Rust, following `0x3::token` and `0x3::token_transfers`. Tokens live in tables,
+//! so the mirrored resources hold `TableHandle`s and the getters read the table items.
+
+use crate::{harness::MoveHarness, summary::TxnSummary, MoveResource};
+use aptos_types::{
+    account_address::AccountAddress, event::EventHandle, state_store::table::TableHandle,
+};
+use cached_framework_packages::aptos_token_sdk_builder;
+use language_e2e_tests::account::Account;
+use serde::{Deserialize, Serialize};
+
+/// Mimics `0x3::token::TokenDataId`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenDataId {
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub name: String,
+}
+
+impl TokenDataId {
+    pub fn new(creator: AccountAddress, collection: &str, name: &str) -> Self {
+        Self {
+            creator,
+            collection: collection.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// Mimics `0x3::token::TokenId`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenId {
+    pub token_data_id: TokenDataId,
+    pub property_version: u64,
+}
+
+impl TokenId {
+    /// The id of the tokens with `property_version` 0, i.e. the ones minted.
+    pub fn new(creator: AccountAddress, collection: &str, name: &str) -> Self {
+        Self {
+            token_data_id: TokenDataId::new(creator, collection, name),
+            property_version: 0,
+        }
+    }
+}
+
+/// Mimics `0x3::property_map::PropertyValue`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PropertyValue {
+    pub value: Vec<u8>,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// Mimics `0x3::property_map::PropertyMap`. The `SimpleMap` is represented by its entries, which
+/// serialize the same way.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PropertyMap {
+    pub map: Vec<(String, PropertyValue)>,
+}
+
+/// Mimics `0x3::token::Token`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+    pub id: TokenId,
+    pub amount: u64,
+    pub token_properties: PropertyMap,
+}
+
+/// Mimics `0x3::token::Royalty`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Royalty {
+    pub royalty_points_numerator: u64,
+    pub royalty_points_denominator: u64,
+    pub payee_address: AccountAddress,
+}
+
+/// Mimics `0x3::token::TokenMutabilityConfig`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenMutabilityConfig {
+    pub maximum: bool,
+    pub uri: bool,
+    pub royalty: bool,
+    pub description: bool,
+    pub properties: bool,
+}
+
+/// Mimics `0x3::token::TokenData`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenData {
+    pub maximum: u64,
+    pub largest_property_version: u64,
+    pub supply: u64,
+    pub uri: String,
+    pub royalty: Royalty,
+    pub name: String,
+    pub description: String,
+    pub default_properties: PropertyMap,
+    pub mutability_config: TokenMutabilityConfig,
+}
+
+/// Mimics `0x3::token::CollectionMutabilityConfig`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CollectionMutabilityConfig {
+    pub description: bool,
+    pub uri: bool,
+    pub maximum: bool,
+}
+
+/// Mimics `0x3::token::CollectionData`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CollectionData {
+    pub description: String,
+    pub name: String,
+    pub uri: String,
+    pub supply: u64,
+    pub maximum: u64,
+    pub mutability_config: CollectionMutabilityConfig,
+}
+
+/// Mimics `0x3::token::TokenStore`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x3", module = "token")]
+pub struct TokenStore {
+    pub tokens: TableHandle,
+    pub deposit_events: EventHandle,
+    pub withdraw_events: EventHandle,
+    pub burn_events: EventHandle,
+    pub mutate_token_property_events: EventHandle,
+}
+
+/// Mimics `0x3::token::Collections`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x3", module = "token")]
+pub struct Collections {
+    pub collection_data: TableHandle,
+    pub token_data: TableHandle,
+    pub create_collection_events: EventHandle,
+    pub create_token_data_events: EventHandle,
+    pub mint_token_events: EventHandle,
+}
+
+/// Mimics `0x1::table_with_length::TableWithLength`, e.g. as a table item.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TableWithLength {
+    pub inner: TableHandle,
+    pub length: u64,
+}
+
+/// Mimics `0x3::token_transfers::TokenTransfers`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, MoveResource)]
+#[move_resource(address = "0x3", module = "token_transfers")]
+pub struct TokenTransfers {
+    pub pending_claims: TableWithLength,
+}
+
+/// Creates the collection `name` of `creator`, holding up to `maximum` token data, with an empty
+/// description and uri, none of which can be mutated.
+pub fn create_collection(
+    harness: &mut MoveHarness,
+    creator: &Account,
+    name: &str,
+    maximum: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        creator,
+        aptos_token_sdk_builder::token_create_collection_script(
+            name.as_bytes().to_vec(),
+            vec![],
+            vec![],
+            maximum,
+            vec![false; 3],
+        ),
+    )
+}
+
+/// Creates the token `name` in the collection of `creator`, of which at most `maximum` can be
+/// minted, and mints `balance` of it to `creator`. The token has no properties, no royalty and
+/// can't be mutated.
+pub fn create_token(
+    harness: &mut MoveHarness,
+    creator: &Account,
+    collection: &str,
+    name: &str,
+    balance: u64,
+    maximum: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        creator,
+        aptos_token_sdk_builder::token_create_token_script(
+            collection.as_bytes().to_vec(),
+            name.as_bytes().to_vec(),
+            vec![],
+            balance,
+            maximum,
+            vec![],
+            *creator.address(),
+            1,
+            0,
+            vec![false; 5],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    )
+}
+
+/// Mints `amount` more of the token `name` to `creator`, who created it.
+pub fn mint_token(
+    harness: &mut MoveHarness,
+    creator: &Account,
+    collection: &str,
+    name: &str,
+    amount: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        creator,
+        aptos_token_sdk_builder::token_mint_script(
+            *creator.address(),
+            collection.as_bytes().to_vec(),
+            name.as_bytes().to_vec(),
+            amount,
+        ),
+    )
+}
+
+/// Transfers `amount` of the token from `sender` to `receiver` in one multi-agent transaction,
+/// signed by both.
+pub fn direct_transfer(
+    harness: &mut MoveHarness,
+    sender: &Account,
+    receiver: &Account,
+    id: &TokenId,
+    amount: u64,
+) -> TxnSummary {
+    harness.run_multi_agent(
+        sender,
+        &[receiver.clone()],
+        aptos_token_sdk_builder::token_direct_transfer_script(
+            id.token_data_id.creator,
+            id.token_data_id.collection.as_bytes().to_vec(),
+            id.token_data_id.name.as_bytes().to_vec(),
+            id.property_version,
+            amount,
+        ),
+    )
+}
+
+/// Offers `amount` of the token to `receiver`, who opts in by claiming it, see `claim_token`.
+/// The offered tokens are withdrawn from `sender` until then.
+pub fn offer_token(
+    harness: &mut MoveHarness,
+    sender: &Account,
+    receiver: AccountAddress,
+    id: &TokenId,
+    amount: u64,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        sender,
+        aptos_token_sdk_builder::token_transfers_offer_script(
+            receiver,
+            id.token_data_id.creator,
+            id.token_data_id.collection.as_bytes().to_vec(),
+            id.token_data_id.name.as_bytes().to_vec(),
+            id.property_version,
+            amount,
+        ),
+    )
+}
+
+/// Claims all of the token offered to `receiver` by `sender`, see `offer_token`.
+pub fn claim_token(
+    harness: &mut MoveHarness,
+    receiver: &Account,
+    sender: AccountAddress,
+    id: &TokenId,
+) -> TxnSummary {
+    harness.run_transaction_payload(
+        receiver,
+        aptos_token_sdk_builder::token_transfers_claim_script(
+            sender,
+            id.token_data_id.creator,
+            id.token_data_id.collection.as_bytes().to_vec(),
+            id.token_data_id.name.as_bytes().to_vec(),
+            id.property_version,
+        ),
+    )
+}
+
+/// Returns how much of the token `owner` holds, following `0x3::token::balance_of`, but 0 if
+/// `owner` has no token store.
+pub fn get_token_balance(harness: &MoveHarness, owner: &AccountAddress, id: &TokenId) -> u64 {
+    harness
+        .read_move_resource::<TokenStore>(owner)
+        .and_then(|store| harness.read_table_item::<_, Token>(store.tokens, id))
+        .map_or(0, |token| token.amount)
+}
+
+/// Returns the token data of `id`, if created.
+pub fn get_token_data(harness: &MoveHarness, id: &TokenDataId) -> Option<TokenData> {
+    let collections = harness.read_move_resource::<Collections>(&id.creator)?;
+    harness.read_table_item(collections.token_data, id)
+}
+
+/// Returns the collection `name` of `creator`, if created.
+pub fn get_collection_data(
+    harness: &MoveHarness,
+    creator: &AccountAddress,
+    name: &str,
+) -> Option<CollectionData> {
+    let collections = harness.read_move_resource::<Collections>(creator)?;
+    harness.read_table_item(collections.collection_data, &name.to_owned())
+}
+
+/// Returns how much of the token `sender` has offered to `receiver` and `receiver` has not claimed
+/// yet.
+pub fn get_pending_claim(
+    harness: &MoveHarness,
+    sender: &AccountAddress,
+    receiver: &AccountAddress,
+    id: &TokenId,
+) -> u64 {
+    harness
+        .read_move_resource::<TokenTransfers>(sender)
+        .and_then(|transfers| {
+            harness.read_table_item::<_, TableWithLength>(transfers.pending_claims.inner, receiver)
+        })
+        .and_then(|claims| harness.read_table_item::<_, Token>(claims.inner, id))
+        .map_or(0, |token| token.amount)
+}
@@ -0,0 +1,248 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{printer::render_write_set, resource::MoveResource};
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{AccountResource, CoinStoreResource, CORE_CODE_ADDRESS},
+    state_store::{state_key::StateKey, table::TableHandle},
+    utility_coin::APTOS_COIN_TYPE,
+    write_set::{WriteOp, WriteSet},
+};
+use move_deps::move_core_types::{
+    ident_str,
+    language_storage::{ResourceKey, StructTag},
+    move_resource::MoveStructType,
+    parser::parse_struct_tag,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An allowlist of the state a transaction is expected to write. Used via
+/// `MoveHarness::expect_writes` to catch transactions touching unrelated global state.
+///
+/// Writes needed to pay for gas (the sender's account and coin store, and the coin supply) are
+/// always allowed.
+#[derive(Clone, Debug, Default)]
+pub struct WriteAllowlist {
+    addresses: BTreeSet<AccountAddress>,
+    resources: BTreeSet<StructTag>,
+    resources_at: BTreeSet<(AccountAddress, StructTag)>,
+    tables: BTreeSet<TableHandle>,
+}
+
+impl WriteAllowlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows any write under the given address.
+    pub fn address(mut self, addr: AccountAddress) -> Self {
+        self.addresses.insert(addr);
+        self
+    }
+
+    /// Allows writes of the given resource under any address. A tag without type arguments
+    /// matches all instantiations of a generic resource.
+    pub fn resource(mut self, tag: StructTag) -> Self {
+        self.resources.insert(tag);
+        self
+    }
+
+    /// Allows writes of the given resource under the given address.
+    pub fn resource_at(mut self, addr: AccountAddress, tag: StructTag) -> Self {
+        self.resources_at.insert((addr, tag));
+        self
+    }
+
+    /// Allows writes of items of the given table.
+    pub fn table(mut self, handle: TableHandle) -> Self {
+        self.tables.insert(handle);
+        self
+    }
+
+    /// Returns true if a transaction sent by `sender` may write the given state key.
+    pub fn allows(&self, sender: &AccountAddress, key: &StateKey) -> bool {
+        match key {
+            StateKey::AccessPath(path) => {
+                if self.addresses.contains(&path.address) {
+                    return true;
+                }
+                let tag = match path.get_struct_tag() {
+                    Some(tag) => tag,
+                    None => return false,
+                };
+                is_gas_payment_write(sender, &path.address, &tag)
+                    || self.resources_at.contains(&(path.address, tag.clone()))
+                    || self
+                        .resources
+                        .iter()
+                        .any(|allowed| tag_matches(allowed, &tag))
+            }
+            StateKey::TableItem { handle, .. } => self.tables.contains(handle),
+            StateKey::Raw(_) => false,
+        }
+    }
+
+    /// Asserts that the write set only touches allowed state. `before` is the state the write
+    /// set applies to, used to render the write set on failure.
+    pub fn check<S: StateView>(&self, sender: &AccountAddress, before: &S, write_set: &WriteSet) {
+        let unexpected: Vec<_> = write_set
+            .iter()
+            .filter(|(key, _)| !self.allows(sender, key))
+            .map(|(key, _)| key)
+            .collect();
+        assert!(
+            unexpected.is_empty(),
+            "transaction wrote state outside of the allowlist: {:?}\nwrite set:\n{}",
+            unexpected,
+            render_write_set(before, write_set)
+        );
+    }
+}
+
+/// How a transaction changed a state key, see `WriteSetDiff`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WriteKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// The state written by a transaction, with the values before and after. Available for the last
+/// transaction via `MoveHarness::last_writes`, and asserted via `assert_writes!`.
+#[derive(Clone, Debug, Default)]
+pub struct WriteSetDiff {
+    writes: BTreeMap<StateKey, (Option<Vec<u8>>, Option<Vec<u8>>)>,
+}
+
+impl WriteSetDiff {
+    /// Diffs the write set against `before`, the state it applies to.
+    pub fn new<S: StateView>(before: &S, write_set: &WriteSet) -> Self {
+        let writes = write_set
+            .iter()
+            .map(|(key, op)| {
+                let old = before.get_state_value(key).ok().flatten();
+                let new = match op {
+                    WriteOp::Value(blob) => Some(blob.clone()),
+                    WriteOp::Deletion => None,
+                };
+                (key.clone(), (old, new))
+            })
+            .collect();
+        Self { writes }
+    }
+
+    /// Returns how the state key was changed, or `None` if it was not written.
+    pub fn kind(&self, key: &StateKey) -> Option<WriteKind> {
+        self.writes.get(key).map(|(old, new)| match (old, new) {
+            (None, Some(_)) => WriteKind::Created,
+            (Some(_), Some(_)) => WriteKind::Modified,
+            // Deleting a key which never existed is still a deletion in the write set, even
+            // though it changes nothing.
+            (_, None) => WriteKind::Deleted,
+        })
+    }
+
+    pub fn created(&self) -> Vec<&StateKey> {
+        self.keys_of_kind(WriteKind::Created)
+    }
+
+    pub fn modified(&self) -> Vec<&StateKey> {
+        self.keys_of_kind(WriteKind::Modified)
+    }
+
+    pub fn deleted(&self) -> Vec<&StateKey> {
+        self.keys_of_kind(WriteKind::Deleted)
+    }
+
+    fn keys_of_kind(&self, kind: WriteKind) -> Vec<&StateKey> {
+        self.writes
+            .keys()
+            .filter(|key| self.kind(key) == Some(kind))
+            .collect()
+    }
+
+    /// Returns the resource `T` at `addr` before and after the transaction, or `None` if it was
+    /// not written.
+    pub fn resource<T: MoveResource>(
+        &self,
+        addr: &AccountAddress,
+    ) -> Option<(Option<T>, Option<T>)> {
+        let key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            *addr,
+            T::struct_tag(),
+        )));
+        let decode = |blob: &Option<Vec<u8>>| {
+            blob.as_ref().map(|blob| {
+                bcs::from_bytes::<T>(blob).expect(
+                    "serialization expected to succeed (Rust type incompatible with Move type?)",
+                )
+            })
+        };
+        self.writes
+            .get(&key)
+            .map(|(old, new)| (decode(old), decode(new)))
+    }
+
+    /// Returns the resources written, other than to pay for gas of a transaction sent by
+    /// `sender`. Table items and modules are not included.
+    pub fn resources(&self, sender: &AccountAddress) -> BTreeSet<(AccountAddress, StructTag)> {
+        self.writes
+            .keys()
+            .filter_map(|key| match key {
+                StateKey::AccessPath(path) => path.get_struct_tag().map(|tag| (path.address, tag)),
+                _ => None,
+            })
+            .filter(|(addr, tag)| !is_gas_payment_write(sender, addr, tag))
+            .collect()
+    }
+
+    /// Asserts that exactly the given resources were written, besides paying for gas, see
+    /// `assert_writes!`. Struct tags are given like `0x1::stake::StakePool`.
+    pub fn assert_resources(&self, sender: &AccountAddress, expected: &[(AccountAddress, &str)]) {
+        let expected: BTreeSet<_> = expected
+            .iter()
+            .map(|(addr, tag)| {
+                let tag =
+                    parse_struct_tag(tag).unwrap_or_else(|_| panic!("invalid struct tag {}", tag));
+                (*addr, tag)
+            })
+            .collect();
+        let actual = self.resources(sender);
+        let render = |resources: &BTreeSet<(AccountAddress, StructTag)>| {
+            resources
+                .iter()
+                .map(|(addr, tag)| format!("  {} at {}", tag, addr))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert!(
+            actual == expected,
+            "transaction wrote unexpected resources\nmissing:\n{}\nunexpected:\n{}",
+            render(&expected.difference(&actual).cloned().collect()),
+            render(&actual.difference(&expected).cloned().collect())
+        );
+    }
+}
+
+fn tag_matches(allowed: &StructTag, tag: &StructTag) -> bool {
+    allowed == tag
+        || (allowed.type_params.is_empty()
+            && allowed.address == tag.address
+            && allowed.module == tag.module
+            && allowed.name == tag.name)
+}
+
+fn is_gas_payment_write(sender: &AccountAddress, addr: &AccountAddress, tag: &StructTag) -> bool {
+    let coin_info = StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: ident_str!("coin").to_owned(),
+        name: ident_str!("CoinInfo").to_owned(),
+        type_params: vec![APTOS_COIN_TYPE.clone()],
+    };
+    (addr == sender
+        && (tag == &AccountResource::struct_tag() || tag == &CoinStoreResource::struct_tag()))
+        || (addr == &CORE_CODE_ADDRESS && tag == &coin_info)
+}
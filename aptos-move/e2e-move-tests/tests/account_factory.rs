@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{account_address::AccountAddress, transaction::TransactionStatus};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, AptosCoin, MoveHarness};
+use move_deps::move_core_types::vm_status::StatusCode;
+
+#[test]
+fn account_factory_initial_conditions() {
+    let mut harness = MoveHarness::new();
+    let addr = AccountAddress::from_hex_literal("0x123").unwrap();
+    let account = harness
+        .account()
+        .address(addr)
+        .balance(1_000_000)
+        .sequence_number(5)
+        .build();
+    assert_eq!(*account.address(), addr);
+    assert_eq!(harness.balance::<AptosCoin>(&addr), 1_000_000);
+
+    // The harness picks up the custom sequence number.
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    assert_success!(harness.run_transaction_payload(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100),
+    ));
+}
+
+#[test]
+fn account_factory_multisig_signers() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_multisig_account(2, 3);
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+
+    // Any two of the keys meet the threshold.
+    assert_success!(harness.run_transaction_payload(
+        &account.with_multisig_signers(&[0, 2]),
+        aptos_stdlib::aptos_coin_transfer(receiver, 100),
+    ));
+    assert_success!(harness.run_transaction_payload(
+        &account.with_multisig_signers(&[1, 2]),
+        aptos_stdlib::aptos_coin_transfer(receiver, 100),
+    ));
+    // A single key does not.
+    assert_eq!(
+        harness.run_transaction_payload(
+            &account.with_multisig_signers(&[1]),
+            aptos_stdlib::aptos_coin_transfer(receiver, 100),
+        ),
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+    );
+}
+
+#[test]
+fn account_factory_multisig() {
+    let mut harness = MoveHarness::new();
+    let account = harness
+        .account()
+        .address(AccountAddress::from_hex_literal("0x123").unwrap())
+        .multisig(2, 3)
+        .build();
+    assert_eq!(account.multisig().unwrap().private_keys.len(), 3);
+
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let receiver_balance = harness.balance::<AptosCoin>(receiver.address());
+    assert_success!(harness.run_transaction_payload(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100),
+    ));
+    assert_eq!(
+        harness.balance::<AptosCoin>(receiver.address()),
+        receiver_balance + 100
+    );
+}
+
+#[test]
+fn account_factory_seeded_accounts() {
+    let mut harness = MoveHarness::new();
+    let accounts = harness.new_accounts(42, 3);
+    let addresses: Vec<_> = accounts.iter().map(|account| *account.address()).collect();
+    assert_eq!(addresses.len(), 3);
+    assert!(addresses[0] != addresses[1] && addresses[1] != addresses[2]);
+
+    // The same seed yields the same accounts, even in another harness.
+    let mut other = MoveHarness::new();
+    let other_addresses: Vec<_> = other
+        .new_accounts(42, 3)
+        .iter()
+        .map(|account| *account.address())
+        .collect();
+    assert_eq!(other_addresses, addresses);
+    assert!(other.new_accounts(43, 1)[0].address() != &addresses[0]);
+
+    // Seeded accounts can sign.
+    assert_success!(harness.run_transaction_payload(
+        &accounts[0],
+        aptos_stdlib::aptos_coin_transfer(addresses[1], 100),
+    ));
+}
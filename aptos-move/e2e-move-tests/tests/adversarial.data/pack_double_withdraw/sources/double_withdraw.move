@@ -0,0 +1,25 @@
+/// Attempts to withdraw the same funds twice within a single transaction.
+module 0xcafe::double_withdraw {
+    use std::signer;
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+    use aptos_framework::stake;
+
+    /// Withdraws `amount` of inactive stake twice using the same owner capability.
+    public entry fun withdraw_stake_twice(owner: &signer, amount: u64) {
+        let owner_cap = stake::extract_owner_cap(owner);
+        let coins = stake::withdraw_with_cap(&owner_cap, amount);
+        coin::merge(&mut coins, stake::withdraw_with_cap(&owner_cap, amount));
+        coin::deposit(signer::address_of(owner), coins);
+        stake::deposit_owner_cap(owner, owner_cap);
+    }
+
+    /// Withdraws the full balance of the sender twice.
+    public entry fun withdraw_balance_twice(account: &signer) {
+        let account_addr = signer::address_of(account);
+        let balance = coin::balance<AptosCoin>(account_addr);
+        let coins = coin::withdraw<AptosCoin>(account, balance);
+        coin::merge(&mut coins, coin::withdraw<AptosCoin>(account, balance));
+        coin::deposit(account_addr, coins);
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adversarial scenarios which try to extract funds from the framework more than once.
+//!
+//! Move has no dynamic dispatch and forbids cyclic module dependencies, so framework functions
+//! cannot be re-entered from user code. The attacks below instead try to reuse capabilities and
+//! balances within a single transaction, and assert that the framework aborts them.
+
+use aptos_types::account_address::AccountAddress;
+use e2e_move_tests::{
+    aptos_call, assert_abort_in, assert_success, get_stake_pool, setup_staking, unlock_stake,
+    MoveHarness,
+};
+use framework::natives::code::UpgradePolicy;
+use language_e2e_tests::account::Account;
+
+mod common;
+
+/// Creates a harness with the attacker account at `0xcafe`, which has published the adversarial
+/// modules.
+fn setup_attacker() -> (MoveHarness, Account) {
+    // Code publishing doesn't work well with parallel execution yet.
+    let mut harness = MoveHarness::new_no_parallel();
+    let attacker = harness.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(harness.publish_package(
+        &attacker,
+        &common::package_path("adversarial.data/pack_double_withdraw"),
+        UpgradePolicy::compat(),
+    ));
+    (harness, attacker)
+}
+
+#[test]
+fn adversarial_double_stake_withdrawal() {
+    let (mut harness, attacker) = setup_attacker();
+    let attacker_address = *attacker.address();
+    let stake_amount = 50_000_000;
+    assert_success!(setup_staking(&mut harness, &attacker, stake_amount));
+    harness.new_epoch();
+
+    // Unlock part of the stake and wait for the lockup to expire.
+    let unlocked = stake_amount / 2;
+    assert_success!(unlock_stake(&mut harness, &attacker, unlocked));
+    harness.fast_forward_secs(7200);
+    harness.new_epoch();
+    assert_eq!(
+        get_stake_pool(&harness, &attacker_address).inactive,
+        unlocked
+    );
+
+    // The second withdrawal finds no inactive stake left.
+    assert_abort_in!(
+        aptos_call!(
+            harness,
+            &attacker,
+            0xcafe::double_withdraw::withdraw_stake_twice(unlocked)
+        ),
+        "0x1::stake",
+        ENO_COINS_TO_WITHDRAW
+    );
+    assert_eq!(
+        get_stake_pool(&harness, &attacker_address).inactive,
+        unlocked
+    );
+}
+
+#[test]
+fn adversarial_double_balance_withdrawal() {
+    let (mut harness, attacker) = setup_attacker();
+
+    // The second withdrawal exceeds the remaining balance (EINSUFFICIENT_BALANCE).
+    assert_abort_in!(
+        aptos_call!(
+            harness,
+            &attacker,
+            0xcafe::double_withdraw::withdraw_balance_twice()
+        ),
+        "0x1::coin",
+        0x10005
+    );
+}
@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    state_store::{state_key::StateKey, table::TableHandle},
+    write_set::{WriteOp, WriteSetMut},
+};
+use e2e_move_tests::{aggregator::Aggregator, MoveHarness};
+
+const AGGREGATOR: Aggregator = Aggregator {
+    handle: 0x42,
+    key: 0x7,
+    limit: 1_000,
+};
+
+#[test]
+fn read_aggregator_value() {
+    let mut harness = MoveHarness::new();
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::TableItem {
+            handle: TableHandle(AGGREGATOR.handle),
+            key: bcs::to_bytes(&AGGREGATOR.key).unwrap(),
+        },
+        WriteOp::Value(bcs::to_bytes(&250u128).unwrap()),
+    )])
+    .freeze()
+    .unwrap();
+    harness.executor.apply_write_set(&write_set);
+
+    assert_eq!(harness.read_aggregator(&AGGREGATOR), 250);
+}
+
+#[test]
+#[should_panic(expected = "aggregator 7 of table 66 must have a value")]
+fn read_aggregator_missing() {
+    let harness = MoveHarness::new();
+    harness.read_aggregator(&AGGREGATOR);
+}
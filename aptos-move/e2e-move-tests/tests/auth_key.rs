@@ -0,0 +1,39 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use aptos_types::{account_address::AccountAddress, transaction::TransactionStatus};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, AptosCoin, MoveHarness};
+use move_deps::move_core_types::vm_status::StatusCode;
+
+#[test]
+fn auth_key_rotation() {
+    let mut harness = MoveHarness::new();
+    let mut account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+    let balance = harness.balance::<AptosCoin>(&receiver);
+    let old_account = account.clone();
+
+    assert_success!(
+        harness.rotate_auth_key(&mut account, Ed25519PrivateKey::generate_for_testing())
+    );
+    assert_ne!(account.auth_key(), old_account.auth_key());
+
+    // Transactions are signed with the new key from now on.
+    assert_success!(
+        harness.run_transaction_payload(&account, aptos_stdlib::account_transfer(receiver, 1_000))
+    );
+    assert_eq!(harness.balance::<AptosCoin>(&receiver), balance + 1_000);
+
+    // The old key no longer signs for the account.
+    assert_eq!(
+        harness.run_transaction_payload(
+            &old_account,
+            aptos_stdlib::account_transfer(receiver, 1_000)
+        ),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+}
@@ -0,0 +1,130 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{account_address::AccountAddress, event::EventHandle};
+use e2e_move_tests::{
+    assert_success,
+    coin::{mint_coin, publish_test_coin, register_coin, transfer_coin, TestCoin},
+    get_stake_pool, setup_staking, AptosCoin, MoveHarness, MoveResource,
+};
+use move_deps::move_core_types::{language_storage::TypeTag, parser::parse_struct_tag};
+use serde::Deserialize;
+use serde_json::json;
+use std::marker::PhantomData;
+
+/// Mimics `0x1::coin::Coin`
+#[derive(Deserialize)]
+struct Coin<C> {
+    value: u64,
+    #[serde(skip)]
+    _coin_type: PhantomData<C>,
+}
+
+/// Mimics `0x1::coin::CoinStore`
+#[derive(Deserialize, MoveResource)]
+#[move_resource(address = "0x1", module = "coin")]
+struct CoinStore<C> {
+    coin: Coin<C>,
+    frozen: bool,
+    deposit_events: EventHandle,
+    withdraw_events: EventHandle,
+}
+
+#[test]
+fn test_coin_transfer_while_staked() {
+    let mut harness = MoveHarness::new();
+    let minter = publish_test_coin(&mut harness);
+    let staker = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let staker_address = *staker.address();
+    let receiver_address = *receiver.address();
+    assert_success!(setup_staking(&mut harness, &staker, 100_000_000_000_000));
+    let active_stake = get_stake_pool(&harness, &staker_address).active;
+
+    assert_success!(register_coin::<TestCoin>(&mut harness, &staker));
+    assert_success!(register_coin::<TestCoin>(&mut harness, &receiver));
+    assert_success!(mint_coin::<TestCoin>(
+        &mut harness,
+        &minter,
+        staker_address,
+        1_000
+    ));
+    assert_eq!(harness.balance::<TestCoin>(&staker_address), 1_000);
+
+    // The transfer moves only the test coin, while gas is paid in Aptos coins from the balance
+    // left besides the stake.
+    let aptos_balance = harness.balance::<AptosCoin>(&staker_address);
+    let output = transfer_coin::<TestCoin>(&mut harness, &staker, receiver_address, 400);
+    assert_success!(output.clone());
+    assert_eq!(harness.balance::<TestCoin>(&staker_address), 600);
+    assert_eq!(harness.balance::<TestCoin>(&receiver_address), 400);
+    assert_eq!(
+        harness.balance::<AptosCoin>(&staker_address),
+        aptos_balance - output.fee_charged()
+    );
+    assert_eq!(
+        get_stake_pool(&harness, &staker_address).active,
+        active_stake
+    );
+}
+
+#[test]
+fn test_coin_store_mirror_type_params() {
+    let mut harness = MoveHarness::new();
+    let minter = publish_test_coin(&mut harness);
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    assert_eq!(
+        CoinStore::<TestCoin>::struct_tag(),
+        parse_struct_tag("0x1::coin::CoinStore<0xc0ffee::test_coin::TestCoin>").unwrap()
+    );
+
+    assert_success!(register_coin::<TestCoin>(&mut harness, &owner));
+    assert_success!(mint_coin::<TestCoin>(
+        &mut harness,
+        &minter,
+        owner_address,
+        1_000
+    ));
+    let store = harness
+        .read_move_resource::<CoinStore<TestCoin>>(&owner_address)
+        .unwrap();
+    assert_eq!(store.coin.value, 1_000);
+    assert!(!store.frozen);
+    assert_eq!(store.deposit_events.count(), 1);
+    assert_eq!(store.withdraw_events.count(), 0);
+    assert_eq!(
+        harness
+            .read_move_resource::<CoinStore<AptosCoin>>(&owner_address)
+            .unwrap()
+            .coin
+            .value,
+        harness.balance::<AptosCoin>(&owner_address)
+    );
+}
+
+#[test]
+fn test_coin_transfer_json() {
+    let mut harness = MoveHarness::new();
+    let minter = publish_test_coin(&mut harness);
+    let sender = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let receiver_address = *receiver.address();
+    assert_success!(register_coin::<TestCoin>(&mut harness, &sender));
+    assert_success!(register_coin::<TestCoin>(&mut harness, &receiver));
+    assert_success!(mint_coin::<TestCoin>(
+        &mut harness,
+        &minter,
+        *sender.address(),
+        1_000
+    ));
+
+    // Generic entry functions take their type arguments as on the REST API.
+    assert_success!(harness.run_entry_json(
+        &sender,
+        "0x1::coin::transfer",
+        vec![TypeTag::Struct(TestCoin::struct_tag())],
+        json!([receiver_address.to_hex_literal(), "400"]),
+    ));
+    assert_eq!(harness.balance::<TestCoin>(&receiver_address), 400);
+}
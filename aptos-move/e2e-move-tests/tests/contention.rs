@@ -0,0 +1,56 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use aptos_vm::parallel_executor::SchedulerStats;
+use e2e_move_tests::{
+    contention::{
+        coin_store_hotspot, run_contended_block, stake_pool_hotspot, CONTENDED_BLOCK_SIZE,
+    },
+    get_stake_pool,
+    scenarios::single_validator_active,
+    AptosCoin, MoveHarness,
+};
+
+/// Every transaction executes at least once, and each extra incarnation follows an abort.
+fn assert_consistent_stats(stats: &SchedulerStats) {
+    assert_eq!(stats.num_txns, CONTENDED_BLOCK_SIZE);
+    assert!(stats.num_incarnations >= stats.num_txns);
+    assert_eq!(stats.num_reexecutions(), stats.num_aborts);
+}
+
+#[test]
+fn contended_coin_store() {
+    let mut harness = MoveHarness::new();
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x123").unwrap())
+        .address();
+    let initial_balance = harness.balance::<AptosCoin>(&receiver);
+
+    let txns = coin_store_hotspot(&mut harness, receiver, CONTENDED_BLOCK_SIZE, 1_000);
+    let stats = run_contended_block(&mut harness, txns);
+
+    assert_consistent_stats(&stats);
+    assert_eq!(
+        harness.balance::<AptosCoin>(&receiver),
+        initial_balance + CONTENDED_BLOCK_SIZE as u64 * 1_000
+    );
+}
+
+#[test]
+fn contended_stake_pool() {
+    let (mut harness, validator) = single_validator_active();
+    let pool_address = *validator.address();
+    let initial_pool = get_stake_pool(&harness, &pool_address);
+
+    let txns = stake_pool_hotspot(&mut harness, &validator, CONTENDED_BLOCK_SIZE, 100_000);
+    let stats = run_contended_block(&mut harness, txns);
+
+    assert_consistent_stats(&stats);
+    let pool = get_stake_pool(&harness, &pool_address);
+    assert_eq!(pool.active, initial_pool.active);
+    assert_eq!(
+        pool.pending_active,
+        initial_pool.pending_active + CONTENDED_BLOCK_SIZE as u64 * 100_000
+    );
+}
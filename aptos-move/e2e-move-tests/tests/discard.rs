@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{account_address::AccountAddress, vm_status::DiscardedVMStatus};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_discard, assert_success, DiscardReason, MoveHarness};
+
+#[test]
+fn discard_reasons() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    // Enough for transactions at the usual gas unit price, but not at the maximum one.
+    let poor = harness.account().balance(1_000_000_000).build();
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+    let transfer = || aptos_stdlib::aptos_coin_transfer(receiver, 100);
+    assert_success!(harness.run_transaction_payload(&account, transfer()));
+
+    let cases = [
+        (
+            &account,
+            DiscardReason::StaleSequenceNumber,
+            DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD,
+        ),
+        (
+            &poor,
+            DiscardReason::InsufficientBalanceForGas,
+            DiscardedVMStatus::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+        ),
+        (
+            &account,
+            DiscardReason::Expired,
+            DiscardedVMStatus::TRANSACTION_EXPIRED,
+        ),
+        (
+            &account,
+            DiscardReason::WrongChainId,
+            DiscardedVMStatus::BAD_CHAIN_ID,
+        ),
+    ];
+    for (sender, reason, status) in cases {
+        assert_discard!(harness.run_discarded(sender, transfer(), reason), status);
+    }
+
+    // Discarded transactions don't use up sequence numbers.
+    assert_success!(harness.run_transaction_payload(&account, transfer()));
+    assert_success!(harness.run_transaction_payload(&poor, transfer()));
+}
+
+#[test]
+fn discard_after_expiration() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+    let transfer = || aptos_stdlib::aptos_coin_transfer(receiver, 100);
+    let expiration_secs = harness.get_on_chain_time_usecs() / 1_000_000 + 60;
+    harness.set_expiration_timestamp_secs(Some(expiration_secs));
+
+    // The transaction is valid until it expires.
+    assert_success!(harness.run_transaction_payload(&account, transfer()));
+    let txn = harness.create_transaction_payload(&account, transfer());
+    assert_eq!(txn.expiration_timestamp_secs(), expiration_secs);
+    assert_discard!(
+        harness.run_expired(txn),
+        DiscardedVMStatus::TRANSACTION_EXPIRED
+    );
+    assert_eq!(
+        harness.get_on_chain_time_usecs(),
+        expiration_secs * 1_000_000
+    );
+
+    // Transactions expiring in the future are accepted again, with the sequence number of the
+    // expired one.
+    harness.set_expiration_timestamp_secs(None);
+    assert_success!(harness.run_transaction_payload(&account, transfer()));
+}
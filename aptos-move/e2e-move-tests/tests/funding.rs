@@ -0,0 +1,66 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{aptos_test_root_address, CoinStoreResource},
+    state_store::state_key::StateKey,
+};
+use e2e_move_tests::{coin::Capabilities, AptosCoin, MoveHarness, MoveResource};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+
+fn check_fund(mut harness: MoveHarness) {
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let addr = *account.address();
+    let initial_balance = harness.balance::<AptosCoin>(&addr);
+
+    harness.fund(&addr, 1_000);
+    harness.fund(&addr, 2_000);
+    assert_eq!(harness.balance::<AptosCoin>(&addr), initial_balance + 3_000);
+    assert_eq!(harness.total_funded(), 3_000);
+}
+
+#[test]
+fn fund_test_genesis() {
+    check_fund(MoveHarness::new());
+}
+
+#[test]
+fn fund_mainnet_genesis() {
+    check_fund(MoveHarness::new_mainnet());
+}
+
+#[test]
+#[should_panic(expected = "::coin::CoinStore")]
+fn balance_without_coin_store() {
+    let harness = MoveHarness::new();
+    harness.balance::<AptosCoin>(&AccountAddress::from_hex_literal("0x456").unwrap());
+}
+
+#[test]
+fn fund_mainnet_genesis_with_test_mint() {
+    let mut harness = MoveHarness::new_mainnet();
+    harness.enable_test_mint();
+    assert!(harness.exists_resource(&aptos_test_root_address(), Capabilities::struct_tag()));
+    check_fund(harness);
+}
+
+#[test]
+fn state_view_reflects_funding() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let addr = *account.address();
+    harness.fund(&addr, 1_000);
+
+    let key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        addr,
+        CoinStoreResource::struct_tag(),
+    )));
+    let bytes = harness.state_view().get_state_value(&key).unwrap().unwrap();
+    assert_eq!(
+        bcs::from_bytes::<CoinStoreResource>(&bytes).unwrap().coin(),
+        harness.balance::<AptosCoin>(&addr)
+    );
+}
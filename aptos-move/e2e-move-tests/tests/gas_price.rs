@@ -0,0 +1,119 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    account_address::AccountAddress, on_chain_config::GasSchedule, transaction::TransactionStatus,
+    vm_status::StatusCode,
+};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, assert_vm_status, gas_price::GasPricePolicy, MoveHarness};
+use language_e2e_tests::account::Account;
+
+fn setup() -> (MoveHarness, Account) {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    harness.set_gas_schedule_entry("txn.min_price_per_gas_unit", 100);
+    (harness, account)
+}
+
+#[test]
+fn gas_price_escalation_accepted() {
+    let (mut harness, account) = setup();
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let policy = GasPricePolicy {
+        initial_price: 10,
+        multiplier: 3,
+        max_attempts: 5,
+    };
+    let outcome = harness.run_with_gas_escalation(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100),
+        &policy,
+    );
+    assert_eq!(outcome.accepted_price(), Some(270));
+    outcome.assert_accepted_within(300);
+    for (_, status) in &outcome.attempts[..3] {
+        assert_eq!(
+            status,
+            &TransactionStatus::Discard(StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND)
+        );
+    }
+
+    // The sequence number only advanced for the accepted transaction.
+    harness.set_gas_schedule_entry("txn.min_price_per_gas_unit", 0);
+    assert_success!(harness.run_transaction_payload(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100),
+    ));
+}
+
+#[test]
+fn gas_price_escalation_gives_up() {
+    let (mut harness, account) = setup();
+    let policy = GasPricePolicy {
+        initial_price: 10,
+        multiplier: 2,
+        max_attempts: 3,
+    };
+    let outcome = harness.run_with_gas_escalation(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*account.address(), 100),
+        &policy,
+    );
+    assert_eq!(outcome.attempts.len(), 3);
+    outcome.assert_never_accepted();
+}
+
+#[test]
+fn custom_gas_schedule() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let transfer = aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100);
+    let output = harness.run_transaction_payload(&account, transfer.clone());
+    assert_success!(output.clone());
+    let gas_used = output.gas_used();
+
+    // Doubling the cost of every instruction makes the same transaction more expensive.
+    let schedule = harness.gas_schedule();
+    harness.set_gas_schedule(&GasSchedule {
+        entries: schedule
+            .entries
+            .iter()
+            .map(|(name, value)| {
+                let factor = if name.starts_with("instr.") { 2 } else { 1 };
+                (name.clone(), value * factor)
+            })
+            .collect(),
+    });
+    let output = harness.run_transaction_payload(&account, transfer);
+    assert_success!(output.clone());
+    assert!(output.gas_used() > gas_used);
+}
+
+#[test]
+fn out_of_gas_under_expensive_schedule() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let schedule = harness.gas_schedule();
+    harness.set_gas_schedule(&GasSchedule {
+        entries: schedule
+            .entries
+            .iter()
+            .map(|(name, value)| {
+                let factor = if name.starts_with("instr.") {
+                    1_000_000
+                } else {
+                    1
+                };
+                (name.clone(), value * factor)
+            })
+            .collect(),
+    });
+    let output = harness.run_transaction_payload(
+        &account,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 100),
+    );
+    assert_vm_status!(output, StatusCode::OUT_OF_GAS);
+}
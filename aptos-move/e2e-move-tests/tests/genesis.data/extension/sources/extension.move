@@ -0,0 +1,7 @@
+module aptos_framework::extension {
+    use aptos_framework::block;
+
+    public fun block_height(): u64 {
+        block::get_current_block_height()
+    }
+}
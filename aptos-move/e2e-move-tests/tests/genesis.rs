@@ -0,0 +1,275 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos::{common::types::MovePackageDir, move_tool::BuiltPackage};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::{ChainId, NamedChain},
+    vm_status::DiscardedVMStatus,
+};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{
+    add_stake, assert_aborts_with, assert_discard, assert_success, expected_epoch_rewards,
+    genesis::{GenesisConfig, ValidatorSpec},
+    get_lockup_remaining_secs, get_stake_pool, get_staking_config, get_validator_config,
+    get_validator_set, initialize_staking, rotate_consensus_key, setup_staking, DiscardReason,
+    MoveHarness,
+};
+
+mod common;
+
+fn genesis_variants() -> Vec<(&'static str, MoveHarness)> {
+    vec![
+        ("test", MoveHarness::new()),
+        ("testnet", MoveHarness::new_testnet()),
+        ("mainnet", MoveHarness::new_mainnet()),
+    ]
+}
+
+#[test]
+fn staking_config_per_genesis() {
+    for (name, harness) in genesis_variants() {
+        let config = get_staking_config(&harness);
+        let (lockup_secs, allow_validator_set_change, voting_power_increase_limit) = match name {
+            "test" => (7200, true, 50),
+            "testnet" => (24 * 3600, false, 20),
+            "mainnet" => (30 * 24 * 3600, true, 30),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            config.recurring_lockup_duration_secs, lockup_secs,
+            "{}",
+            name
+        );
+        assert_eq!(
+            config.allow_validator_set_change, allow_validator_set_change,
+            "{}",
+            name
+        );
+        assert_eq!(
+            config.voting_power_increase_limit, voting_power_increase_limit,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn join_validator_set_per_genesis() {
+    for (name, mut harness) in genesis_variants() {
+        let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+        let status = setup_staking(&mut harness, &owner, 50_000_000);
+        match name {
+            "test" => assert_success!(status),
+            "testnet" => {
+                assert_aborts_with!(status, stake::ENO_POST_GENESIS_VALIDATOR_SET_CHANGE_ALLOWED)
+            }
+            "mainnet" => assert_aborts_with!(status, stake::ESTAKE_TOO_LOW),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn genesis_with_validators() {
+    let operator_address = AccountAddress::from_hex_literal("0x123").unwrap();
+    let voter_address = AccountAddress::from_hex_literal("0x234").unwrap();
+    let mut harness = MoveHarness::new_with_validators(&[
+        ValidatorSpec::new(10_000_000),
+        ValidatorSpec::new(20_000_000)
+            .operator(operator_address)
+            .voter(voter_address),
+        ValidatorSpec::new(30_000_000),
+    ]);
+    let validators: Vec<_> = harness
+        .genesis_validators()
+        .iter()
+        .map(|validator| {
+            (
+                *validator.address(),
+                validator.owner.clone(),
+                validator.operator.clone(),
+            )
+        })
+        .collect();
+
+    let validator_set = get_validator_set(&harness);
+    assert_eq!(validator_set.active_validators.len(), 3);
+    assert_eq!(validator_set.total_voting_power(), 60_000_000);
+    for (index, (address, _, _)) in validators.iter().enumerate() {
+        assert!(validator_set.contains_active(address));
+        assert_eq!(validator_set.index_of(address), Some(index as u32));
+    }
+    let (pool_address, owner, operator) = &validators[1];
+    assert_eq!(*operator.address(), operator_address);
+    assert_eq!(
+        harness.genesis_validators()[1].voter.address(),
+        &voter_address
+    );
+
+    // All accounts can sign, including those at static addresses.
+    assert_success!(rotate_consensus_key(&mut harness, operator, *pool_address));
+    harness.fund(owner.address(), 1_000);
+    assert_success!(add_stake(&mut harness, owner, 1_000));
+    assert_eq!(get_stake_pool(&harness, pool_address).pending_active, 1_000);
+}
+
+#[test]
+fn genesis_with_short_lockup() {
+    let mut harness = MoveHarness::new_with_genesis(
+        GenesisConfig::test()
+            .epoch_interval_secs(60)
+            .recurring_lockup_duration_secs(120)
+            .voting_duration_secs(60),
+    );
+    assert_eq!(
+        get_staking_config(&harness).recurring_lockup_duration_secs,
+        120
+    );
+    assert_eq!(harness.genesis_validators().len(), 1);
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let pool_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 10_000_000));
+
+    // Every epoch takes the configured interval, and the lockup renews every other epoch.
+    let mut lockups = vec![];
+    for _ in 0..3 {
+        harness.new_epoch();
+        lockups.push(get_lockup_remaining_secs(&harness, &pool_address));
+    }
+    assert_eq!(lockups, vec![120, 60, 120]);
+    assert!(get_validator_set(&harness).contains_active(&pool_address));
+}
+
+#[test]
+fn genesis_with_required_stake() {
+    let mut harness = MoveHarness::new_with_genesis(
+        GenesisConfig::test().required_stake(20_000_000, 200_000_000),
+    );
+    let config = get_staking_config(&harness);
+    assert_eq!(
+        (config.minimum_stake, config.maximum_stake),
+        (20_000_000, 200_000_000)
+    );
+
+    let small = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    assert_aborts_with!(
+        setup_staking(&mut harness, &small, 10_000_000),
+        stake::ESTAKE_TOO_LOW
+    );
+    let large = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let large_address = *large.address();
+    assert_aborts_with!(
+        initialize_staking(
+            &mut harness,
+            &large,
+            250_000_000,
+            large_address,
+            large_address
+        ),
+        stake::ESTAKE_EXCEEDS_MAX
+    );
+}
+
+#[test]
+fn genesis_without_validator_set_change() {
+    let mut harness =
+        MoveHarness::new_with_genesis(GenesisConfig::test().allow_validator_set_change(false));
+    assert!(!get_staking_config(&harness).allow_validator_set_change);
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    assert_aborts_with!(
+        setup_staking(&mut harness, &owner, 50_000_000),
+        stake::ENO_POST_GENESIS_VALIDATOR_SET_CHANGE_ALLOWED
+    );
+}
+
+#[test]
+fn genesis_with_chain_id() {
+    for id in [
+        NamedChain::MAINNET.id(),
+        NamedChain::TESTNET.id(),
+        NamedChain::TESTING.id(),
+        42,
+    ] {
+        let chain_id = ChainId::new(id);
+        let mut harness = MoveHarness::new_with_genesis(GenesisConfig::test().chain_id(chain_id));
+        assert_eq!(harness.chain_id(), chain_id);
+        let on_chain_id: u8 = harness.execute_view_function(
+            str::parse("0x1::chain_id::get").unwrap(),
+            vec![],
+            vec![],
+        );
+        assert_eq!(on_chain_id, id);
+
+        // Transactions created by the harness are for its chain, others are discarded.
+        let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+        let receiver = *harness.account().build().address();
+        let transfer = || aptos_stdlib::aptos_coin_transfer(receiver, 100);
+        assert_success!(harness.run_transaction_payload(&account, transfer()));
+        assert_discard!(
+            harness.run_discarded(&account, transfer(), DiscardReason::WrongChainId),
+            DiscardedVMStatus::BAD_CHAIN_ID
+        );
+        let other_chain_id = if id == NamedChain::TESTING.id() {
+            ChainId::new(NamedChain::MAINNET.id())
+        } else {
+            ChainId::test()
+        };
+        let txn = account
+            .transaction()
+            .chain_id(other_chain_id)
+            .sequence_number(11)
+            .gas_unit_price(1)
+            .payload(transfer())
+            .sign();
+        assert_discard!(harness.run(txn), DiscardedVMStatus::BAD_CHAIN_ID);
+    }
+}
+
+#[test]
+fn genesis_with_rewards_apy() {
+    let mut harness =
+        MoveHarness::new_with_genesis(GenesisConfig::test().rewards_apy_percentage(20));
+    // The yearly rate is spread over the hourly epochs of a year, rounding down.
+    let config = get_staking_config(&harness);
+    assert_eq!(
+        config.rewards_rate,
+        config.rewards_rate_denominator / 5 / (365 * 24)
+    );
+
+    let pool_address = *harness.genesis_validators()[0].address();
+    let index = get_validator_config(&harness, &pool_address).validator_index as u32;
+    let stake = get_stake_pool(&harness, &pool_address).active;
+    harness.new_block_with_metadata(Some(index), vec![]);
+    let rewards = expected_epoch_rewards(&harness, stake, 1, 1);
+    assert!(rewards > 0);
+    harness.new_epoch();
+    assert_eq!(
+        get_stake_pool(&harness, &pool_address).active,
+        stake + rewards
+    );
+}
+
+#[test]
+fn genesis_with_framework_package() {
+    let package = BuiltPackage::build(
+        MovePackageDir::new(common::package_path("genesis.data/extension")),
+        true,
+        false,
+    )
+    .unwrap();
+    let mut harness =
+        MoveHarness::new_with_genesis(GenesisConfig::test().framework_package(&package));
+
+    // The extension is published under 0x1 and can use the framework.
+    let height = |harness: &MoveHarness| -> u64 {
+        harness.execute_view_function(
+            str::parse("0x1::extension::block_height").unwrap(),
+            vec![],
+            vec![],
+        )
+    };
+    let before = height(&harness);
+    harness.new_epoch();
+    assert_eq!(height(&harness), before + 1);
+}
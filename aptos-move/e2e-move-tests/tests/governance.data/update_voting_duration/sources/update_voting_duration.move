@@ -0,0 +1,12 @@
+script {
+    use aptos_framework::aptos_governance;
+    use aptos_framework::governance_proposal::GovernanceProposal;
+    use aptos_framework::voting;
+
+    /// Resolves the given proposal by changing the voting duration, keeping the other parameters
+    /// of test genesis.
+    fun main(proposal_id: u64, voting_duration_secs: u64) {
+        let proposal = voting::resolve<GovernanceProposal>(@aptos_framework, proposal_id);
+        aptos_governance::update_governance_config(proposal, 0, 0, voting_duration_secs);
+    }
+}
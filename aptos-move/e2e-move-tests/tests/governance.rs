@@ -0,0 +1,151 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::transaction::TransactionArgument;
+use e2e_move_tests::{
+    assert_aborts_with, assert_success, get_stake_pool, get_staking_config,
+    governance::{
+        create_proposal, execution_hash, get_governance_config, has_voted, pass_proposal,
+        update_staking_config, upgrade_framework, vote, CreateProposalEvent, GovernanceConfig,
+        StakingConfigUpdate,
+    },
+    increase_lockup, scenarios, MoveHarness,
+};
+use std::fs;
+
+mod common;
+
+#[test]
+fn governance_update_voting_duration() {
+    let (mut harness, small, large) = scenarios::two_validators_unequal_stake();
+    let code = MoveHarness::compile_script(&common::package_path(
+        "governance.data/update_voting_duration",
+    ));
+
+    let output = pass_proposal(
+        &mut harness,
+        &small,
+        *small.address(),
+        &[(&small, *small.address()), (&large, *large.address())],
+        code.clone(),
+        vec![TransactionArgument::U64(1800)],
+    );
+    assert_success!(output);
+    assert_eq!(
+        get_governance_config(&harness),
+        GovernanceConfig {
+            min_voting_threshold: 0,
+            required_proposer_stake: 0,
+            voting_duration_secs: 1800,
+        }
+    );
+
+    // The first proposal has id 0, and can only be resolved once.
+    let status = harness.run_script(
+        &small,
+        code,
+        vec![],
+        vec![TransactionArgument::U64(0), TransactionArgument::U64(600)],
+    );
+    assert_aborts_with!(status, voting::EPROPOSAL_ALREADY_RESOLVED);
+}
+
+#[test]
+fn governance_voting_records() {
+    let (mut harness, small, large) = scenarios::two_validators_unequal_stake();
+    let output = create_proposal(
+        &mut harness,
+        &small,
+        *small.address(),
+        execution_hash(b"resolution script"),
+    );
+    assert_success!(output.clone());
+    let proposal_id = harness.events_for::<CreateProposalEvent>(&output)[0].proposal_id;
+
+    assert_success!(vote(
+        &mut harness,
+        &large,
+        *large.address(),
+        proposal_id,
+        false
+    ));
+    assert!(has_voted(&harness, *large.address(), proposal_id));
+    assert!(!has_voted(&harness, *small.address(), proposal_id));
+}
+
+#[test]
+fn governance_update_staking_config() {
+    let (mut harness, small, large) = scenarios::two_validators_unequal_stake();
+    let voters = [(&small, *small.address()), (&large, *large.address())];
+
+    for update in [
+        StakingConfigUpdate::RewardsRate {
+            rewards_rate: 1,
+            rewards_rate_denominator: 1000,
+        },
+        StakingConfigUpdate::VotingPowerIncreaseLimit(10),
+        StakingConfigUpdate::RequiredStake {
+            minimum_stake: 1_000,
+            maximum_stake: 1_000_000_000,
+        },
+        StakingConfigUpdate::RecurringLockupDurationSecs(4 * 3600),
+    ] {
+        // Voting requires a lockup beyond the end of the proposal.
+        assert_success!(increase_lockup(&mut harness, &small));
+        assert_success!(increase_lockup(&mut harness, &large));
+        assert_success!(update_staking_config(
+            &mut harness,
+            &small,
+            *small.address(),
+            &voters,
+            update
+        ));
+    }
+
+    // Invalid values are rejected, keeping the config.
+    assert_success!(increase_lockup(&mut harness, &small));
+    assert_success!(increase_lockup(&mut harness, &large));
+    let config = get_staking_config(&harness);
+    let status = update_staking_config(
+        &mut harness,
+        &small,
+        *small.address(),
+        &voters,
+        StakingConfigUpdate::VotingPowerIncreaseLimit(51),
+    );
+    assert_aborts_with!(status, staking_config::EINVALID_VOTING_POWER_INCREASE_LIMIT);
+    assert_eq!(get_staking_config(&harness), config);
+}
+
+#[test]
+fn governance_upgrade_framework() {
+    let (mut harness, small, large) = scenarios::two_validators_unequal_stake();
+    let stake_pool = get_stake_pool(&harness, large.address());
+
+    assert_success!(upgrade_framework(
+        &mut harness,
+        &small,
+        *small.address(),
+        &[(&small, *small.address()), (&large, *large.address())],
+        |package| {
+            // Adding a function is a compatible upgrade.
+            let path = package.join("sources").join("stake.move");
+            let mut source = fs::read_to_string(&path).unwrap();
+            let end = source.rfind('}').unwrap();
+            source.insert_str(end, "    public fun upgraded(): u64 { 42 }\n");
+            fs::write(&path, source).unwrap();
+        },
+    ));
+
+    let upgraded: u64 =
+        harness.execute_view_function(str::parse("0x1::stake::upgraded").unwrap(), vec![], vec![]);
+    assert_eq!(upgraded, 42);
+
+    // The state of the framework is kept, and it keeps working on it.
+    assert_eq!(
+        get_stake_pool(&harness, large.address()).active,
+        stake_pool.active
+    );
+    harness.new_epoch();
+    assert_success!(increase_lockup(&mut harness, &large));
+}
@@ -0,0 +1,34 @@
+module 0xcafe::table_owner {
+    use std::signer;
+    use aptos_std::table::{Self, Table};
+
+    struct Owner has key {
+        items: Table<u64, u64>,
+    }
+
+    struct NewOwner has key {
+        items: Table<u64, u64>,
+    }
+
+    public entry fun create(account: &signer, count: u64) {
+        let items = table::new();
+        let i = 0;
+        while (i < count) {
+            table::add(&mut items, i, i);
+            i = i + 1;
+        };
+        move_to(account, Owner { items })
+    }
+
+    /// Deletes `Owner` and drops its table without removing the items.
+    public entry fun delete(account: &signer) acquires Owner {
+        let Owner { items } = move_from<Owner>(signer::address_of(account));
+        table::drop_unchecked(items)
+    }
+
+    /// Deletes `Owner` but keeps its table alive in `NewOwner`.
+    public entry fun transfer(account: &signer) acquires Owner {
+        let Owner { items } = move_from<Owner>(signer::address_of(account));
+        move_to(account, NewOwner { items })
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use e2e_move_tests::{assert_success, orphans::OrphanedState, MoveHarness};
+use framework::natives::code::UpgradePolicy;
+use language_e2e_tests::account::Account;
+
+mod common;
+
+fn setup() -> (MoveHarness, Account) {
+    // Code publishing doesn't work well with parallel execution yet.
+    let mut h = MoveHarness::new_no_parallel();
+    let acc = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(h.publish_package(
+        &acc,
+        &common::package_path("orphans.data/pack_table_owner"),
+        UpgradePolicy::compat(),
+    ));
+    assert_success!(h.run_entry_function(
+        &acc,
+        str::parse("0xcafe::table_owner::create").unwrap(),
+        vec![],
+        vec![bcs::to_bytes::<u64>(&3).unwrap()],
+    ));
+    (h, acc)
+}
+
+#[test]
+fn orphans_table_items_detected() {
+    let (mut h, acc) = setup();
+    assert_success!(h.run_entry_function(
+        &acc,
+        str::parse("0xcafe::table_owner::delete").unwrap(),
+        vec![],
+        vec![],
+    ));
+    let orphans = h.check_orphaned_state();
+    assert_eq!(orphans.len(), 1);
+    assert!(matches!(
+        orphans[0],
+        OrphanedState::TableItems { count: 3, .. }
+    ));
+}
+
+#[test]
+fn orphans_moved_table_not_reported() {
+    let (mut h, acc) = setup();
+    assert_success!(h.run_entry_function(
+        &acc,
+        str::parse("0xcafe::table_owner::transfer").unwrap(),
+        vec![],
+        vec![],
+    ));
+    assert!(h.check_orphaned_state().is_empty());
+}
@@ -0,0 +1,42 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, AptosCoin, MoveHarness};
+
+#[test]
+fn replay_transactions_from_file() {
+    // Capture transactions of seeded accounts, so they can be replayed in another harness.
+    let mut capture = MoveHarness::new();
+    let accounts = capture.new_accounts(7, 2);
+    let receiver = *accounts[1].address();
+    let txns = vec![
+        capture.create_transaction_payload(
+            &accounts[0],
+            aptos_stdlib::aptos_coin_transfer(receiver, 1_000),
+        ),
+        capture.create_transaction_payload(
+            &accounts[0],
+            aptos_stdlib::aptos_coin_transfer(receiver, 2_000),
+        ),
+    ];
+    let path = std::env::temp_dir().join(format!("replay-{}.bcs", std::process::id()));
+    MoveHarness::write_transactions_to_file(&path, &txns);
+
+    let mut harness = MoveHarness::new();
+    let accounts = harness.new_accounts(7, 2);
+    let balance = harness.balance::<AptosCoin>(&receiver);
+    let outputs = harness.replay_from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(outputs.len(), 2);
+    for output in outputs {
+        assert_success!(output);
+    }
+    assert_eq!(harness.balance::<AptosCoin>(&receiver), balance + 3_000);
+
+    // Transactions created by the harness continue after the replayed ones.
+    assert_success!(harness.run_transaction_payload(
+        &accounts[0],
+        aptos_stdlib::aptos_coin_transfer(receiver, 500)
+    ));
+}
@@ -0,0 +1,23 @@
+module vault::vault {
+    use aptos_framework::account::{Self, SignerCapability};
+    use aptos_framework::resource_account;
+
+    struct Vault has key {
+        signer_cap: SignerCapability,
+    }
+
+    struct Value has key {
+        value: u64,
+    }
+
+    fun init_module(resource: &signer) {
+        let signer_cap = resource_account::retrieve_resource_account_cap(resource, @origin);
+        move_to(resource, Vault { signer_cap });
+    }
+
+    public entry fun store_value(_caller: &signer, value: u64) acquires Vault {
+        let vault = borrow_global<Vault>(@vault);
+        let resource = account::create_signer_with_capability(&vault.signer_cap);
+        move_to(&resource, Value { value });
+    }
+}
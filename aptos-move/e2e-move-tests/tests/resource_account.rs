@@ -0,0 +1,133 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    account_address::AccountAddress, account_config::AccountResource,
+    transaction::TransactionStatus,
+};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{
+    assert_success,
+    resource_account::{
+        create_resource_account, create_resource_account_and_publish_package, get_container,
+        resource_account_address, SignerCapability,
+    },
+    AptosCoin, MoveHarness,
+};
+use move_deps::move_core_types::{
+    move_resource::MoveStructType, parser::parse_struct_tag, vm_status::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+mod common;
+
+/// Mimics `vault::vault::Vault`
+#[derive(Serialize, Deserialize)]
+struct Vault {
+    signer_cap: SignerCapability,
+}
+
+/// Mimics `vault::vault::Value`
+#[derive(Serialize, Deserialize)]
+struct Value {
+    value: u64,
+}
+
+fn auth_key(harness: &MoveHarness, addr: &AccountAddress) -> Vec<u8> {
+    harness
+        .read_resource::<AccountResource>(addr, AccountResource::struct_tag())
+        .unwrap()
+        .authentication_key()
+        .to_vec()
+}
+
+#[test]
+fn create_resource_account_keeps_cap_in_container() {
+    let mut harness = MoveHarness::new();
+    let origin = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+
+    let resource = create_resource_account(&mut harness, &origin, b"pool");
+    assert_eq!(
+        *resource.address(),
+        resource_account_address(origin.address(), b"pool")
+    );
+    assert_eq!(
+        get_container(&harness, origin.address()).unwrap().store,
+        vec![(
+            *resource.address(),
+            SignerCapability {
+                account: *resource.address()
+            }
+        )]
+    );
+    assert_eq!(
+        auth_key(&harness, resource.address()),
+        auth_key(&harness, origin.address())
+    );
+
+    // The resource account signs with origin's key and pays for itself.
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+    let balance = harness.balance::<AptosCoin>(&receiver);
+    assert_success!(
+        harness.run_transaction_payload(&resource, aptos_stdlib::account_transfer(receiver, 1_000))
+    );
+    assert_eq!(harness.balance::<AptosCoin>(&receiver), balance + 1_000);
+}
+
+#[test]
+fn create_resource_account_and_publish_package_retrieves_cap() {
+    let mut harness = MoveHarness::new();
+    let origin = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+
+    let resource = create_resource_account_and_publish_package(
+        &mut harness,
+        &origin,
+        b"vault",
+        &common::package_path("resource_account.data/vault"),
+    );
+    // The package's named address `vault` is precomputed from the seed.
+    assert_eq!(
+        *resource.address(),
+        AccountAddress::from_hex_literal(
+            "0x200141ea562d30696dd7ef796707a5d908acb95606cc6b3912b98c7594a8c64c"
+        )
+        .unwrap()
+    );
+    let vault_addr = resource.address().to_hex_literal();
+
+    // `init_module` took the cap, which emptied and removed origin's container, and locked the
+    // account.
+    let vault = harness
+        .read_resource::<Vault>(
+            resource.address(),
+            parse_struct_tag(&format!("{}::vault::Vault", vault_addr)).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(vault.signer_cap.account, *resource.address());
+    assert!(get_container(&harness, origin.address()).is_none());
+    assert_eq!(auth_key(&harness, resource.address()), vec![0; 32]);
+    assert_eq!(
+        harness.run_transaction_payload(
+            &resource,
+            aptos_stdlib::account_transfer(*origin.address(), 1_000)
+        ),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+
+    // The module still acts as the account through the cap.
+    assert_success!(harness.run_entry_function(
+        &origin,
+        str::parse(&format!("{}::vault::store_value", vault_addr)).unwrap(),
+        vec![],
+        vec![bcs::to_bytes::<u64>(&42).unwrap()],
+    ));
+    let value = harness
+        .read_resource::<Value>(
+            resource.address(),
+            parse_struct_tag(&format!("{}::vault::Value", vault_addr)).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(value.value, 42);
+}
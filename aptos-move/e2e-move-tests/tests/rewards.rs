@@ -0,0 +1,212 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use e2e_move_tests::{
+    assert_success, expected_rewards,
+    fairness::{simulate_weighted_proposals, weighted_proposers, SimulatedValidator},
+    generators::{performance_records, stake_distribution, ProposalRecord},
+    get_stake_pool, get_staking_config, get_validator_config, set_rewards_rate, setup_staking,
+    MoveHarness,
+};
+use proptest::{
+    prelude::*,
+    test_runner::{RngAlgorithm, TestRng},
+};
+
+const NUM_VALIDATORS: usize = 3;
+
+/// Sets up validators with the given stakes, applies one epoch of the given performance and
+/// returns the rewards each validator received.
+fn run_epoch_with_performance(
+    harness: &mut MoveHarness,
+    stakes: &[u64],
+    records: &[ProposalRecord],
+) -> Vec<u64> {
+    let validators: Vec<_> = stakes
+        .iter()
+        .enumerate()
+        .map(|(i, stake)| {
+            let address = AccountAddress::from_hex_literal(&format!("0x{:x}", 0x100 + i)).unwrap();
+            let validator = harness.new_account_at(address);
+            assert_success!(setup_staking(harness, &validator, *stake));
+            address
+        })
+        .collect();
+    harness.new_epoch();
+
+    for (address, record) in validators.iter().zip(records) {
+        let index = get_validator_config(harness, address).validator_index as u32;
+        record.apply(harness, index);
+    }
+    harness.new_epoch();
+
+    validators
+        .iter()
+        .zip(stakes)
+        .map(|(address, stake)| get_stake_pool(harness, address).active - stake)
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn test_rewards_apportionment(
+        stakes in stake_distribution(NUM_VALIDATORS, 1_000_000, 15_000_000),
+        records in performance_records(NUM_VALIDATORS, 10),
+    ) {
+        let mut harness = MoveHarness::new();
+        let config = get_staking_config(&harness);
+        let rewards = run_epoch_with_performance(&mut harness, &stakes, &records);
+
+        let full_rewards = |stake: u64| {
+            (stake as u128 * config.rewards_rate as u128 / config.rewards_rate_denominator as u128)
+                as u64
+        };
+        for ((reward, stake), record) in rewards.iter().zip(&stakes).zip(&records) {
+            // Rewards are proportional to the stake and the ratio of successful proposals.
+            let expected = expected_rewards(
+                *stake,
+                record.successful,
+                record.total(),
+                config.rewards_rate,
+                config.rewards_rate_denominator,
+            )
+            .unwrap();
+            prop_assert_eq!(*reward, expected);
+            prop_assert!(*reward <= full_rewards(*stake));
+        }
+
+        // The total rewards distributed never exceed the configured rate.
+        let total_stake: u64 = stakes.iter().sum();
+        prop_assert!(rewards.iter().sum::<u64>() <= full_rewards(total_stake));
+
+        // A validator with at least the stake and performance of another never receives less.
+        for i in 0..NUM_VALIDATORS {
+            for j in 0..NUM_VALIDATORS {
+                if stakes[i] >= stakes[j] && records[i].performs_at_least_as_well_as(&records[j]) {
+                    prop_assert!(
+                        rewards[i] >= rewards[j],
+                        "validator {} received {} but validator {} received {}",
+                        i,
+                        rewards[i],
+                        j,
+                        rewards[j]
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rewards_fair_under_weighted_proposer_selection() {
+    let mut harness = MoveHarness::new();
+    let stakes = [10_000_000, 15_000_000, 20_000_000];
+    let addresses: Vec<_> = stakes
+        .iter()
+        .enumerate()
+        .map(|(i, stake)| {
+            let address = AccountAddress::from_hex_literal(&format!("0x{:x}", 0x100 + i)).unwrap();
+            let validator = harness.new_account_at(address);
+            assert_success!(setup_staking(&mut harness, &validator, *stake));
+            address
+        })
+        .collect();
+    harness.new_epoch();
+    let validators: Vec<_> = addresses
+        .iter()
+        .map(|address| SimulatedValidator {
+            address: *address,
+            validator_index: get_validator_config(&harness, address).validator_index as u32,
+        })
+        .collect();
+
+    let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    let report = simulate_weighted_proposals(&mut harness, &validators, 20, 10, &mut rng);
+    report.assert_within(0.05);
+}
+
+#[test]
+#[should_panic(expected = "cannot choose proposers if all weights are 0")]
+fn test_weighted_proposers_zero_weights() {
+    let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    weighted_proposers(&[0, 0], 1, &mut rng);
+}
+
+const EDGE_CASE_STAKE: u64 = 50_000_000;
+
+/// Runs an epoch with one successful and one failed proposal of a validator with
+/// `EDGE_CASE_STAKE` under the given rewards rate, and returns its rewards.
+fn run_epoch_with_rewards_rate(rewards_rate: u64, rewards_rate_denominator: u64) -> u64 {
+    let mut harness = MoveHarness::new();
+    let address = AccountAddress::from_hex_literal("0x123").unwrap();
+    let validator = harness.new_account_at(address);
+    assert_success!(setup_staking(&mut harness, &validator, EDGE_CASE_STAKE));
+    harness.new_epoch();
+
+    set_rewards_rate(&mut harness, rewards_rate, rewards_rate_denominator);
+    let index = get_validator_config(&harness, &address).validator_index as u32;
+    ProposalRecord {
+        successful: 1,
+        failed: 1,
+    }
+    .apply(&mut harness, index);
+    harness.new_epoch();
+    get_stake_pool(&harness, &address).active - EDGE_CASE_STAKE
+}
+
+#[test]
+fn test_rewards_rate_edge_cases() {
+    // (rewards_rate, rewards_rate_denominator, expected rewards)
+    let cases = [
+        (0, 1, 0),
+        (0, u64::MAX, 0),
+        // A zero denominator is rejected by governance, but must not break the epoch change.
+        (1, 0, 0),
+        (1, 1, EDGE_CASE_STAKE / 2),
+        (1, u64::MAX, 0),
+        (u64::MAX, u64::MAX, EDGE_CASE_STAKE / 2),
+        // Rewards are rounded down: 50M / 6 = 8_333_333.33...
+        (1, 3, 8_333_333),
+        // 50M * 2 / 6 = 16_666_666.66...
+        (2, 3, 16_666_666),
+        // The largest rate whose rewards, added to the stake, still fit into u64.
+        (
+            (u64::MAX - EDGE_CASE_STAKE) / EDGE_CASE_STAKE * 2,
+            1,
+            (u64::MAX - EDGE_CASE_STAKE) / EDGE_CASE_STAKE * EDGE_CASE_STAKE,
+        ),
+    ];
+    for (rewards_rate, rewards_rate_denominator, expected) in cases {
+        assert_eq!(
+            expected_rewards(
+                EDGE_CASE_STAKE,
+                1,
+                2,
+                rewards_rate,
+                rewards_rate_denominator
+            ),
+            Some(expected),
+            "rate {}/{}",
+            rewards_rate,
+            rewards_rate_denominator
+        );
+        assert_eq!(
+            run_epoch_with_rewards_rate(rewards_rate, rewards_rate_denominator),
+            expected,
+            "rate {}/{}",
+            rewards_rate,
+            rewards_rate_denominator
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_rewards_rate_overflow_fails_epoch_change() {
+    // The rewards of the maximum rate do not fit into u64, so the epoch change aborts.
+    assert_eq!(expected_rewards(EDGE_CASE_STAKE, 1, 2, u64::MAX, 1), None);
+    run_epoch_with_rewards_rate(u64::MAX, 1);
+}
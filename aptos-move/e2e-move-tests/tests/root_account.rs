@@ -0,0 +1,56 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use aptos_types::{account_address::AccountAddress, transaction::TransactionStatus};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, AptosCoin, MoveHarness};
+use language_e2e_tests::account::Account;
+use move_deps::move_core_types::vm_status::StatusCode;
+
+#[test]
+fn root_account_mints_under_test_genesis() {
+    let mut harness = MoveHarness::new();
+    let addr = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x123").unwrap())
+        .address();
+    let initial_balance = harness.balance::<AptosCoin>(&addr);
+
+    let root = harness.root_account();
+    assert_success!(
+        harness.run_transaction_payload(&root, aptos_stdlib::aptos_coin_mint(addr, 1_000))
+    );
+    assert_eq!(harness.balance::<AptosCoin>(&addr), initial_balance + 1_000);
+}
+
+#[test]
+fn root_account_with_replaced_key() {
+    let mut harness = MoveHarness::new();
+    let addr = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x123").unwrap())
+        .address();
+    let root = harness.set_root_key(Ed25519PrivateKey::generate_for_testing());
+
+    assert_success!(
+        harness.run_transaction_payload(&root, aptos_stdlib::aptos_coin_mint(addr, 1_000))
+    );
+    // The genesis key no longer signs for the core resources account.
+    let genesis_root = Account::new_aptos_root();
+    assert_eq!(
+        harness.run_transaction_payload(&genesis_root, aptos_stdlib::aptos_coin_mint(addr, 1_000)),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+}
+
+#[test]
+fn root_account_unusable_under_mainnet_genesis() {
+    let mut harness = MoveHarness::new_mainnet();
+    let addr = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x123").unwrap())
+        .address();
+    let root = harness.root_account();
+    assert_eq!(
+        harness.run_transaction_payload(&root, aptos_stdlib::aptos_coin_mint(addr, 1_000)),
+        TransactionStatus::Discard(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST)
+    );
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use e2e_move_tests::{
+    get_stake_pool, get_validator_set,
+    scenarios::{
+        single_validator_active, two_validators_unequal_stake,
+        validator_pending_inactive_with_unlocked_stake, LARGE_VALIDATOR_STAKE,
+        SMALL_VALIDATOR_STAKE, UNLOCKED_STAKE, VALIDATOR_STAKE,
+    },
+    ValidatorState,
+};
+
+#[test]
+fn scenario_single_validator_active() {
+    let (harness, validator) = single_validator_active();
+    assert!(get_validator_set(&harness).contains_active(validator.address()));
+    assert_eq!(
+        get_stake_pool(&harness, validator.address()).active,
+        VALIDATOR_STAKE
+    );
+}
+
+#[test]
+fn scenario_two_validators_unequal_stake() {
+    let (harness, small, large) = two_validators_unequal_stake();
+    let validator_set = get_validator_set(&harness);
+    assert_eq!(validator_set.active_validators.len(), 3);
+    // The genesis validator has a stake of 100M.
+    assert_eq!(
+        validator_set.total_voting_power(),
+        (100_000_000 + SMALL_VALIDATOR_STAKE + LARGE_VALIDATOR_STAKE) as u128
+    );
+    for validator in [&small, &large] {
+        let index = validator_set.index_of(validator.address()).unwrap();
+        assert_eq!(
+            validator_set.at_index(index).unwrap().account_address,
+            *validator.address()
+        );
+    }
+    assert_eq!(
+        get_stake_pool(&harness, small.address()).active,
+        SMALL_VALIDATOR_STAKE
+    );
+    assert_eq!(
+        get_stake_pool(&harness, large.address()).active,
+        LARGE_VALIDATOR_STAKE
+    );
+}
+
+#[test]
+fn scenario_validator_pending_inactive_with_unlocked_stake() {
+    let (harness, validator) = validator_pending_inactive_with_unlocked_stake();
+    let validator_set = get_validator_set(&harness);
+    assert_eq!(
+        validator_set
+            .find(validator.address())
+            .map(|(state, _)| state),
+        Some(ValidatorState::PendingInactive)
+    );
+    let stake_pool = get_stake_pool(&harness, validator.address());
+    assert_eq!(stake_pool.active, VALIDATOR_STAKE - UNLOCKED_STAKE);
+    assert_eq!(stake_pool.pending_inactive, UNLOCKED_STAKE);
+}
@@ -0,0 +1,11 @@
+script {
+    use std::signer;
+    use aptos_framework::stake;
+
+    /// Sets up a stake pool owned and operated by `owner`, and adds stake to it in two steps.
+    fun main(owner: signer, initial_stake_amount: u64, additional_stake_amount: u64) {
+        let owner_address = signer::address_of(&owner);
+        stake::initialize_owner_only(&owner, initial_stake_amount, owner_address, owner_address);
+        stake::add_stake(&owner, additional_stake_amount);
+    }
+}
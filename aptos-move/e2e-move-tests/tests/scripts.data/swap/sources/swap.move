@@ -0,0 +1,11 @@
+script {
+    use std::signer;
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+
+    /// Atomically exchanges coins between two parties, which both need to sign.
+    fun main(first: signer, second: signer, first_amount: u64, second_amount: u64) {
+        coin::transfer<AptosCoin>(&first, signer::address_of(&second), first_amount);
+        coin::transfer<AptosCoin>(&second, signer::address_of(&first), second_amount);
+    }
+}
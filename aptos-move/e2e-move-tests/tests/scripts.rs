@@ -0,0 +1,95 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_keygen::KeyGen;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{Script, TransactionArgument, TransactionPayload, TransactionStatus},
+};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, get_stake_pool, AddStakeEvent, AptosCoin, MoveHarness};
+use language_e2e_tests::account::Account;
+
+mod common;
+
+#[test]
+fn script_batched_stake_setup() {
+    let mut harness = MoveHarness::new();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    let code = MoveHarness::compile_script(&common::package_path("scripts.data/stake_setup"));
+
+    let output = harness.run_script(
+        &owner,
+        code,
+        vec![],
+        vec![
+            TransactionArgument::U64(40_000_000),
+            TransactionArgument::U64(10_000_000),
+        ],
+    );
+    assert_success!(output.clone());
+    assert_eq!(
+        harness.events_for::<AddStakeEvent>(&output),
+        vec![
+            AddStakeEvent {
+                pool_address: owner_address,
+                amount_added: 40_000_000,
+            },
+            AddStakeEvent {
+                pool_address: owner_address,
+                amount_added: 10_000_000,
+            },
+        ]
+    );
+    let stake_pool = get_stake_pool(&harness, &owner_address);
+    assert_eq!(stake_pool.active, 50_000_000);
+    assert_eq!(stake_pool.operator_address, owner_address);
+}
+
+#[test]
+fn script_multi_agent_swap() {
+    let mut harness = MoveHarness::new();
+    let first = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let second = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let first_balance = harness.balance::<AptosCoin>(first.address());
+    let second_balance = harness.balance::<AptosCoin>(second.address());
+    let code = MoveHarness::compile_script(&common::package_path("scripts.data/swap"));
+    let swap = |first_amount, second_amount| {
+        TransactionPayload::Script(Script::new(
+            code.clone(),
+            vec![],
+            vec![
+                TransactionArgument::U64(first_amount),
+                TransactionArgument::U64(second_amount),
+            ],
+        ))
+    };
+
+    let output = harness.run_multi_agent(&first, &[second.clone()], swap(1_000, 300));
+    assert_success!(output.clone());
+    assert_eq!(
+        harness.balance::<AptosCoin>(first.address()),
+        first_balance - 1_000 + 300 - output.fee_charged()
+    );
+    assert_eq!(
+        harness.balance::<AptosCoin>(second.address()),
+        second_balance + 1_000 - 300
+    );
+
+    // Only the sequence number of the sender advanced, so the secondary signer can still send.
+    assert_success!(harness.run_transaction_payload(
+        &second,
+        aptos_stdlib::aptos_coin_transfer(*first.address(), 1),
+    ));
+
+    // A secondary signer with the wrong key is rejected.
+    let (private_key, public_key) = KeyGen::from_seed([1; 32]).generate_ed25519_keypair();
+    let impostor = Account::new_validator(*second.address(), private_key, public_key);
+    let status = TransactionStatus::from(harness.run_multi_agent(&first, &[impostor], swap(1, 1)));
+    assert!(
+        matches!(status, TransactionStatus::Discard(_)),
+        "{:?}",
+        status
+    );
+}
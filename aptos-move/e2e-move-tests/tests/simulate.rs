@@ -0,0 +1,35 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{account_address::AccountAddress, transaction::TransactionStatus};
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, AptosCoin, MoveHarness};
+
+#[test]
+fn simulate_matches_execution() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let receiver = *harness
+        .new_account_at(AccountAddress::from_hex_literal("0x234").unwrap())
+        .address();
+    let transfer = || aptos_stdlib::aptos_coin_transfer(receiver, 100);
+    let balance_before = harness.balance::<AptosCoin>(account.address());
+
+    let simulated = harness.simulate(&account, transfer());
+    assert!(matches!(simulated.status(), TransactionStatus::Keep(_)));
+    // Nothing was applied.
+    assert_eq!(
+        harness.balance::<AptosCoin>(account.address()),
+        balance_before
+    );
+
+    // The simulation used the sequence number the transaction now runs with, on the same state.
+    let summary = harness.run_transaction_payload(&account, transfer());
+    assert_success!(summary);
+    assert_eq!(simulated.gas_used(), summary.gas_used());
+    assert_eq!(simulated.events(), summary.events());
+    assert_eq!(
+        harness.balance::<AptosCoin>(account.address()),
+        balance_before - 100 - summary.fee_charged()
+    );
+}
@@ -1,19 +1,60 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::account_address::AccountAddress;
+use aptos_crypto::{bls12381, PrivateKey, Uniform};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    stake_pool::StakePool,
+    state_store::state_key::StateKey,
+    utility_coin::APTOS_COIN_TYPE,
+    write_set::{WriteOp, WriteSetMut},
+};
 use cached_framework_packages::aptos_stdlib;
 use e2e_move_tests::{
-    assert_abort, assert_success, enable_golden, get_stake_pool, get_validator_config,
-    get_validator_set, initialize_staking, join_validator_set, leave_validator_set,
-    rotate_consensus_key, setup_staking, unlock_stake, withdraw_stake, MoveHarness,
+    abort_location::first_use_of_abort_constant,
+    add_stake, assert_abort, assert_aborts_with, assert_event, assert_resource_eq, assert_success,
+    assert_writes,
+    determinism::{assert_deterministic, sweep_concurrency},
+    diff::resource_diff,
+    enable_golden, expect_event, expected_epoch_rewards, fixtures,
+    generators::staking_operations,
+    genesis::{GenesisConfig, ValidatorSpec},
+    get_lockup_remaining_secs, get_stake_pool, get_staking_config, get_validator_config,
+    get_validator_performance, get_validator_set, head_framework, increase_lockup,
+    initialize_staking, join_validator_set,
+    layout::assert_layout_compatible,
+    leave_validator_set,
+    printer::render_write_set,
+    rotate_consensus_key, rotate_consensus_key_with,
+    scenarios::{
+        single_validator_active, two_validators_unequal_stake,
+        validator_pending_inactive_with_unlocked_stake, LARGE_VALIDATOR_STAKE,
+        SMALL_VALIDATOR_STAKE, VALIDATOR_STAKE,
+    },
+    set_delegated_voter, set_operator, setup_staking, unlock_stake,
+    update_network_and_fullnode_addresses, withdraw_stake,
+    writes::{WriteKind, WriteSetDiff},
+    AddStakeEvent, AptosCoin, DistributeRewardsEvent, IncreaseLockupEvent,
+    IndividualValidatorPerformance, JoinValidatorSetEvent, MoveHarness, SetOperatorEvent,
+    UnlockStakeEvent, WriteAllowlist,
+};
+use language_e2e_tests::golden_outputs::GoldenRedactions;
+use move_deps::move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, ResourceKey, CORE_CODE_ADDRESS},
+    parser::parse_struct_tag,
 };
-use move_deps::move_core_types::language_storage::CORE_CODE_ADDRESS;
+use proptest::prelude::*;
+use serde_json::json;
+use std::{cell::RefCell, rc::Rc};
 
 #[test]
 fn test_staking_end_to_end() {
     let mut harness = MoveHarness::new();
     enable_golden!(harness);
+    // Gas used by staking operations may drift slightly between framework versions.
+    harness.set_gas_tolerance_percent(10);
     let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
     let operator = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
     let owner_address = *owner.address();
@@ -42,7 +83,7 @@ fn test_staking_end_to_end() {
     let stake_pool = get_stake_pool(&harness, &owner_address);
     assert_eq!(
         stake_pool.locked_until_secs,
-        harness.executor.get_block_time_seconds() + 7200
+        harness.get_block_time_secs() + 7200
     );
 
     // Unlock 1/4 stake.
@@ -54,7 +95,7 @@ fn test_staking_end_to_end() {
     assert_eq!(stake_pool.pending_inactive, amount_to_withdraw);
 
     // Wait until stake is unlocked.
-    harness.fast_forward(7200);
+    harness.fast_forward_secs(7200);
     harness.new_epoch();
     let stake_pool = get_stake_pool(&harness, &owner_address);
     assert_eq!(stake_pool.pending_inactive, 0);
@@ -69,12 +110,12 @@ fn test_staking_end_to_end() {
     assert_eq!(stake_pool.active, remaining_stake);
     assert_eq!(
         stake_pool.locked_until_secs,
-        harness.executor.get_block_time_seconds() + 7200
+        harness.get_block_time_secs() + 7200
     );
 
     // Validator takes the rest of the stake out.
     assert_success!(unlock_stake(&mut harness, &owner, remaining_stake));
-    harness.fast_forward(7200);
+    harness.fast_forward_secs(7200);
     harness.new_epoch();
     assert_success!(withdraw_stake(&mut harness, &owner, remaining_stake));
     let stake_pool = get_stake_pool(&harness, &owner_address);
@@ -84,7 +125,6 @@ fn test_staking_end_to_end() {
 
 #[test]
 fn test_staking_mainnet() {
-    // TODO: Update to have custom validators/accounts with initial balances at genesis.
     let mut harness = MoveHarness::new_mainnet();
     enable_golden!(harness);
 
@@ -111,6 +151,38 @@ fn test_staking_mainnet() {
     assert_eq!(validator_set.active_validators.len(), 2);
 }
 
+#[test]
+fn test_staking_mainnet_custom_genesis() {
+    // The minimum stake on mainnet, 1M APT.
+    let stake_amount = 100_000_000_000_000;
+    let validator_address = AccountAddress::from_hex_literal("0x234").unwrap();
+    let account_address = AccountAddress::from_hex_literal("0x123").unwrap();
+    let mut harness = MoveHarness::new_with_genesis(
+        GenesisConfig::mainnet()
+            .validator(ValidatorSpec::new(2 * stake_amount))
+            .validator(ValidatorSpec::new(stake_amount).owner(validator_address))
+            .account(account_address, 2 * stake_amount),
+    );
+
+    let validator_set = get_validator_set(&harness);
+    assert_eq!(validator_set.active_validators.len(), 2);
+    assert!(validator_set.contains_active(&validator_address));
+    assert_eq!(validator_set.total_voting_power(), 3 * stake_amount as u128);
+    assert_eq!(
+        harness.balance::<AptosCoin>(&account_address),
+        2 * stake_amount
+    );
+    assert_eq!(harness.total_funded(), 0);
+
+    // The account funded at genesis can join, within the voting power increase limit.
+    let account = harness.genesis_accounts()[0].clone();
+    assert_success!(setup_staking(&mut harness, &account, stake_amount));
+    harness.new_epoch();
+    let validator_set = get_validator_set(&harness);
+    assert_eq!(validator_set.active_validators.len(), 3);
+    assert!(validator_set.contains_active(&account_address));
+}
+
 #[test]
 fn test_staking_rewards() {
     // Genesis starts with one validator with index 0
@@ -122,7 +194,6 @@ fn test_staking_rewards() {
     let validator_2_address = *validator_2.address();
 
     // Initialize the validators.
-    let rewards_per_epoch = 285;
     let mut stake_amount_2 = 25_000_000;
     assert_success!(setup_staking(&mut harness, &validator_2, stake_amount_2));
     let mut stake_amount_1 = 25_000_000;
@@ -135,9 +206,12 @@ fn test_staking_rewards() {
     // Both validators propose a block in the current epoch. Both should receive rewards.
     harness.new_block_with_metadata(Some(index_1), vec![]);
     harness.new_block_with_metadata(Some(index_2), vec![]);
+    let rewards_1 = expected_epoch_rewards(&harness, stake_amount_1, 1, 1);
+    let rewards_2 = expected_epoch_rewards(&harness, stake_amount_2, 1, 1);
+    assert!(rewards_1 > 0 && rewards_2 > 0);
     harness.new_epoch();
-    stake_amount_1 += rewards_per_epoch;
-    stake_amount_2 += rewards_per_epoch;
+    stake_amount_1 += rewards_1;
+    stake_amount_2 += rewards_2;
     assert_eq!(
         get_stake_pool(&harness, &validator_1_address).active,
         stake_amount_1
@@ -149,8 +223,8 @@ fn test_staking_rewards() {
 
     // Each validator proposes in their own epoch. They receive the rewards at the end of each epoch
     harness.new_block_with_metadata(Some(index_1), vec![]);
+    stake_amount_1 += expected_epoch_rewards(&harness, stake_amount_1, 1, 1);
     harness.new_epoch();
-    stake_amount_1 += rewards_per_epoch;
     assert_eq!(
         get_stake_pool(&harness, &validator_1_address).active,
         stake_amount_1
@@ -160,12 +234,12 @@ fn test_staking_rewards() {
         stake_amount_2
     );
     harness.new_block_with_metadata(Some(index_2), vec![]);
+    stake_amount_2 += expected_epoch_rewards(&harness, stake_amount_2, 1, 1);
     harness.new_epoch();
     assert_eq!(
         get_stake_pool(&harness, &validator_1_address).active,
         stake_amount_1
     );
-    stake_amount_2 += rewards_per_epoch;
     assert_eq!(
         get_stake_pool(&harness, &validator_2_address).active,
         stake_amount_2
@@ -174,12 +248,13 @@ fn test_staking_rewards() {
     // Validator 1 misses one proposal and thus receives no rewards while validator 2 didn't miss
     // any so they receive full rewards.
     harness.new_block_with_metadata(Some(index_2), vec![index_1]);
+    assert_eq!(expected_epoch_rewards(&harness, stake_amount_1, 0, 1), 0);
+    stake_amount_2 += expected_epoch_rewards(&harness, stake_amount_2, 1, 1);
     harness.new_epoch();
     assert_eq!(
         get_stake_pool(&harness, &validator_1_address).active,
         stake_amount_1
     );
-    stake_amount_2 += rewards_per_epoch;
     assert_eq!(
         get_stake_pool(&harness, &validator_2_address).active,
         stake_amount_2
@@ -187,8 +262,8 @@ fn test_staking_rewards() {
 
     // Validator 1 misses one proposal but has one successful so they receive half of the rewards.
     harness.new_block_with_metadata(Some(index_1), vec![index_1]);
+    stake_amount_1 += expected_epoch_rewards(&harness, stake_amount_1, 1, 2);
     harness.new_epoch();
-    stake_amount_1 += rewards_per_epoch / 2;
     assert_eq!(
         get_stake_pool(&harness, &validator_1_address).active,
         stake_amount_1
@@ -210,18 +285,879 @@ fn test_staking_rewards_pending_inactive() {
     // Validator requests to leave.
     leave_validator_set(&mut harness, &validator, validator_address);
     let validator_set = get_validator_set(&harness);
-    assert_eq!(
-        validator_set.pending_inactive[0].account_address,
-        validator_address
-    );
-    let index = get_validator_config(&harness, &validator_address).validator_index as u32;
+    assert!(validator_set.contains_pending_inactive(&validator_address));
+    let index = validator_set.index_of(&validator_address).unwrap();
 
     // Validator proposes a block in the current epoch and should receive rewards despite
     // being pending_inactive.
     harness.new_block_with_metadata(Some(index), vec![]);
+    let rewards = expected_epoch_rewards(&harness, stake_amount, 1, 1);
     harness.new_epoch();
     assert_eq!(
         get_stake_pool(&harness, &validator_address).active,
-        stake_amount + 570
+        stake_amount + rewards
+    );
+}
+
+#[test]
+fn test_staking_unlock_only_writes_stake_pool() {
+    let mut harness = MoveHarness::new();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+
+    // Unlocking must not touch anything but the stake pool (besides paying for gas).
+    harness.expect_writes(WriteAllowlist::new().resource_at(
+        owner_address,
+        parse_struct_tag("0x1::stake::StakePool").unwrap(),
+    ));
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+}
+
+#[test]
+fn test_staking_rewards_deterministic() {
+    assert_deterministic(MoveHarness::new, |harness| {
+        let validator = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+        let validator_address = *validator.address();
+        assert_success!(setup_staking(harness, &validator, 50_000_000));
+        harness.new_epoch();
+        let index = get_validator_config(harness, &validator_address).validator_index as u32;
+        harness.new_block_with_metadata(Some(index), vec![]);
+        harness.new_epoch();
+        assert_success!(unlock_stake(harness, &validator, 10_000_000));
+    });
+}
+
+#[test]
+fn test_staking_layout_compatible() {
+    let mut harness = MoveHarness::new();
+    harness.record_written_state();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+    harness.new_epoch();
+    assert_layout_compatible(&mut harness);
+}
+
+#[test]
+fn test_staking_head_framework_builders() {
+    let mut harness = MoveHarness::new();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+
+    // Builders generated from the work tree sources produce the same payloads as the cached ones.
+    assert_success!(
+        harness.run_transaction_payload(&owner, head_framework::stake_unlock(10_000_000))
+    );
+    assert_eq!(
+        get_stake_pool(&harness, &owner_address).pending_inactive,
+        10_000_000
+    );
+}
+
+#[test]
+fn test_staking_resource_diff() {
+    let mut harness = MoveHarness::new();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+
+    let before = get_stake_pool(&harness, &owner_address);
+    assert_resource_eq!(before, get_stake_pool(&harness, &owner_address));
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+    let after = get_stake_pool(&harness, &owner_address);
+    let paths: Vec<_> = resource_diff(&before, &after)
+        .unwrap()
+        .into_iter()
+        .map(|diff| diff.path)
+        .collect();
+    assert_eq!(
+        paths,
+        vec!["active", "pending_inactive", "unlock_stake_events.count"]
+    );
+}
+
+#[test]
+fn test_staking_clock_timeline() {
+    let mut harness = MoveHarness::new();
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.clock().to_next_epoch().run();
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+
+    // Unlocked stake stays pending until the lockup expires.
+    let start_secs = harness.get_block_time_secs();
+    harness.clock().advance_secs(100).run();
+    assert_eq!(harness.get_block_time_secs(), start_secs + 100);
+    assert_eq!(get_stake_pool(&harness, &owner_address).inactive, 0);
+
+    harness.clock().epochs(2).run();
+    let stake_pool = get_stake_pool(&harness, &owner_address);
+    assert_eq!(stake_pool.pending_inactive, 0);
+    assert_eq!(stake_pool.inactive, 10_000_000);
+}
+
+#[test]
+fn test_staking_fast_forward_to_epoch_and_timestamp() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    let epoch = harness.current_epoch();
+    harness.fast_forward_to_epoch(epoch + 1);
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+
+    // Unlocked stake becomes inactive after the lockup, two epochs later.
+    harness.fast_forward_to_epoch(epoch + 3);
+    assert_eq!(harness.current_epoch(), epoch + 3);
+    assert_eq!(
+        get_stake_pool(&harness, &owner_address).inactive,
+        10_000_000
+    );
+
+    // Blocks reconfigure whenever the epoch interval of an hour passes.
+    let start_secs = harness.get_on_chain_time_usecs() / 1_000_000;
+    harness.fast_forward_to_timestamp_secs(start_secs + 3 * 3600 + 10);
+    assert_eq!(
+        harness.get_on_chain_time_usecs(),
+        (start_secs + 3 * 3600 + 10) * 1_000_000
+    );
+    assert_eq!(harness.current_epoch(), epoch + 6);
+}
+
+#[test]
+fn test_rotate_consensus_key_proof_of_possession() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let pool_address = *owner.address();
+    assert_success!(initialize_staking(
+        &mut harness,
+        &owner,
+        50_000_000,
+        pool_address,
+        pool_address
+    ));
+
+    let consensus_key = bls12381::PrivateKey::generate_for_testing();
+    assert_success!(rotate_consensus_key_with(
+        &mut harness,
+        &owner,
+        pool_address,
+        &consensus_key,
+        false
+    ));
+    assert_eq!(
+        get_validator_config(&harness, &pool_address).consensus_public_key,
+        consensus_key.public_key()
+    );
+
+    // A proof of possession made with another key is rejected, and the key is kept.
+    assert_aborts_with!(
+        rotate_consensus_key_with(
+            &mut harness,
+            &owner,
+            pool_address,
+            &bls12381::PrivateKey::generate_for_testing(),
+            true
+        ),
+        stake::EINVALID_PUBLIC_KEY
+    );
+    assert_eq!(
+        get_validator_config(&harness, &pool_address).consensus_public_key,
+        consensus_key.public_key()
+    );
+}
+
+#[test]
+fn test_staking_operator_handover() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let operator = fixtures::operator(&mut harness);
+    let pool_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+
+    let output = set_operator(&mut harness, &owner, *operator.address());
+    assert_success!(output);
+    assert_eq!(
+        harness.events_for::<SetOperatorEvent>(&output),
+        vec![SetOperatorEvent {
+            pool_address,
+            old_operator: pool_address,
+            new_operator: *operator.address(),
+        }]
+    );
+
+    // Only the new operator can update the addresses of the validator.
+    assert_aborts_with!(
+        update_network_and_fullnode_addresses(&mut harness, &owner, pool_address, vec![1], vec![2]),
+        stake::ENOT_OPERATOR
+    );
+    assert_success!(update_network_and_fullnode_addresses(
+        &mut harness,
+        &operator,
+        pool_address,
+        vec![1],
+        vec![2]
+    ));
+    let config = get_validator_config(&harness, &pool_address);
+    assert_eq!(config.validator_network_addresses, vec![1]);
+    assert_eq!(config.fullnode_network_addresses, vec![2]);
+
+    // The owner keeps control over voting and the lockup.
+    let voter = fixtures::voter_address();
+    assert_success!(set_delegated_voter(&mut harness, &owner, voter));
+    assert_eq!(
+        get_stake_pool(&harness, &pool_address).delegated_voter,
+        voter
+    );
+    let output = increase_lockup(&mut harness, &owner);
+    assert_success!(output);
+    let event = &harness.events_for::<IncreaseLockupEvent>(&output)[0];
+    let lockup_secs = get_staking_config(&harness).recurring_lockup_duration_secs;
+    assert_eq!(
+        event.new_locked_until_secs,
+        harness.get_block_time_secs() + lockup_secs
+    );
+    assert_eq!(
+        get_stake_pool(&harness, &pool_address).locked_until_secs,
+        event.new_locked_until_secs
+    );
+}
+
+#[test]
+fn test_staking_new_epochs_batch() {
+    let pool_state = |harness: &MoveHarness, pool_address: &AccountAddress| {
+        let stake_pool = get_stake_pool(harness, pool_address);
+        (
+            stake_pool.active,
+            stake_pool.inactive,
+            stake_pool.pending_inactive,
+            stake_pool.locked_until_secs,
+            harness.current_epoch(),
+            harness.get_on_chain_time_usecs(),
+        )
+    };
+    let (mut batched, validator) = validator_pending_inactive_with_unlocked_stake();
+    let (mut looped, _) = validator_pending_inactive_with_unlocked_stake();
+    let pool_address = *validator.address();
+
+    // A batch of epochs ends in the same state as the same number of single epochs.
+    batched.new_epochs(10);
+    for _ in 0..10 {
+        looped.new_epoch();
+    }
+    assert_eq!(
+        pool_state(&batched, &pool_address),
+        pool_state(&looped, &pool_address)
+    );
+    assert_eq!(get_stake_pool(&batched, &pool_address).pending_inactive, 0);
+}
+
+#[test]
+fn test_staking_run_epochs_with_proposals() {
+    let (mut harness, small, large) = two_validators_unequal_stake();
+    let small_address = *small.address();
+    let large_address = *large.address();
+    let set = get_validator_set(&harness);
+    let small_index = set.index_of(&small_address).unwrap();
+    let large_index = set.index_of(&large_address).unwrap();
+    let genesis_address = *harness.genesis_validators()[0].address();
+    let genesis_stake = get_stake_pool(&harness, &genesis_address).active;
+    let epoch = harness.current_epoch();
+
+    // The small validator proposes once while the large one fails, then the large validator
+    // proposes two epochs later. Nobody proposes in between, which earns no rewards.
+    let small_stake =
+        SMALL_VALIDATOR_STAKE + expected_epoch_rewards(&harness, SMALL_VALIDATOR_STAKE, 1, 1);
+    let large_stake =
+        LARGE_VALIDATOR_STAKE + expected_epoch_rewards(&harness, LARGE_VALIDATOR_STAKE, 1, 1);
+    let pools = harness.run_epochs_with_proposals(&[
+        (epoch, Some(small_index), vec![large_index]),
+        (epoch + 2, Some(large_index), vec![]),
+    ]);
+    assert_eq!(harness.current_epoch(), epoch + 3);
+    assert_eq!(pools[&small_address].active, small_stake);
+    assert_eq!(pools[&large_address].active, large_stake);
+    // The genesis validator never proposed.
+    assert_eq!(pools[&genesis_address].active, genesis_stake);
+    assert_eq!(pools.len(), 3);
+}
+
+#[test]
+fn test_staking_rewards_event() {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    let index = get_validator_config(&harness, &validator_address).validator_index as u32;
+    harness.new_block_with_metadata(Some(index), vec![]);
+    let rewards = expected_epoch_rewards(&harness, VALIDATOR_STAKE, 1, 1);
+    let output = harness.new_epoch_raw();
+    assert_event!(
+        output,
+        DistributeRewardsEvent {
+            pool_address: validator_address,
+            rewards_amount: rewards,
+        }
+    );
+}
+
+#[test]
+fn test_staking_txn_summary() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(initialize_staking(
+        &mut harness,
+        &owner,
+        50_000_000,
+        owner_address,
+        owner_address
+    ));
+
+    let balance = harness.balance::<AptosCoin>(&owner_address);
+    let summary = add_stake(&mut harness, &owner, 1_000);
+    assert_success!(summary.clone());
+    assert!(summary.gas_used() > 0);
+    assert_eq!(
+        summary.fee_charged(),
+        summary.gas_used() * summary.gas_unit_price()
+    );
+    assert_eq!(
+        harness.balance::<AptosCoin>(&owner_address),
+        balance - 1_000 - summary.fee_charged()
+    );
+    let next_summary = add_stake(&mut harness, &owner, 1_000);
+    assert_eq!(
+        next_summary.sequence_number(),
+        summary.sequence_number().map(|seq| seq + 1)
     );
+
+    let stake_pool_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        owner_address,
+        parse_struct_tag("0x1::stake::StakePool").unwrap(),
+    )));
+    assert!(summary.written_keys().contains(&stake_pool_key));
+}
+
+#[test]
+fn test_staking_view_functions() {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    assert_success!(unlock_stake(&mut harness, &validator, 10_000_000));
+
+    let stake: (u64, u64, u64, u64) = harness.execute_view_function(
+        str::parse("0x1::stake::get_stake").unwrap(),
+        vec![],
+        vec![bcs::to_bytes(&validator_address).unwrap()],
+    );
+    let stake_pool = get_stake_pool(&harness, &validator_address);
+    assert_eq!(
+        stake,
+        (
+            stake_pool.active,
+            stake_pool.inactive,
+            stake_pool.pending_active,
+            stake_pool.pending_inactive
+        )
+    );
+    assert_eq!(stake, (40_000_000, 0, 0, 10_000_000));
+
+    let balance: u64 = harness.execute_view_function(
+        str::parse("0x1::coin::balance").unwrap(),
+        vec![APTOS_COIN_TYPE.clone()],
+        vec![bcs::to_bytes(&validator_address).unwrap()],
+    );
+    assert_eq!(balance, harness.balance::<AptosCoin>(&validator_address));
+}
+
+#[test]
+fn test_staking_events() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 10_000_000));
+    let event = expect_event!(
+        harness,
+        JoinValidatorSetEvent {
+            pool_address: owner_address
+        }
+    );
+    assert_eq!(event.pool_address, owner_address);
+
+    let output = add_stake(&mut harness, &owner, 1_000);
+    assert_eq!(
+        harness.events_for::<AddStakeEvent>(&output),
+        vec![AddStakeEvent {
+            pool_address: owner_address,
+            amount_added: 1_000,
+        }]
+    );
+    assert!(harness.events_for::<UnlockStakeEvent>(&output).is_empty());
+
+    // Rewards are distributed by the block prologue of the epoch change.
+    harness.new_epoch();
+    let index = get_validator_config(&harness, &owner_address).validator_index as u32;
+    harness.new_block_with_metadata(Some(index), vec![]);
+    harness.new_epoch();
+    expect_event!(
+        harness,
+        DistributeRewardsEvent {
+            pool_address: owner_address,
+        }
+    );
+}
+
+#[test]
+fn test_staking_register_twice() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(initialize_staking(
+        &mut harness,
+        &owner,
+        50_000_000,
+        owner_address,
+        owner_address
+    ));
+    assert_aborts_with!(
+        initialize_staking(
+            &mut harness,
+            &owner,
+            50_000_000,
+            owner_address,
+            owner_address
+        ),
+        stake::EALREADY_REGISTERED
+    );
+}
+
+#[test]
+fn test_staking_abort_constant_use() {
+    let stake = ModuleId::new(CORE_CODE_ADDRESS, ident_str!("stake").to_owned());
+    // `ENOT_OPERATOR` (12) is asserted in several functions; the first assert is reported, not
+    // the declaration.
+    let constant_use = first_use_of_abort_constant(&stake, 0x10000 | 12).unwrap();
+    assert_eq!(constant_use.error_name, "ENOT_OPERATOR");
+    let source = std::fs::read_to_string(&constant_use.file).unwrap();
+    let line = source.lines().nth(constant_use.line - 1).unwrap();
+    assert!(line.contains("assert!") && line.contains("(ENOT_OPERATOR)"));
+}
+
+#[test]
+fn test_staking_add_stake_json() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(initialize_staking(
+        &mut harness,
+        &owner,
+        50_000_000,
+        owner_address,
+        owner_address
+    ));
+    assert_success!(harness.run_entry_json(
+        &owner,
+        "0x1::stake::add_stake",
+        vec![],
+        json!(["1000000"])
+    ));
+    assert_eq!(get_stake_pool(&harness, &owner_address).active, 51_000_000);
+}
+
+#[test]
+fn test_staking_verify_epilogues() {
+    let mut harness = MoveHarness::new();
+    harness.verify_epilogues();
+    let owner = fixtures::owner(&mut harness);
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+    harness.clock().advance_secs(7200).to_next_epoch().run();
+    // Withdrawing deposits the stake back into the owner's coin store.
+    assert_success!(withdraw_stake(&mut harness, &owner, 10_000_000));
+    // Aborted transactions still run the epilogue.
+    assert_abort!(withdraw_stake(&mut harness, &owner, 10_000_000), _);
+}
+
+#[test]
+fn test_staking_verify_event_determinism() {
+    let mut harness = MoveHarness::new();
+    harness.verify_event_determinism();
+    let owner = fixtures::owner(&mut harness);
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+    assert_success!(unlock_stake(&mut harness, &owner, 10_000_000));
+}
+
+#[test]
+fn test_staking_block_hooks() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    harness.new_epoch();
+
+    // Add stake before every block and record the active stake after every block.
+    let stakes = Rc::new(RefCell::new(vec![]));
+    harness.before_block(move |harness| assert_success!(add_stake(harness, &owner, 1_000)));
+    let recorded = stakes.clone();
+    harness.after_block(move |harness| {
+        recorded
+            .borrow_mut()
+            .push(get_stake_pool(harness, &owner_address).active)
+    });
+    harness.new_block_with_metadata(None, vec![]);
+    harness.new_epoch();
+    assert_eq!(*stakes.borrow(), vec![50_000_000, 50_002_000]);
+}
+
+#[test]
+fn test_staking_render_write_set() {
+    let (mut harness, owner) = single_validator_active();
+    let txn = harness.create_transaction_payload(&owner, aptos_stdlib::stake_unlock(10_000_000));
+    let output = harness.executor.execute_transaction(txn);
+    let rendered = render_write_set(harness.executor.get_state_view(), output.write_set());
+    let stake_pool = rendered
+        .lines()
+        .find(|line| line.contains("::stake::StakePool at"))
+        .unwrap();
+    assert!(stake_pool.starts_with('~'));
+    assert!(stake_pool.contains("active.value: \"50000000\" -> \"40000000\""));
+    assert!(stake_pool.contains("pending_inactive.value: \"0\" -> \"10000000\""));
+}
+
+#[test]
+#[should_panic(expected = "is already being written")]
+fn test_staking_golden_conflict() {
+    let mut harness = MoveHarness::new();
+    enable_golden!(harness);
+    let mut other = MoveHarness::new();
+    enable_golden!(other);
+}
+
+#[test]
+fn test_staking_usage_lint() {
+    let mut harness = MoveHarness::new();
+    harness.enable_usage_lint();
+    let owner = fixtures::owner(&mut harness);
+    let _operator = fixtures::operator(&mut harness);
+    assert_success!(setup_staking(&mut harness, &owner, 50_000_000));
+    let warnings = harness.usage_lint_warnings();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("created but never used"));
+    assert!(warnings[1].contains("set up but never asserted"));
+
+    get_stake_pool(&harness, owner.address());
+    assert_eq!(harness.usage_lint_warnings().len(), 1);
+}
+
+#[test]
+fn test_staking_concurrency_sweep() {
+    let runs = sweep_concurrency(&[1, 2, 8, 32], |harness| {
+        let validators: Vec<_> = (0..4)
+            .map(|i| {
+                harness.new_account_at(
+                    AccountAddress::from_hex_literal(&format!("0x{:x}", 0x100 + i)).unwrap(),
+                )
+            })
+            .collect();
+        let txns = validators
+            .iter()
+            .map(|validator| {
+                harness.create_transaction_payload(
+                    validator,
+                    aptos_stdlib::stake_initialize_owner_only(
+                        10_000_000,
+                        *validator.address(),
+                        *validator.address(),
+                    ),
+                )
+            })
+            .collect();
+        for status in harness.run_block(txns) {
+            assert_success!(status);
+        }
+    });
+    assert_eq!(runs.len(), 4);
+}
+
+#[test]
+fn test_staking_auto_epoch() {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    harness.set_auto_epoch(3600);
+
+    // Pending stake is activated by the first transaction after the epoch interval passed.
+    assert_success!(add_stake(&mut harness, &validator, 1_000));
+    harness.fast_forward_secs(3600);
+    assert_success!(add_stake(&mut harness, &validator, 1_000));
+    assert_eq!(
+        get_stake_pool(&harness, &validator_address).pending_active,
+        2_000
+    );
+
+    harness.fast_forward_secs(1);
+    assert_success!(add_stake(&mut harness, &validator, 1_000));
+    let stake_pool = get_stake_pool(&harness, &validator_address);
+    assert_eq!(stake_pool.pending_active, 0);
+    assert!(stake_pool.active >= 50_000_000 + 3_000);
+}
+
+#[test]
+fn test_staking_auto_epoch_longer_than_default() {
+    let (mut harness, _validator) = single_validator_active();
+    harness.set_auto_epoch(3 * 3600);
+
+    // `new_epoch` fast forwards by the longer interval, so the block prologue reconfigures.
+    let epoch = harness.current_epoch();
+    harness.new_epoch();
+    assert_eq!(harness.current_epoch(), epoch + 1);
+    harness.fast_forward_to_epoch(epoch + 3);
+}
+
+#[test]
+fn test_staking_lockup_time_units() {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    assert_eq!(
+        get_lockup_remaining_secs(&harness, &validator_address),
+        7200
+    );
+
+    // Sub-second advances move the block time but not the lockup, which is in whole seconds.
+    let start_usecs = harness.get_block_time_usecs();
+    harness.fast_forward_usecs(999_999);
+    assert_eq!(harness.get_block_time_usecs(), start_usecs + 999_999);
+    harness.clock().advance_usecs(1).advance_secs(99).run();
+    assert_eq!(harness.get_block_time_usecs(), start_usecs + 100_000_000);
+    assert_eq!(
+        get_lockup_remaining_secs(&harness, &validator_address),
+        7100
+    );
+
+    harness.fast_forward_secs(7100);
+    assert_eq!(get_lockup_remaining_secs(&harness, &validator_address), 0);
+}
+
+#[test]
+fn test_staking_writes() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 10_000_000));
+    harness.new_epoch();
+
+    // Unlocking only touches the stake pool, besides paying for gas.
+    assert_success!(unlock_stake(&mut harness, &owner, 1_000));
+    assert_writes!(harness, owner_address, {
+        owner_address => "0x1::stake::StakePool",
+    });
+    let pool_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        owner_address,
+        parse_struct_tag("0x1::stake::StakePool").unwrap(),
+    )));
+    assert_eq!(
+        harness.last_writes().kind(&pool_key),
+        Some(WriteKind::Modified)
+    );
+    assert!(harness.last_writes().created().is_empty());
+    assert!(harness.last_writes().deleted().is_empty());
+}
+
+#[test]
+fn test_staking_writes_delete_missing() {
+    let harness = MoveHarness::new();
+    let pool_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::from_hex_literal("0x999").unwrap(),
+        parse_struct_tag("0x1::stake::StakePool").unwrap(),
+    )));
+    let write_set = WriteSetMut::new(vec![(pool_key.clone(), WriteOp::Deletion)])
+        .freeze()
+        .unwrap();
+    let writes = WriteSetDiff::new(harness.state_view(), &write_set);
+    assert_eq!(writes.kind(&pool_key), Some(WriteKind::Deleted));
+    assert!(writes.created().is_empty());
+}
+
+#[test]
+fn test_staking_block_with_metadata() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    assert_success!(setup_staking(&mut harness, &owner, 10_000_000));
+    harness.new_epoch();
+
+    // Both unlocks land in the block proposed by the validator, in order.
+    let index = get_validator_config(&harness, &owner_address).validator_index as u32;
+    let txns = vec![
+        harness.create_transaction_payload(&owner, aptos_stdlib::stake_unlock(1_000)),
+        harness.create_transaction_payload(&owner, aptos_stdlib::stake_unlock(2_000)),
+    ];
+    let outputs = harness.run_block_with_metadata(Some(index), vec![], txns);
+    assert_eq!(outputs.len(), 2);
+    for output in outputs {
+        assert_success!(output);
+    }
+    assert_eq!(
+        get_stake_pool(&harness, &owner_address).pending_inactive,
+        3_000
+    );
+
+    // The proposal recorded by the block prologue is rewarded at the next epoch.
+    harness.new_epoch();
+    expect_event!(
+        harness,
+        DistributeRewardsEvent {
+            pool_address: owner_address,
+        }
+    );
+}
+
+#[test]
+fn test_staking_validator_performance() {
+    let (mut harness, validator) = single_validator_active();
+    let index = get_validator_config(&harness, validator.address()).validator_index as usize;
+    assert_eq!(
+        get_validator_performance(&harness)[index],
+        IndividualValidatorPerformance::default()
+    );
+
+    harness.new_block_with_metadata(Some(index as u32), vec![]);
+    harness.new_block_with_metadata(Some(index as u32), vec![]);
+    harness.new_block_with_metadata(None, vec![index as u32]);
+    assert_eq!(
+        get_validator_performance(&harness)[index],
+        IndividualValidatorPerformance {
+            successful_proposals: 2,
+            failed_proposals: 1,
+        }
+    );
+
+    // The epoch change resets the counts.
+    harness.new_epoch();
+    assert_eq!(
+        get_validator_performance(&harness)[index],
+        IndividualValidatorPerformance::default()
+    );
+}
+
+#[test]
+fn test_staking_block_timestamps() {
+    let (mut harness, validator) = single_validator_active();
+    let validator_address = *validator.address();
+    assert_success!(unlock_stake(&mut harness, &validator, 1_000));
+    let start_usecs = harness.get_block_time_usecs();
+    let secs = |seconds: u64| start_usecs + seconds * 1_000_000;
+
+    // Blocks 30 minutes apart, the second of which starts an epoch before the lockup expires.
+    harness.new_block_at_usecs(secs(1800), None, vec![]);
+    assert_eq!(harness.get_on_chain_time_usecs(), secs(1800));
+    harness.new_block_at_usecs(secs(3601), None, vec![]);
+    let stake_pool = get_stake_pool(&harness, &validator_address);
+    assert!(stake_pool.pending_inactive >= 1_000);
+    assert_eq!(stake_pool.inactive, 0);
+
+    // The lockup expires at 7200 seconds, but the unlocked stake stays pending until the next
+    // epoch, which a block one second later starts.
+    harness.new_block_at_usecs(secs(7201), None, vec![]);
+    assert_eq!(get_lockup_remaining_secs(&harness, &validator_address), 0);
+    assert!(get_stake_pool(&harness, &validator_address).pending_inactive >= 1_000);
+    harness.new_block_at_usecs(secs(7202), None, vec![]);
+    let stake_pool = get_stake_pool(&harness, &validator_address);
+    assert_eq!(stake_pool.pending_inactive, 0);
+    assert!(stake_pool.inactive >= 1_000);
+}
+
+#[test]
+fn test_staking_golden_redactions() {
+    let mut harness = MoveHarness::new();
+    let owner = fixtures::owner(&mut harness);
+    let owner_address = *owner.address();
+    let txn = harness.create_transaction_payload(
+        &owner,
+        aptos_stdlib::stake_initialize_owner_only(10_000_000, owner_address, owner_address),
+    );
+    let output = format!("{:#?}", harness.run_raw(txn));
+    assert!(output.contains(&owner_address.to_hex()));
+
+    let mut redactions = GoldenRedactions::default();
+    redactions.address(owner_address, "owner").gas_used();
+    let redacted = redactions.apply(&output, harness.get_block_time_usecs());
+    assert!(!redacted.contains(&owner_address.to_hex()));
+    assert!(redacted.contains("<owner>"));
+    assert!(redacted.contains("gas_used: <gas>"));
+}
+
+/// Returns the coins held by the account at `address`, both in its coin store and staked in its
+/// stake pool, and when the pool is locked until, if it exists.
+fn holdings(harness: &MoveHarness, address: &AccountAddress) -> (u64, Option<u64>) {
+    let balance = harness.balance::<AptosCoin>(address);
+    match harness
+        .read_resource::<StakePool>(address, parse_struct_tag("0x1::stake::StakePool").unwrap())
+    {
+        Some(pool) => (
+            balance + pool.active + pool.inactive + pool.pending_active + pool.pending_inactive,
+            Some(pool.locked_until_secs),
+        ),
+        None => (balance, None),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn test_staking_operation_sequences(operations in staking_operations(3, 10_000_000, 20)) {
+        let mut harness = MoveHarness::new();
+        let validators: Vec<_> = (0..3)
+            .map(|i| {
+                let address =
+                    AccountAddress::from_hex_literal(&format!("0x{:x}", 0x100 + i)).unwrap();
+                harness.new_account_at(address)
+            })
+            .collect();
+        let mut before: Vec<_> = validators
+            .iter()
+            .map(|validator| holdings(&harness, validator.address()))
+            .collect();
+
+        for operation in operations {
+            let summaries = operation.apply(&mut harness, &validators);
+            let fees: u64 = summaries.iter().map(|summary| summary.fee_charged()).sum();
+            for (i, validator) in validators.iter().enumerate() {
+                let address = *validator.address();
+                let rewards: u64 = summaries
+                    .iter()
+                    .flat_map(|summary| harness.events_for::<DistributeRewardsEvent>(summary))
+                    .filter(|event| event.pool_address == address)
+                    .map(|event| event.rewards_amount)
+                    .sum();
+                let fees = if operation.validator() == Some(i) { fees } else { 0 };
+                let (total, locked_until_secs) = holdings(&harness, &address);
+
+                // Coins only leave for fees and only enter as rewards.
+                prop_assert_eq!(
+                    total,
+                    before[i].0 - fees + rewards,
+                    "holdings of validator {} after {:?}",
+                    i,
+                    operation
+                );
+                // Lockups are only ever extended.
+                prop_assert!(
+                    locked_until_secs >= before[i].1,
+                    "lockup of validator {} shortened by {:?}",
+                    i,
+                    operation
+                );
+                before[i] = (total, locked_until_secs);
+            }
+        }
+    }
 }
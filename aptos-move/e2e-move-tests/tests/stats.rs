@@ -0,0 +1,39 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{assert_success, MoveHarness};
+
+#[test]
+fn execution_stats() {
+    let mut harness = MoveHarness::new();
+    let account = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let total_before = harness.total_stats();
+
+    // Transferring to a new account creates its account and coin store.
+    let receiver = AccountAddress::from_hex_literal("0x234").unwrap();
+    let summary =
+        harness.run_transaction_payload(&account, aptos_stdlib::account_transfer(receiver, 1_000));
+    assert_success!(summary);
+    let stats = harness.last_txn_stats();
+    assert_eq!(stats.transactions, 1);
+    assert_eq!(stats.gas_used, summary.gas_used());
+    assert_eq!(stats.events, summary.events().len() as u64);
+    assert!(stats.slots_created >= 2);
+    assert_eq!(stats.slots_deleted, 0);
+    assert!(stats.bytes_written > 0);
+
+    // A second transfer only modifies existing state.
+    assert_success!(
+        harness.run_transaction_payload(&account, aptos_stdlib::account_transfer(receiver, 1_000))
+    );
+    assert_eq!(harness.last_txn_stats().slots_created, 0);
+
+    let total = harness.total_stats();
+    assert_eq!(total.transactions, total_before.transactions + 2);
+    assert_eq!(
+        total.gas_used,
+        total_before.gas_used + stats.gas_used + harness.last_txn_stats().gas_used
+    );
+}
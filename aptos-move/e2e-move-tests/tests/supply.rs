@@ -0,0 +1,54 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use cached_framework_packages::aptos_stdlib;
+use e2e_move_tests::{
+    assert_success, get_validator_set, scenarios::single_validator_active, DistributeRewardsEvent,
+};
+
+#[test]
+fn supply_conserved_across_fees_and_rewards() {
+    let (mut harness, validator) = single_validator_active();
+    let pool_address = *validator.address();
+    let index = get_validator_set(&harness).index_of(&pool_address).unwrap();
+    let supply = harness.total_supply();
+    let funded = harness.total_funded();
+    let burned = harness.total_fees_burned();
+
+    // A transaction burns its fee.
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let supply_before = harness.total_supply();
+    let burned_before = harness.total_fees_burned();
+    let summary = harness.run_transaction_payload(
+        &validator,
+        aptos_stdlib::aptos_coin_transfer(*receiver.address(), 1_000),
+    );
+    assert_success!(summary);
+    assert!(summary.fee_charged() > 0);
+    assert_eq!(
+        supply_before - harness.total_supply(),
+        summary.fee_charged() as u128
+    );
+    assert_eq!(
+        harness.total_fees_burned() - burned_before,
+        summary.fee_charged() as u128
+    );
+
+    // Rewards are minted at the epoch change.
+    harness.new_block_with_metadata(Some(index), vec![]);
+    let output = harness.new_epoch_raw();
+    let rewards: u128 = harness
+        .events_for::<DistributeRewardsEvent>(&output)
+        .iter()
+        .map(|event| event.rewards_amount as u128)
+        .sum();
+    assert!(rewards > 0);
+
+    // Coins are only minted by funding and rewards, and only burned by fees.
+    assert_eq!(
+        harness.total_supply(),
+        supply + (harness.total_funded() - funded) + rewards
+            - (harness.total_fees_burned() - burned)
+    );
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_address::AccountAddress;
+use e2e_move_tests::{
+    assert_success,
+    token::{
+        claim_token, create_collection, create_token, direct_transfer, get_collection_data,
+        get_pending_claim, get_token_balance, get_token_data, mint_token, offer_token, TokenId,
+    },
+    MoveHarness,
+};
+
+#[test]
+fn token_create_mint_and_transfer() {
+    let mut harness = MoveHarness::new();
+    let creator = harness.new_account_at(AccountAddress::from_hex_literal("0x123").unwrap());
+    let owner = harness.new_account_at(AccountAddress::from_hex_literal("0x234").unwrap());
+    let id = TokenId::new(*creator.address(), "collection", "token");
+
+    assert_success!(create_collection(&mut harness, &creator, "collection", 10));
+    assert_success!(create_token(
+        &mut harness,
+        &creator,
+        "collection",
+        "token",
+        3,
+        10
+    ));
+    assert_success!(mint_token(&mut harness, &creator, "collection", "token", 2));
+    assert_eq!(
+        get_collection_data(&harness, creator.address(), "collection")
+            .unwrap()
+            .supply,
+        1
+    );
+    assert_eq!(
+        get_token_data(&harness, &id.token_data_id).unwrap().supply,
+        5
+    );
+    assert_eq!(get_token_balance(&harness, creator.address(), &id), 5);
+    assert_eq!(get_token_balance(&harness, owner.address(), &id), 0);
+
+    // A direct transfer needs both signatures.
+    assert_success!(direct_transfer(&mut harness, &creator, &owner, &id, 1));
+    assert_eq!(get_token_balance(&harness, creator.address(), &id), 4);
+    assert_eq!(get_token_balance(&harness, owner.address(), &id), 1);
+
+    // An offer holds the tokens until the receiver opts in by claiming them.
+    assert_success!(offer_token(
+        &mut harness,
+        &creator,
+        *owner.address(),
+        &id,
+        2
+    ));
+    assert_eq!(get_token_balance(&harness, creator.address(), &id), 2);
+    assert_eq!(
+        get_pending_claim(&harness, creator.address(), owner.address(), &id),
+        2
+    );
+    assert_success!(claim_token(&mut harness, &owner, *creator.address(), &id));
+    assert_eq!(
+        get_pending_claim(&harness, creator.address(), owner.address(), &id),
+        0
+    );
+    assert_eq!(get_token_balance(&harness, owner.address(), &id), 3);
+}
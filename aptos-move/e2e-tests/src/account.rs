@@ -4,7 +4,7 @@
 //! Test infrastructure for modeling Aptos accounts.
 
 use crate::gas_costs;
-use aptos_crypto::ed25519::*;
+use aptos_crypto::{ed25519::*, multi_ed25519::*, SigningKey};
 use aptos_keygen::KeyGen;
 use aptos_types::{
     access_path::AccessPath,
@@ -39,6 +39,34 @@ pub struct Account {
     pub privkey: Ed25519PrivateKey,
     /// The current public key for this account.
     pub pubkey: Ed25519PublicKey,
+    /// The keys of this account if it is a multi-ed25519 account, which are used instead of
+    /// `privkey` and `pubkey`.
+    multisig: Option<MultisigKeys>,
+}
+
+/// The keys of a multi-ed25519 account.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultisigKeys {
+    pub private_keys: Vec<Ed25519PrivateKey>,
+    pub public_key: MultiEd25519PublicKey,
+    /// The number of keys which need to sign a transaction.
+    pub threshold: u8,
+    /// The indices of the keys which sign transactions, by default the first `threshold`.
+    pub signers: Vec<u8>,
+}
+
+impl MultisigKeys {
+    /// Signs the transaction with the keys of `signers`.
+    fn sign(&self, raw_txn: RawTransaction) -> SignedTransaction {
+        let signatures = self
+            .signers
+            .iter()
+            .map(|index| (self.private_keys[*index as usize].sign(&raw_txn), *index))
+            .collect();
+        let signature =
+            MultiEd25519Signature::new(signatures).expect("signers must be distinct key indices");
+        SignedTransaction::new_multisig(raw_txn, self.public_key.clone(), signature)
+    }
 }
 
 impl Account {
@@ -70,6 +98,36 @@ impl Account {
             addr,
             privkey,
             pubkey,
+            multisig: None,
+        }
+    }
+
+    /// Creates a new multi-ed25519 account at the given address, with `num_keys` keys generated
+    /// from `seed`, `threshold` of which need to sign transactions.
+    ///
+    /// Like with [`Account::new`], the account returned by this constructor is a purely logical
+    /// entity.
+    pub fn new_multisig_at(
+        addr: AccountAddress,
+        seed: &mut KeyGen,
+        threshold: u8,
+        num_keys: u8,
+    ) -> Self {
+        let (private_keys, public_keys): (Vec<_>, Vec<_>) = (0..num_keys)
+            .map(|_| seed.generate_ed25519_keypair())
+            .unzip();
+        let public_key = MultiEd25519PublicKey::new(public_keys.clone(), threshold)
+            .expect("threshold must not exceed the number of keys");
+        Account {
+            addr,
+            privkey: private_keys[0].clone(),
+            pubkey: public_keys[0].clone(),
+            multisig: Some(MultisigKeys {
+                private_keys,
+                public_key,
+                threshold,
+                signers: (0..threshold).collect(),
+            }),
         }
     }
 
@@ -86,6 +144,7 @@ impl Account {
             addr,
             privkey,
             pubkey,
+            multisig: None,
         }
     }
 
@@ -98,6 +157,7 @@ impl Account {
             addr: address,
             pubkey: GENESIS_KEYPAIR.1.clone(),
             privkey: GENESIS_KEYPAIR.0.clone(),
+            multisig: None,
         }
     }
 
@@ -136,17 +196,46 @@ impl Account {
         AccessPath::resource_access_path(resource_tag)
     }
 
-    /// Changes the keys for this account to the provided ones.
+    /// Changes the keys for this account to the provided ones. This turns a multi-ed25519
+    /// account into a single key account.
     pub fn rotate_key(&mut self, privkey: Ed25519PrivateKey, pubkey: Ed25519PublicKey) {
         self.privkey = privkey;
         self.pubkey = pubkey;
+        self.multisig = None;
+    }
+
+    /// Returns this multi-ed25519 account signing transactions with the keys at the given
+    /// indices, e.g. fewer than the threshold, or keys other than the first ones.
+    pub fn with_multisig_signers(&self, signers: &[u8]) -> Account {
+        let mut account = self.clone();
+        let multisig = account
+            .multisig
+            .as_mut()
+            .expect("account must be a multi-ed25519 account");
+        assert!(
+            signers
+                .iter()
+                .all(|index| (*index as usize) < multisig.private_keys.len()),
+            "signers must be indices of the {} keys",
+            multisig.private_keys.len()
+        );
+        multisig.signers = signers.to_vec();
+        account
+    }
+
+    /// Returns the keys of this account if it is a multi-ed25519 account.
+    pub fn multisig(&self) -> Option<&MultisigKeys> {
+        self.multisig.as_ref()
     }
 
     /// Computes the authentication key for this account, as stored on the chain.
     ///
     /// This is the same as the account's address if the keys have never been rotated.
     pub fn auth_key(&self) -> Vec<u8> {
-        AuthenticationKey::ed25519(&self.pubkey).to_vec()
+        match &self.multisig {
+            Some(multisig) => AuthenticationKey::multi_ed25519(&multisig.public_key).to_vec(),
+            None => AuthenticationKey::ed25519(&self.pubkey).to_vec(),
+        }
     }
 
     pub fn transaction(&self) -> TransactionBuilder {
@@ -253,7 +342,7 @@ impl TransactionBuilder {
     }
 
     pub fn sign(self) -> SignedTransaction {
-        RawTransaction::new(
+        let raw_txn = RawTransaction::new(
             *self.sender.address(),
             self.sequence_number.expect("sequence number not set"),
             self.program.expect("transaction payload not set"),
@@ -261,10 +350,14 @@ impl TransactionBuilder {
             self.gas_unit_price.unwrap_or(0),
             self.ttl.unwrap_or(DEFAULT_EXPIRATION_TIME),
             self.chain_id.unwrap_or_else(ChainId::test),
-        )
-        .sign(&self.sender.privkey, self.sender.pubkey)
-        .unwrap()
-        .into_inner()
+        );
+        match &self.sender.multisig {
+            Some(multisig) => multisig.sign(raw_txn),
+            None => raw_txn
+                .sign(&self.sender.privkey, self.sender.pubkey)
+                .unwrap()
+                .into_inner(),
+        }
     }
 
     pub fn sign_multi_agent(self) -> SignedTransaction {
@@ -418,7 +511,7 @@ impl AccountData {
     pub fn to_bytes(&self) -> Vec<u8> {
         let account = AccountResource::new(
             self.sequence_number,
-            AuthenticationKey::ed25519(&self.account.pubkey).to_vec(),
+            self.account.auth_key(),
             self.coin_register_events.clone(),
         );
         bcs::to_bytes(&account).unwrap()
@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use vm_genesis::{
     generate_genesis_change_set_for_mainnet, generate_genesis_change_set_for_testing,
-    GenesisOptions,
+    generate_genesis_change_set_for_testnet, GenesisOptions,
 };
 
 /// Dummy genesis ChangeSet for testing
@@ -31,6 +31,9 @@ pub static GENESIS_CHANGE_SET_FRESH: Lazy<ChangeSet> =
 pub static GENESIS_CHANGE_SET_MAINNET: Lazy<ChangeSet> =
     Lazy::new(|| generate_genesis_change_set_for_mainnet(GenesisOptions::Fresh));
 
+pub static GENESIS_CHANGE_SET_TESTNET: Lazy<ChangeSet> =
+    Lazy::new(|| generate_genesis_change_set_for_testnet(GenesisOptions::Fresh));
+
 /// An in-memory implementation of [`StateView`] and [`RemoteCache`] for the VM.
 ///
 /// Tests use this to set up state, and pass in a reference to the cache whenever a `StateView` or
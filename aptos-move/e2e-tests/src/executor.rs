@@ -5,17 +5,18 @@
 
 use serde::Serialize;
 use std::{
+    cell::Cell,
     env,
     fs::{self, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
 };
 
-use crate::data_store::GENESIS_CHANGE_SET_MAINNET;
+use crate::data_store::{GENESIS_CHANGE_SET_MAINNET, GENESIS_CHANGE_SET_TESTNET};
 use crate::{
     account::{Account, AccountData},
     data_store::{FakeDataStore, GENESIS_CHANGE_SET, GENESIS_CHANGE_SET_FRESH},
-    golden_outputs::GoldenOutputs,
+    golden_outputs::{GoldenOutputs, GoldenRedactions},
 };
 use aptos_bitvec::BitVec;
 use aptos_crypto::HashValue;
@@ -28,6 +29,7 @@ use aptos_types::{
         new_block_event_key, AccountResource, CoinStoreResource, NewBlockEvent, CORE_CODE_ADDRESS,
     },
     block_metadata::BlockMetadata,
+    chain_id::ChainId,
     on_chain_config::{OnChainConfig, ValidatorSet, Version},
     state_store::state_key::StateKey,
     transaction::{
@@ -40,7 +42,7 @@ use aptos_types::{
 use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
     move_vm_ext::{MoveVmExt, SessionId},
-    parallel_executor::ParallelAptosVM,
+    parallel_executor::{ParallelAptosVM, SchedulerStats},
     AptosVM, VMExecutor, VMValidator,
 };
 use move_deps::{
@@ -77,9 +79,12 @@ pub struct FakeExecutor {
     data_store: FakeDataStore,
     block_time: u64,
     executed_output: Option<GoldenOutputs>,
+    golden_redactions: GoldenRedactions,
     trace_dir: Option<PathBuf>,
     rng: KeyGen,
     no_parallel_exec: bool,
+    concurrency_level: usize,
+    last_parallel_stats: Cell<Option<SchedulerStats>>,
 }
 
 impl FakeExecutor {
@@ -89,9 +94,12 @@ impl FakeExecutor {
             data_store: FakeDataStore::default(),
             block_time: 0,
             executed_output: None,
+            golden_redactions: GoldenRedactions::default(),
             trace_dir: None,
             rng: KeyGen::from_seed(RNG_SEED),
             no_parallel_exec: false,
+            concurrency_level: num_cpus::get(),
+            last_parallel_stats: Cell::new(None),
         };
         executor.apply_write_set(write_set);
         executor
@@ -103,6 +111,23 @@ impl FakeExecutor {
         self
     }
 
+    /// Configure the number of threads used for parallel execution. Defaults to the number of
+    /// CPUs.
+    pub fn set_concurrency_level(mut self, concurrency_level: usize) -> Self {
+        self.concurrency_level = concurrency_level;
+        self
+    }
+
+    /// Returns the number of threads used for parallel execution.
+    pub fn concurrency_level(&self) -> usize {
+        self.concurrency_level
+    }
+
+    /// Returns the scheduler counts of the last block executed in parallel, if any.
+    pub fn last_parallel_stats(&self) -> Option<SchedulerStats> {
+        self.last_parallel_stats.get()
+    }
+
     /// Create an executor from a saved genesis blob
     pub fn from_saved_genesis(saved_genesis_blob: &[u8]) -> Self {
         let change_set = bcs::from_bytes::<ChangeSet>(saved_genesis_blob).unwrap();
@@ -124,15 +149,61 @@ impl FakeExecutor {
         Self::from_genesis(GENESIS_CHANGE_SET_MAINNET.clone().write_set())
     }
 
+    /// Creates an executor using the mainnet genesis, but with the given validators instead of
+    /// the single standard validator.
+    pub fn from_mainnet_genesis_with_validators(validators: &[vm_genesis::Validator]) -> Self {
+        let change_set = vm_genesis::generate_genesis_change_set_for_mainnet_with_validators(
+            vm_genesis::GenesisOptions::Fresh,
+            validators,
+        );
+        Self::from_genesis(change_set.write_set())
+    }
+
+    /// Creates an executor using the testnet genesis.
+    pub fn from_testnet_genesis() -> Self {
+        Self::from_genesis(GENESIS_CHANGE_SET_TESTNET.clone().write_set())
+    }
+
+    /// Creates an executor using the standard genesis, but with the given validators instead of
+    /// the single standard validator.
+    pub fn from_fresh_genesis_with_validators(validators: &[vm_genesis::Validator]) -> Self {
+        let change_set = vm_genesis::generate_genesis_change_set_for_testing_with_validators(
+            vm_genesis::GenesisOptions::Fresh,
+            validators,
+        );
+        Self::from_genesis(change_set.write_set())
+    }
+
+    /// Creates an executor using a genesis with the given validators, configuration and chain ID,
+    /// which also publishes the `extra_modules` under 0x1 alongside the framework.
+    pub fn from_genesis_with_configuration(
+        validators: &[vm_genesis::Validator],
+        genesis_config: &vm_genesis::GenesisConfiguration,
+        chain_id: ChainId,
+        extra_modules: &[Vec<u8>],
+    ) -> Self {
+        let change_set = vm_genesis::generate_genesis_change_set_with_configuration(
+            vm_genesis::GenesisOptions::Fresh,
+            validators,
+            genesis_config,
+            chain_id,
+            extra_modules,
+        );
+        Self::from_genesis(change_set.write_set())
+    }
+
     /// Creates an executor in which no genesis state has been applied yet.
     pub fn no_genesis() -> Self {
         FakeExecutor {
             data_store: FakeDataStore::default(),
             block_time: 0,
             executed_output: None,
+            golden_redactions: GoldenRedactions::default(),
             trace_dir: None,
             rng: KeyGen::from_seed(RNG_SEED),
             no_parallel_exec: false,
+            concurrency_level: num_cpus::get(),
+            last_parallel_stats: Cell::new(None),
         }
     }
 
@@ -152,6 +223,11 @@ impl FakeExecutor {
         self.set_tracing(test_name, file_name)
     }
 
+    /// Returns the redactions applied to the output before it is recorded in the golden file.
+    pub fn golden_redactions(&mut self) -> &mut GoldenRedactions {
+        &mut self.golden_redactions
+    }
+
     fn set_tracing(&mut self, test_name: &str, file_name: String) {
         // NOTE: tracing is only available when
         //  - the e2e test outputs a golden file, and
@@ -337,8 +413,12 @@ impl FakeExecutor {
         &self,
         txn_block: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
-        let (result, _) =
-            ParallelAptosVM::execute_block(txn_block, &self.data_store, num_cpus::get())?;
+        let (result, _, stats) = ParallelAptosVM::execute_block_with_stats(
+            txn_block,
+            &self.data_store,
+            self.concurrency_level,
+        )?;
+        self.last_parallel_stats.set(Some(stats));
 
         Ok(result)
     }
@@ -363,11 +443,12 @@ impl FakeExecutor {
         let output = AptosVM::execute_block(txn_block.clone(), &self.data_store);
         if !self.no_parallel_exec {
             let parallel_output = self.execute_transaction_block_parallel(txn_block);
-            assert_eq!(output, parallel_output);
+            assert_parallel_equivalent(&output, &parallel_output);
         }
 
         if let Some(logger) = &self.executed_output {
-            logger.log(format!("{:#?}\n", output).as_str());
+            let output = format!("{:#?}\n", output);
+            logger.log(&self.golden_redactions.apply(&output, self.block_time));
         }
 
         // dump serialized transaction output after execution, if tracing
@@ -435,32 +516,23 @@ impl FakeExecutor {
         &self.data_store
     }
 
-    pub fn new_block(&mut self) {
-        self.new_block_with_timestamp(self.block_time + 1);
+    pub fn new_block(&mut self) -> TransactionOutput {
+        self.new_block_with_timestamp(self.block_time + 1)
     }
 
-    pub fn new_block_with_timestamp(&mut self, time_microseconds: u64) {
+    pub fn new_block_with_timestamp(&mut self, time_microseconds: u64) -> TransactionOutput {
         self.block_time = time_microseconds;
         self.new_block_with_metadata(None, vec![])
     }
 
+    /// Executes a block prologue with the given metadata and applies its write set. Returns the
+    /// output of the block prologue.
     pub fn new_block_with_metadata(
         &mut self,
         proposer_index: Option<u32>,
         failed_proposer_indices: Vec<u32>,
-    ) {
-        let validator_set = ValidatorSet::fetch_config(&self.data_store.as_move_resolver())
-            .expect("Unable to retrieve the validator set from storage");
-        let new_block = BlockMetadata::new(
-            HashValue::zero(),
-            0,
-            0,
-            *validator_set.payload().next().unwrap().account_address(),
-            proposer_index,
-            BitVec::with_num_bits(validator_set.num_validators() as u16).into(),
-            failed_proposer_indices,
-            self.block_time,
-        );
+    ) -> TransactionOutput {
+        let new_block = self.block_metadata(proposer_index, failed_proposer_indices);
         let output = self
             .execute_transaction_block(vec![Transaction::BlockMetadata(new_block)])
             .expect("Executing block prologue should succeed")
@@ -471,6 +543,40 @@ impl FakeExecutor {
         assert_eq!(event.key(), &new_block_event_key());
         assert!(bcs::from_bytes::<NewBlockEvent>(event.event_data()).is_ok());
         self.apply_write_set(output.write_set());
+        output
+    }
+
+    /// Creates the metadata of a block at the current block time, i.e. the input of its block
+    /// prologue.
+    pub fn block_metadata(
+        &self,
+        proposer_index: Option<u32>,
+        failed_proposer_indices: Vec<u32>,
+    ) -> BlockMetadata {
+        let validator_set = ValidatorSet::fetch_config(&self.data_store.as_move_resolver())
+            .expect("Unable to retrieve the validator set from storage");
+        BlockMetadata::new(
+            HashValue::zero(),
+            0,
+            0,
+            *validator_set.payload().next().unwrap().account_address(),
+            proposer_index,
+            BitVec::with_num_bits(validator_set.num_validators() as u16).into(),
+            failed_proposer_indices,
+            self.block_time,
+        )
+    }
+
+    /// Executes a block prologue followed by the user transactions as a single block, without
+    /// applying the outputs. The output of the prologue comes first.
+    pub fn execute_block_with_metadata(
+        &self,
+        metadata: BlockMetadata,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let mut block = vec![Transaction::BlockMetadata(metadata)];
+        block.extend(txn_block.into_iter().map(Transaction::UserTransaction));
+        self.execute_transaction_block(block)
     }
 
     fn module(name: &str) -> ModuleId {
@@ -485,11 +591,11 @@ impl FakeExecutor {
         self.block_time = new_block_time;
     }
 
-    pub fn get_block_time(&mut self) -> u64 {
+    pub fn get_block_time(&self) -> u64 {
         self.block_time
     }
 
-    pub fn get_block_time_seconds(&mut self) -> u64 {
+    pub fn get_block_time_seconds(&self) -> u64 {
         self.block_time / 1_000_000
     }
 
@@ -558,4 +664,77 @@ impl FakeExecutor {
             .into_inner();
         Ok(writeset)
     }
+
+    /// Executes the function `function_name` of module `module_id` against the current state,
+    /// bypassing visibility, and returns its BCS-serialized return values. The effects of the
+    /// function are discarded.
+    pub fn execute_view_function(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        type_params: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
+        // TODO(Gas): we probably want to switch to non-zero costs in the future
+        let vm = MoveVmExt::new(NativeGasParameters::zeros()).unwrap();
+        let remote_view = RemoteStorage::new(&self.data_store);
+        let mut session = vm.new_session(&remote_view, SessionId::void());
+        let return_values = session
+            .execute_function_bypass_visibility(
+                module_id,
+                function_name,
+                type_params,
+                args,
+                &mut UnmeteredGasMeter,
+            )
+            .map_err(|e| e.into_vm_status())?
+            .return_values;
+        Ok(return_values
+            .into_iter()
+            .map(|(bytes, _layout)| bytes)
+            .collect())
+    }
+}
+
+/// Asserts that the sequential and parallel execution of a block agree. On divergence, points
+/// out the first transaction and the part of its output which differ, instead of dumping both
+/// blocks.
+fn assert_parallel_equivalent(
+    sequential: &Result<Vec<TransactionOutput>, VMStatus>,
+    parallel: &Result<Vec<TransactionOutput>, VMStatus>,
+) {
+    if let (Ok(sequential), Ok(parallel)) = (sequential, parallel) {
+        assert_eq!(
+            sequential.len(),
+            parallel.len(),
+            "parallel execution returned a different number of outputs"
+        );
+        for (index, (seq, par)) in sequential.iter().zip(parallel).enumerate() {
+            assert_eq!(
+                seq.status(),
+                par.status(),
+                "status of transaction {} differs under parallel execution",
+                index
+            );
+            assert_eq!(
+                seq.write_set(),
+                par.write_set(),
+                "write set of transaction {} differs under parallel execution",
+                index
+            );
+            assert_eq!(
+                seq.events(),
+                par.events(),
+                "events of transaction {} differ under parallel execution",
+                index
+            );
+            assert_eq!(
+                seq.gas_used(),
+                par.gas_used(),
+                "gas used by transaction {} differs under parallel execution",
+                index
+            );
+        }
+    }
+    assert_eq!(sequential, parallel);
 }
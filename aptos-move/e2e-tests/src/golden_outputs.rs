@@ -1,15 +1,146 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use goldenfile::Mint;
-use move_deps::move_command_line_common::testing::EXP_EXT;
-use std::{cell::RefCell, fmt::Debug, fs::File, io::Write, path::PathBuf};
+use aptos_types::account_address::AccountAddress;
+use move_deps::move_command_line_common::testing::{format_diff, EXP_EXT};
+use once_cell::sync::Lazy;
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::Debug,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
 
 pub const GOLDEN_DIR_PATH: &str = "goldens";
 
+/// If set, golden files are updated instead of checked.
+const UPDATE_ENV_VAR: &str = "UPDATE_GOLDENFILES";
+
+/// The golden files currently written, used to detect two tests (or two harnesses within the
+/// same test) writing the same golden file in parallel.
+static GOLDEN_FILES_IN_USE: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+/// Makes the names of temporary files unique within the process.
+static NEXT_TEMP_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// The placeholder of redacted gas.
+const REDACTED_GAS: &str = "<gas>";
+
+/// The placeholder of the redacted block time.
+const REDACTED_BLOCK_TIME: &str = "<block_time>";
+
+/// Normalizes the output of a test before it is recorded in its golden file, so the golden file
+/// only changes when the output changes meaningfully, and not when e.g. an account address or
+/// the gas used changes incidentally. Values are shown as their BCS bytes in hex, so numbers are
+/// redacted in that form.
+#[derive(Clone, Debug, Default)]
+pub struct GoldenRedactions {
+    replacements: Vec<(String, String)>,
+    gas_used: bool,
+    block_time: bool,
+}
+
+impl GoldenRedactions {
+    /// Replaces every occurrence of `text` by `placeholder`.
+    pub fn text(&mut self, text: impl Into<String>, placeholder: impl Into<String>) -> &mut Self {
+        self.replacements.push((text.into(), placeholder.into()));
+        self
+    }
+
+    /// Replaces the address, also where it is part of a key or value, by `<name>`.
+    pub fn address(&mut self, address: AccountAddress, name: &str) -> &mut Self {
+        self.text(address.to_hex(), format!("<{}>", name))
+    }
+
+    /// Replaces the BCS bytes of `value`, e.g. a timestamp stored in a resource, by `<name>`.
+    pub fn u64(&mut self, value: u64, name: &str) -> &mut Self {
+        self.text(hex::encode(value.to_le_bytes()), format!("<{}>", name))
+    }
+
+    /// Replaces the gas used by every transaction, which is better checked within a tolerance by
+    /// the gas golden file.
+    pub fn gas_used(&mut self) -> &mut Self {
+        self.gas_used = true;
+        self
+    }
+
+    /// Replaces the block time, in microseconds, at which a transaction is executed.
+    pub fn block_time(&mut self) -> &mut Self {
+        self.block_time = true;
+        self
+    }
+
+    /// Applies the redactions to the output of transactions executed at `block_time`.
+    pub fn apply(&self, output: &str, block_time: u64) -> String {
+        let mut output = output.to_string();
+        if self.block_time {
+            output = output.replace(&hex::encode(block_time.to_le_bytes()), REDACTED_BLOCK_TIME);
+        }
+        for (text, placeholder) in &self.replacements {
+            output = output.replace(text, placeholder);
+        }
+        if self.gas_used {
+            output = redact_numbers_after(&output, "gas_used: ", REDACTED_GAS);
+        }
+        output
+    }
+}
+
+/// Replaces the number following every occurrence of `prefix` by `placeholder`.
+fn redact_numbers_after(output: &str, prefix: &str, placeholder: &str) -> String {
+    let mut result = String::with_capacity(output.len());
+    let mut rest = output;
+    while let Some(index) = rest.find(prefix) {
+        let (before, after) = rest.split_at(index + prefix.len());
+        result.push_str(before);
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            result.push_str(placeholder);
+        }
+        rest = &after[digits..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Returns a path for a temporary file next to `golden_path`, unique across processes and
+/// threads, so it can be renamed to the golden file atomically.
+fn temp_path_for(golden_path: &Path) -> PathBuf {
+    let extension = golden_path
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    golden_path.with_extension(format!(
+        "{}.{}.{}.tmp",
+        extension,
+        std::process::id(),
+        NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Replaces the golden file at `path` with `contents` atomically, so tests reading it in
+/// parallel, e.g. in another test binary, never see a partially written file.
+pub fn write_golden_file(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let temp_path = temp_path_for(path);
+    fs::write(&temp_path, contents).unwrap();
+    fs::rename(&temp_path, path).unwrap();
+}
+
+/// Records the output of a test into a temporary file. When dropped, the output is checked
+/// against the golden file or, if `UPDATE_GOLDENFILES` is set, atomically replaces it. This is
+/// safe under `cargo test` parallelism, as long as each golden file is written by a single
+/// test at a time, which is asserted. A mismatch only panics once the golden file is released,
+/// so the diff is computed from files no other test writes.
 pub(crate) struct GoldenOutputs {
-    #[allow(dead_code)]
-    mint: Mint,
+    golden_path: PathBuf,
+    temp_path: PathBuf,
     file: RefCell<File>,
 }
 
@@ -25,19 +156,76 @@ impl GoldenOutputs {
     }
 
     pub fn new_at_path(path: PathBuf, name: &str) -> Self {
-        let mut mint = Mint::new(path);
-        let mut file_path = PathBuf::new();
-        file_path.push(name);
-        let file = RefCell::new(
-            mint.new_goldenfile(file_path.with_extension(EXP_EXT))
-                .unwrap(),
+        let golden_path = path.join(PathBuf::from(name).with_extension(EXP_EXT));
+        assert!(
+            GOLDEN_FILES_IN_USE
+                .lock()
+                .unwrap()
+                .insert(golden_path.clone()),
+            "golden file {} is already being written by another test or harness",
+            golden_path.display()
         );
-        Self { mint, file }
+        let temp_path = temp_path_for(&golden_path);
+        fs::create_dir_all(&path).unwrap();
+        let file = RefCell::new(File::create(&temp_path).unwrap());
+        Self {
+            golden_path,
+            temp_path,
+            file,
+        }
     }
 
     pub fn log(&self, msg: &str) {
         self.file.borrow_mut().write_all(msg.as_bytes()).unwrap();
     }
+
+    /// Checks the recorded output against the golden file, or replaces the golden file with it.
+    /// Returns the error to panic with on a mismatch.
+    fn finish(&self) -> Result<(), String> {
+        self.file.borrow_mut().flush().unwrap();
+        if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+            fs::rename(&self.temp_path, &self.golden_path).unwrap();
+            return Ok(());
+        }
+        let actual = fs::read_to_string(&self.temp_path).unwrap();
+        fs::remove_file(&self.temp_path).unwrap();
+        let expected = fs::read_to_string(&self.golden_path).map_err(|_| {
+            format!(
+                "golden file {} is missing, run with {}=1 to create it",
+                self.golden_path.display(),
+                UPDATE_ENV_VAR
+            )
+        })?;
+        if expected != actual {
+            return Err(format!(
+                "output differs from golden file {}, run with {}=1 to update it:\n{}",
+                self.golden_path.display(),
+                UPDATE_ENV_VAR,
+                format_diff(expected, actual)
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for GoldenOutputs {
+    fn drop(&mut self) {
+        let result = if std::thread::panicking() {
+            // The output of a failing test is incomplete, so neither check nor keep it.
+            let _ = fs::remove_file(&self.temp_path);
+            Ok(())
+        } else {
+            self.finish()
+        };
+        // Released before panicking, so a mismatch does not keep the golden file in use.
+        GOLDEN_FILES_IN_USE
+            .lock()
+            .unwrap()
+            .remove(&self.golden_path);
+        if let Err(message) = result {
+            panic!("{}", message);
+        }
+    }
 }
 
 impl Debug for GoldenOutputs {
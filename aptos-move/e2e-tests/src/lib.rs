@@ -17,7 +17,7 @@ pub mod data_store;
 pub mod execution_strategies;
 pub mod executor;
 pub mod gas_costs;
-mod golden_outputs;
+pub mod golden_outputs;
 pub mod on_chain_configs;
 mod proptest_types;
 pub mod utils;
@@ -3,7 +3,7 @@
 
 use crate::{
     errors::*,
-    scheduler::{Scheduler, SchedulerTask, TaskGuard, TxnIndex, Version},
+    scheduler::{Scheduler, SchedulerStats, SchedulerTask, TaskGuard, TxnIndex, Version},
     task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
     txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
 };
@@ -291,8 +291,22 @@ where
         executor_initial_arguments: E::Argument,
         signature_verified_block: Vec<T>,
     ) -> Result<Vec<E::Output>, E::Error> {
+        self.execute_transactions_parallel_with_stats(
+            executor_initial_arguments,
+            signature_verified_block,
+        )
+        .0
+    }
+
+    /// Like `execute_transactions_parallel`, but also returns the counts of the work done by the
+    /// scheduler, e.g. to observe aborts and re-executions on contended blocks.
+    pub fn execute_transactions_parallel_with_stats(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+    ) -> (Result<Vec<E::Output>, E::Error>, SchedulerStats) {
         if signature_verified_block.is_empty() {
-            return Ok(vec![]);
+            return (Ok(vec![]), SchedulerStats::default());
         }
 
         let num_txns = signature_verified_block.len();
@@ -337,6 +351,7 @@ where
             }
             ret
         };
+        let stats = scheduler.stats();
 
         spawn(move || {
             // Explicit async drops.
@@ -346,12 +361,13 @@ where
             drop(scheduler);
         });
 
-        match maybe_err {
+        let result = match maybe_err {
             Some(err) => Err(err),
             None => {
                 final_results.resize_with(num_txns, E::Output::skip_output);
                 Ok(final_results)
             }
-        }
+        };
+        (result, stats)
     }
 }
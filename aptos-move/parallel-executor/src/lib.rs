@@ -144,3 +144,5 @@ pub mod task;
 mod txn_last_input_output;
 #[cfg(test)]
 mod unit_tests;
+
+pub use scheduler::SchedulerStats;
@@ -135,6 +135,30 @@ pub struct Scheduler {
     txn_dependency: Vec<CachePadded<Mutex<Vec<TxnIndex>>>>,
     /// An index i maps to the most up-to-date status of transaction i.
     txn_status: Vec<CachePadded<Mutex<TransactionStatus>>>,
+
+    /// The number of incarnations created, aborted and suspended on a dependency, respectively.
+    /// They are only read once all workers are done, so they don't synchronize anything.
+    num_incarnations: AtomicUsize,
+    num_aborts: AtomicUsize,
+    num_dependency_waits: AtomicUsize,
+}
+
+/// Counts of the work done by the scheduler while executing a block. Every incarnation beyond
+/// the first of each transaction is a re-execution, caused by an abort. Resuming an execution
+/// suspended on a dependency continues the same incarnation, so it is not counted again.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SchedulerStats {
+    pub num_txns: usize,
+    pub num_incarnations: usize,
+    pub num_aborts: usize,
+    pub num_dependency_waits: usize,
+}
+
+impl SchedulerStats {
+    /// Returns the number of executions beyond the first one of each transaction.
+    pub fn num_reexecutions(&self) -> usize {
+        self.num_incarnations.saturating_sub(self.num_txns)
+    }
 }
 
 /// Public Interfaces for the Scheduler
@@ -153,6 +177,9 @@ impl Scheduler {
             txn_status: (0..num_txns)
                 .map(|_| CachePadded::new(Mutex::new(TransactionStatus::ReadyToExecute(0, None))))
                 .collect(),
+            num_incarnations: AtomicUsize::new(0),
+            num_aborts: AtomicUsize::new(0),
+            num_dependency_waits: AtomicUsize::new(0),
         }
     }
 
@@ -161,6 +188,16 @@ impl Scheduler {
         self.num_txns
     }
 
+    /// Return the counts of the work done so far.
+    pub fn stats(&self) -> SchedulerStats {
+        SchedulerStats {
+            num_txns: self.num_txns,
+            num_incarnations: self.num_incarnations.load(Ordering::Relaxed),
+            num_aborts: self.num_aborts.load(Ordering::Relaxed),
+            num_dependency_waits: self.num_dependency_waits.load(Ordering::Relaxed),
+        }
+    }
+
     /// Try to abort version = (txn_idx, incarnation), called upon validation failure.
     /// When the invocation manages to update the status of the transaction, it changes
     /// Executed(incarnation) => Aborting(incarnation), it returns true. Otherwise,
@@ -172,6 +209,7 @@ impl Scheduler {
 
         if *status == TransactionStatus::Executed(incarnation) {
             *status = TransactionStatus::Aborting(incarnation);
+            self.num_aborts.fetch_add(1, Ordering::Relaxed);
             true
         } else {
             false
@@ -240,6 +278,7 @@ impl Scheduler {
             stored_deps.push(txn_idx);
         }
 
+        self.num_dependency_waits.fetch_add(1, Ordering::Relaxed);
         Some(dep_condvar)
     }
 
@@ -362,6 +401,10 @@ impl Scheduler {
         if let TransactionStatus::ReadyToExecute(incarnation, maybe_condvar) = &*status {
             let ret = (*incarnation, maybe_condvar.clone());
             *status = TransactionStatus::Executing(*incarnation);
+            // Resuming a suspended execution continues the same incarnation.
+            if ret.1.is_none() {
+                self.num_incarnations.fetch_add(1, Ordering::Relaxed);
+            }
             Some(ret)
         } else {
             None
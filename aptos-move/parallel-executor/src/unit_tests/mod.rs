@@ -4,7 +4,7 @@
 use crate::{
     executor::ParallelTransactionExecutor,
     proptest_types::types::{ExpectedOutput, KeyType, Task, Transaction},
-    scheduler::{Scheduler, SchedulerTask, TaskGuard},
+    scheduler::{Scheduler, SchedulerStats, SchedulerTask, TaskGuard},
     task::ModulePath,
 };
 use rand::random;
@@ -383,6 +383,89 @@ fn scheduler_incarnation() {
     assert!(matches!(s.next_task(), SchedulerTask::Done));
 }
 
+#[test]
+fn scheduler_stats() {
+    let s = Scheduler::new(3);
+    let fake_counter = AtomicUsize::new(0);
+
+    for i in 0..3 {
+        assert!(matches!(
+            s.next_task(),
+            SchedulerTask::ExecutionTask((j, 0), None, _) if j == i
+        ));
+    }
+    assert!(s.wait_for_dependency(2, 1).is_some());
+    assert!(matches!(
+        s.finish_execution(0, 0, false, TaskGuard::new(&fake_counter)),
+        SchedulerTask::NoTask
+    ));
+    assert!(matches!(
+        s.finish_execution(1, 0, false, TaskGuard::new(&fake_counter)),
+        SchedulerTask::NoTask
+    ));
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ValidationTask((0, 0), _)
+    ));
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ValidationTask((1, 0), _)
+    ));
+    // resumed task doesn't count as an incarnation
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask((2, 0), Some(_), _)
+    ));
+    assert!(matches!(
+        s.finish_execution(2, 0, false, TaskGuard::new(&fake_counter)),
+        SchedulerTask::NoTask
+    ));
+    assert!(matches!(
+        s.next_task(),
+        SchedulerTask::ValidationTask((2, 0), _)
+    ));
+    assert!(s.try_abort(2, 0));
+    assert!(matches!(
+        s.finish_abort(2, 0, TaskGuard::new(&fake_counter)),
+        SchedulerTask::ExecutionTask((2, 1), None, _)
+    ));
+
+    let stats = s.stats();
+    assert_eq!(
+        stats,
+        SchedulerStats {
+            num_txns: 3,
+            num_incarnations: 4,
+            num_aborts: 1,
+            num_dependency_waits: 1,
+        }
+    );
+    assert_eq!(stats.num_reexecutions(), 1);
+}
+
+#[test]
+fn scheduler_stats_conflicting_block() {
+    // All transactions read and write the same key, so later ones are aborted or wait.
+    let key = KeyType(random::<[u8; 32]>(), false);
+    let transactions: Vec<_> = (0..TXN_PER_BLOCK)
+        .map(|_| Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![vec![key]],
+            writes: vec![vec![(key, random::<u64>())]],
+        })
+        .collect();
+    let (output, stats) = ParallelTransactionExecutor::<
+        Transaction<KeyType<[u8; 32]>, u64>,
+        Task<KeyType<[u8; 32]>, u64>,
+    >::new(num_cpus::get())
+    .execute_transactions_parallel_with_stats((), transactions.clone());
+    assert!(ExpectedOutput::generate_baseline(&transactions).check_output(&output));
+
+    assert_eq!(stats.num_txns, TXN_PER_BLOCK as usize);
+    // Every abort creates exactly one new incarnation of the aborted transaction.
+    assert_eq!(stats.num_reexecutions(), stats.num_aborts);
+}
+
 #[test]
 fn scheduler_stop_idx() {
     let s = Scheduler::new(3);
@@ -52,6 +52,7 @@ const NUM_SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
 const MICRO_SECONDS_PER_SECOND: u64 = 1_000_000;
 const APTOS_COINS_BASE_WITH_DECIMALS: u64 = u64::pow(10, 8);
 
+#[derive(Clone, Debug)]
 pub struct GenesisConfiguration {
     pub allow_new_validators: bool,
     pub epoch_duration_secs: u64,
@@ -476,6 +477,20 @@ pub fn generate_genesis_change_set_for_testing(genesis_options: GenesisOptions)
     generate_test_genesis(&modules, Some(1)).0
 }
 
+/// Generate an artificial genesis `ChangeSet` for testing, with the given validators instead of a
+/// single generated one
+pub fn generate_genesis_change_set_for_testing_with_validators(
+    genesis_options: GenesisOptions,
+    validators: &[Validator],
+) -> ChangeSet {
+    let modules = match genesis_options {
+        GenesisOptions::Compiled => cached_framework_packages::module_blobs().to_vec(),
+        GenesisOptions::Fresh => framework::aptos::module_blobs(),
+    };
+
+    encode_test_genesis_change_set(&modules, validators)
+}
+
 /// Generate a genesis `ChangeSet` for mainnet
 pub fn generate_genesis_change_set_for_mainnet(genesis_options: GenesisOptions) -> ChangeSet {
     let modules = match genesis_options {
@@ -486,6 +501,56 @@ pub fn generate_genesis_change_set_for_mainnet(genesis_options: GenesisOptions)
     generate_mainnet_genesis(&modules, Some(1)).0
 }
 
+/// Generate a genesis `ChangeSet` for mainnet, with the given validators instead of a single
+/// generated one
+pub fn generate_genesis_change_set_for_mainnet_with_validators(
+    genesis_options: GenesisOptions,
+    validators: &[Validator],
+) -> ChangeSet {
+    let modules = match genesis_options {
+        GenesisOptions::Compiled => cached_framework_packages::module_blobs().to_vec(),
+        GenesisOptions::Fresh => framework::aptos::module_blobs(),
+    };
+
+    encode_mainnet_genesis_change_set(&modules, validators)
+}
+
+/// Generate a genesis `ChangeSet` with the given validators, configuration and chain ID, e.g. a
+/// variation of `test_genesis_configuration`. The `extra_modules`, which must be under the framework
+/// address 0x1, are published together with the framework.
+pub fn generate_genesis_change_set_with_configuration(
+    genesis_options: GenesisOptions,
+    validators: &[Validator],
+    genesis_config: &GenesisConfiguration,
+    chain_id: ChainId,
+    extra_modules: &[Vec<u8>],
+) -> ChangeSet {
+    let mut modules = match genesis_options {
+        GenesisOptions::Compiled => cached_framework_packages::module_blobs().to_vec(),
+        GenesisOptions::Fresh => framework::aptos::module_blobs(),
+    };
+    modules.extend_from_slice(extra_modules);
+
+    encode_genesis_change_set(
+        &GENESIS_KEYPAIR.1,
+        validators,
+        &modules,
+        OnChainConsensusConfig::default(),
+        chain_id,
+        genesis_config,
+    )
+}
+
+/// Generate a genesis `ChangeSet` for testnet
+pub fn generate_genesis_change_set_for_testnet(genesis_options: GenesisOptions) -> ChangeSet {
+    let modules = match genesis_options {
+        GenesisOptions::Compiled => cached_framework_packages::module_blobs().to_vec(),
+        GenesisOptions::Fresh => framework::aptos::module_blobs(),
+    };
+
+    generate_testnet_genesis(&modules, Some(1)).0
+}
+
 pub fn test_genesis_transaction() -> Transaction {
     let changeset = test_genesis_change_set_and_validators(None).0;
     Transaction::GenesisTransaction(WriteSetPayload::Direct(changeset))
@@ -572,31 +637,41 @@ pub fn generate_test_genesis(
     count: Option<usize>,
 ) -> (ChangeSet, Vec<TestValidator>) {
     let test_validators = TestValidator::new_test_set(count, Some(100_000_000));
-    let validators_: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
-    let validators = &validators_;
+    let validators: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
+    let genesis = encode_test_genesis_change_set(stdlib_modules, &validators);
+    (genesis, test_validators)
+}
 
-    let genesis = encode_genesis_change_set(
+fn encode_test_genesis_change_set(
+    stdlib_modules: &[Vec<u8>],
+    validators: &[Validator],
+) -> ChangeSet {
+    encode_genesis_change_set(
         &GENESIS_KEYPAIR.1,
         validators,
         stdlib_modules,
         OnChainConsensusConfig::default(),
         ChainId::test(),
-        &GenesisConfiguration {
-            allow_new_validators: true,
-            epoch_duration_secs: 3600,
-            is_test: true,
-            min_stake: 0,
-            min_voting_threshold: 0,
-            // 1M APTOS coins (with 8 decimals).
-            max_stake: 100_000_000_000_000,
-            recurring_lockup_duration_secs: 7200,
-            required_proposer_stake: 0,
-            rewards_apy_percentage: 10,
-            voting_duration_secs: 3600,
-            voting_power_increase_limit: 50,
-        },
-    );
-    (genesis, test_validators)
+        &test_genesis_configuration(),
+    )
+}
+
+/// The configuration of the genesis generated for testing
+pub fn test_genesis_configuration() -> GenesisConfiguration {
+    GenesisConfiguration {
+        allow_new_validators: true,
+        epoch_duration_secs: 3600,
+        is_test: true,
+        min_stake: 0,
+        min_voting_threshold: 0,
+        // 1M APTOS coins (with 8 decimals).
+        max_stake: 100_000_000_000_000,
+        recurring_lockup_duration_secs: 7200,
+        required_proposer_stake: 0,
+        rewards_apy_percentage: 10,
+        voting_duration_secs: 3600,
+        voting_power_increase_limit: 50,
+    }
 }
 
 pub fn generate_mainnet_genesis(
@@ -604,6 +679,49 @@ pub fn generate_mainnet_genesis(
     count: Option<usize>,
 ) -> (ChangeSet, Vec<TestValidator>) {
     // TODO: Update to have custom validators/accounts with initial balances at genesis.
+    let test_validators = TestValidator::new_test_set(count, Some(1_000_000_000_000_000));
+    let validators: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
+    let genesis = encode_mainnet_genesis_change_set(stdlib_modules, &validators);
+    (genesis, test_validators)
+}
+
+fn encode_mainnet_genesis_change_set(
+    stdlib_modules: &[Vec<u8>],
+    validators: &[Validator],
+) -> ChangeSet {
+    encode_genesis_change_set(
+        &GENESIS_KEYPAIR.1,
+        validators,
+        stdlib_modules,
+        OnChainConsensusConfig::default(),
+        ChainId::test(),
+        &mainnet_genesis_configuration(),
+    )
+}
+
+/// The configuration of the genesis generated for mainnet
+pub fn mainnet_genesis_configuration() -> GenesisConfiguration {
+    // TODO: Update once mainnet numbers are decided. These numbers are just placeholders.
+    GenesisConfiguration {
+        allow_new_validators: true,
+        epoch_duration_secs: 2 * 3600, // 2 hours
+        is_test: false,
+        min_stake: 1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1M APT
+        // 400M APT
+        min_voting_threshold: (400_000_000 * APTOS_COINS_BASE_WITH_DECIMALS as u128),
+        max_stake: 50_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 50M APT.
+        recurring_lockup_duration_secs: 30 * 24 * 3600,         // 1 month
+        required_proposer_stake: 1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1M APT
+        rewards_apy_percentage: 10,
+        voting_duration_secs: 7 * 24 * 3600, // 7 days
+        voting_power_increase_limit: 30,
+    }
+}
+
+pub fn generate_testnet_genesis(
+    stdlib_modules: &[Vec<u8>],
+    count: Option<usize>,
+) -> (ChangeSet, Vec<TestValidator>) {
     let test_validators = TestValidator::new_test_set(count, Some(1_000_000_000_000_000));
     let validators_: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
     let validators = &validators_;
@@ -614,20 +732,19 @@ pub fn generate_mainnet_genesis(
         stdlib_modules,
         OnChainConsensusConfig::default(),
         ChainId::test(),
-        // TODO: Update once mainnet numbers are decided. These numbers are just placeholders.
+        // Should be kept in sync with the defaults of the testnet genesis helm chart.
         &GenesisConfiguration {
-            allow_new_validators: true,
+            allow_new_validators: false,
             epoch_duration_secs: 2 * 3600, // 2 hours
-            is_test: false,
+            is_test: true,
             min_stake: 1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1M APT
-            // 400M APT
-            min_voting_threshold: (400_000_000 * APTOS_COINS_BASE_WITH_DECIMALS as u128),
-            max_stake: 50_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 50M APT.
-            recurring_lockup_duration_secs: 30 * 24 * 3600,         // 1 month
+            min_voting_threshold: (1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS as u128), // 1M APT
+            max_stake: 1_000_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1B APT
+            recurring_lockup_duration_secs: 24 * 3600,             // 1 day
             required_proposer_stake: 1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1M APT
             rewards_apy_percentage: 10,
-            voting_duration_secs: 7 * 24 * 3600, // 7 days
-            voting_power_increase_limit: 30,
+            voting_duration_secs: 12 * 3600, // 12 hours
+            voting_power_increase_limit: 20,
         },
     );
     (genesis, test_validators)
@@ -4,6 +4,7 @@
 use crate::common::types::{CliError, MovePackageDir};
 use crate::CliTypedResult;
 use framework::natives::code::{ModuleMetadata, PackageMetadata, UpgradePolicy};
+use move_deps::move_compiler::compiled_unit::CompiledUnit;
 use move_deps::move_package::compilation::compiled_package::CompiledPackage;
 use move_deps::move_package::BuildConfig;
 
@@ -51,6 +52,16 @@ impl BuiltPackage {
             .collect()
     }
 
+    /// Extracts the bytecode of the scripts from the built package.
+    pub fn extract_script_code(&self) -> Vec<Vec<u8>> {
+        self.package
+            .root_compiled_units
+            .iter()
+            .filter(|unit_with_source| matches!(unit_with_source.unit, CompiledUnit::Script(_)))
+            .map(|unit_with_source| unit_with_source.unit.serialize(None))
+            .collect()
+    }
+
     /// Extracts metadata, as needed for publishing a package, from the built package.
     pub fn extract_metadata(
         &self,
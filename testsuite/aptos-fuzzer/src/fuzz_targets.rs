@@ -10,6 +10,7 @@ use std::{collections::BTreeMap, env};
 mod consensus;
 mod executor;
 mod mempool;
+mod move_harness;
 mod move_vm;
 mod network;
 mod proof;
@@ -33,6 +34,8 @@ static ALL_TARGETS: Lazy<BTreeMap<&'static str, Box<dyn FuzzTargetImpl>>> = Lazy
         Box::new(mempool::MempoolIncomingTransactions::default()),
         // Move VM
         Box::new(move_vm::ValueTarget::default()),
+        // Move harness
+        Box::new(move_harness::MoveHarnessEntryFunction::default()),
         // Proof
         Box::new(proof::TestAccumulatorProofFuzzer::default()),
         Box::new(proof::SparseMerkleProofFuzzer::default()),
@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{corpus_from_strategy, fuzz_data_to_value, FuzzTargetImpl};
+use aptos_proptest_helpers::ValueGenerator;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{ScriptFunction, TransactionPayload},
+    utility_coin::APTOS_COIN_TYPE,
+};
+use e2e_move_tests::MoveHarness;
+use move_deps::move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use proptest::{collection::vec, prelude::*, sample::select};
+
+/// Entry functions of the framework, so that most inputs get past function resolution and
+/// exercise argument validation.
+const ENTRY_FUNCTIONS: &[(&str, &str)] = &[
+    ("account", "create_account"),
+    ("account", "rotate_authentication_key"),
+    ("account", "transfer"),
+    ("aptos_coin", "mint"),
+    ("aptos_governance", "create_proposal"),
+    ("aptos_governance", "vote"),
+    ("code", "publish_package_txn"),
+    ("coin", "transfer"),
+    ("coins", "register"),
+    ("managed_coin", "initialize"),
+    ("managed_coin", "register"),
+    ("resource_account", "create_resource_account"),
+    ("stake", "add_stake"),
+    ("stake", "increase_lockup"),
+    ("stake", "initialize_owner_only"),
+    ("stake", "join_validator_set"),
+    ("stake", "leave_validator_set"),
+    ("stake", "rotate_consensus_key"),
+    ("stake", "set_operator"),
+    ("stake", "unlock"),
+    ("stake", "withdraw"),
+];
+
+#[derive(Clone, Debug, Default)]
+pub struct MoveHarnessEntryFunction;
+
+impl FuzzTargetImpl for MoveHarnessEntryFunction {
+    fn description(&self) -> &'static str {
+        "MoveHarness runs an entry function with arbitrary type arguments and BCS arguments"
+    }
+
+    fn generate(&self, _idx: usize, _gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+        Some(corpus_from_strategy(entry_function_call()))
+    }
+
+    fn fuzz(&self, data: &[u8]) {
+        let (module, function, ty_args, args) = fuzz_data_to_value(data, entry_function_call());
+        let (module, function) = match (Identifier::new(module), Identifier::new(function)) {
+            (Ok(module), Ok(function)) => (module, function),
+            // Invalid identifiers cannot be part of a transaction.
+            _ => return,
+        };
+        let mut harness = MoveHarness::new_for_fuzzing();
+        let account = harness.new_account_at(AccountAddress::from_hex_literal("0xf00").unwrap());
+        // Failing transactions are fine -- the fuzzer cares about panics in the VM and framework.
+        let _ = harness.run_transaction_payload(
+            &account,
+            TransactionPayload::ScriptFunction(ScriptFunction::new(
+                ModuleId::new(AccountAddress::ONE, module),
+                function,
+                ty_args,
+                args,
+            )),
+        );
+    }
+}
+
+prop_compose! {
+    fn entry_function_call()(
+        (module, function) in prop_oneof![
+            select(ENTRY_FUNCTIONS)
+                .prop_map(|(module, function)| (module.to_string(), function.to_string())),
+            ("[a-z_]{1,16}", "[a-z_]{1,16}"),
+        ],
+        ty_args in vec(type_arg(), 0..3),
+        args in vec(vec(any::<u8>(), 0..64), 0..6),
+    ) -> (String, String, Vec<TypeTag>, Vec<Vec<u8>>) {
+        (module, function, ty_args, args)
+    }
+}
+
+fn type_arg() -> impl Strategy<Value = TypeTag> {
+    prop_oneof![
+        Just(TypeTag::Bool),
+        Just(TypeTag::U8),
+        Just(TypeTag::U64),
+        Just(TypeTag::U128),
+        Just(TypeTag::Address),
+        Just(TypeTag::Signer),
+        Just(APTOS_COIN_TYPE.clone()),
+        Just(TypeTag::Vector(Box::new(TypeTag::U8))),
+    ]
+}